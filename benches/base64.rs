@@ -0,0 +1,44 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use impl_ssl_tls::base64;
+
+/// Input sizes: a small record, a TLS record and a certificate bundle.
+const SIZES: [usize; 3] = [64, 16 * 1024, 1024 * 1024];
+
+fn sample_bytes(size: usize) -> Vec<u8> {
+    (0..size).map(|i| { (i * 31 + 7) as u8 }).collect()
+}
+
+fn bench_encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("base64_encode");
+    for size in SIZES {
+        let input = sample_bytes(size);
+        let mut output = vec![0; base64::encoded_len(size)];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("encode", size), &input, |b, input| {
+            b.iter(|| { base64::encode(input) })
+        });
+        group.bench_with_input(BenchmarkId::new("encode_to_slice", size), &input, |b, input| {
+            b.iter(|| { base64::encode_to_slice(input, &mut output).unwrap() })
+        });
+    }
+    group.finish();
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("base64_decode");
+    for size in SIZES {
+        let input = base64::encode(sample_bytes(size));
+        let mut output = vec![0; base64::decoded_len_estimate(input.len())];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("decode", size), &input, |b, input| {
+            b.iter(|| { base64::decode(input).unwrap() })
+        });
+        group.bench_with_input(BenchmarkId::new("decode_to_slice", size), &input, |b, input| {
+            b.iter(|| { base64::decode_to_slice(input.as_bytes(), &mut output).unwrap() })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_encode, bench_decode);
+criterion_main!(benches);
@@ -0,0 +1,55 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use impl_ssl_tls::bigint::BigUint;
+
+/// Operand sizes of RSA and DH moduli in bits.
+const BITS: [usize; 2] = [2048, 4096];
+
+/// Odd operand of the size with pseudo-random bits, e.g. for a modulus.
+fn sample_operand(bits: usize, seed: u8) -> BigUint {
+    let mut bytes: Vec<u8> = (0..bits / 8)
+        .map(|i| { (i as u8).wrapping_mul(73).wrapping_add(seed) })
+        .collect();
+    bytes[0] |= 0x80;
+    *bytes.last_mut().unwrap() |= 1;
+    BigUint::from_bytes_be(&bytes)
+}
+
+fn bench_arithmetic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bigint_arithmetic");
+    for bits in BITS {
+        let a = sample_operand(bits, 1);
+        let b = sample_operand(bits, 2);
+        let product = &a * &b;
+        group.bench_with_input(BenchmarkId::new("mul", bits), &bits, |bench, _| {
+            bench.iter(|| { &a * &b })
+        });
+        group.bench_with_input(BenchmarkId::new("div_rem", bits), &bits, |bench, _| {
+            bench.iter(|| { product.div_rem(&a) })
+        });
+        group.bench_with_input(BenchmarkId::new("mod_inverse", bits), &bits, |bench, _| {
+            bench.iter(|| { b.mod_inverse(&a) })
+        });
+    }
+    group.finish();
+}
+
+fn bench_modpow(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bigint_modpow");
+    group.sample_size(10);
+    for bits in BITS {
+        let modulus = sample_operand(bits, 3);
+        let base = &sample_operand(bits, 4) % &modulus;
+        let public_exponent = BigUint::from(65537);
+        let private_exponent = sample_operand(bits, 5) >> 1;
+        group.bench_with_input(BenchmarkId::new("public_exponent", bits), &bits, |bench, _| {
+            bench.iter(|| { base.modpow(&public_exponent, &modulus) })
+        });
+        group.bench_with_input(BenchmarkId::new("private_exponent", bits), &bits, |bench, _| {
+            bench.iter(|| { base.modpow(&private_exponent, &modulus) })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_arithmetic, bench_modpow);
+criterion_main!(benches);
@@ -0,0 +1,22 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use impl_ssl_tls::pk::rsa::RsaPrivateKey;
+use impl_ssl_tls::rng::ChaChaRng;
+
+/// Modulus sizes of the generated keys in bits.
+const BITS: [usize; 2] = [2048, 3072];
+
+fn bench_generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rsa_generate");
+    // the time of a prime search varies a lot with the gap found
+    group.sample_size(10);
+    for bits in BITS {
+        let mut rng = ChaChaRng::from_seed(&[(bits / 1024) as u8; 32]);
+        group.bench_with_input(BenchmarkId::new("e65537", bits), &bits, |bench, bits| {
+            bench.iter(|| { RsaPrivateKey::generate(*bits, 65537, &mut rng).unwrap() })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_generate);
+criterion_main!(benches);
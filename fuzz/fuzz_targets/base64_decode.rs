@@ -0,0 +1,23 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use impl_ssl_tls::base64;
+
+fuzz_target!(|data: &[u8]| {
+    // decoding must never panic, and accepted input must be canonical
+    let engines = [base64::STANDARD, base64::URL_SAFE, base64::URL_SAFE_NO_PAD];
+    for engine in engines {
+        if let Ok(decoded) = engine.decode(data) {
+            assert_eq!(engine.encode(&decoded).as_bytes(), data);
+        }
+    }
+
+    // all decoders of standard BASE64 must agree
+    let expected = base64::decode(data);
+    assert_eq!(base64::ct::decode(data), expected);
+
+    let mut output = vec![0; base64::decoded_len_estimate(data.len())];
+    let sliced = base64::decode_to_slice(data, &mut output)
+        .map(|written| { output[..written].to_vec() });
+    assert_eq!(sliced, expected);
+});
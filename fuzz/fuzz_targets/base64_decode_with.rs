@@ -0,0 +1,23 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use impl_ssl_tls::base64::{self, DecodeOptions};
+
+fuzz_target!(|data: &[u8]| {
+    // the first byte selects the options
+    let (flags, input) = match data.split_first() {
+        Some((flags, input)) => (*flags, input),
+        None => return,
+    };
+    let options = DecodeOptions {
+        ignore_whitespace: flags & 0b001 != 0,
+        allow_missing_padding: flags & 0b010 != 0,
+        allow_trailing_bits: flags & 0b100 != 0,
+    };
+
+    // lenient decoding must never panic, and must accept what strict one does
+    let lenient = base64::decode_with(input, options);
+    if let Ok(strict) = base64::decode(input) {
+        assert_eq!(lenient, Ok(strict));
+    }
+});
@@ -0,0 +1,43 @@
+#!/bin/sh
+# Download the upstream test vectors which the ignored tests read:
+#
+#     res/fetch-vectors.sh && cargo test -- --ignored
+#
+# The vectors under res/generated are generated locally in the same formats,
+# and don't replace these.
+set -eu
+
+cd "$(dirname "$0")"
+CAVP=https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Algorithm-Validation-Program/documents
+
+tmp=$(mktemp -d)
+trap 'rm -rf "$tmp"' EXIT
+
+# SHAVS byte oriented vectors of SHA-2
+mkdir -p cavp/sha2
+curl -fsSL -o "$tmp/sha.zip" "$CAVP/shs/shabytetestvectors.zip"
+unzip -j -o -q "$tmp/sha.zip" '*SHA224*Msg.rsp' '*SHA256*Msg.rsp' '*SHA384*Msg.rsp' '*SHA512*Msg.rsp' -d cavp/sha2
+
+# ECC CDH primitive vectors, and the public key validation of the 186-3
+# ECDSA vectors
+mkdir -p cavp/ecc
+curl -fsSL -o "$tmp/ecccdh.zip" "$CAVP/components/ecccdhtestvectors.zip"
+unzip -j -o -q "$tmp/ecccdh.zip" '*KAS_ECC_CDH_PrimitiveTest.txt' -d cavp/ecc
+curl -fsSL -o "$tmp/ecdsa.zip" "$CAVP/dss/186-3ecdsatestvectors.zip"
+unzip -j -o -q "$tmp/ecdsa.zip" '*PKV.rsp' -d cavp/ecc
+
+# Wycheproof vectors of RSA
+WYCHEPROOF=https://raw.githubusercontent.com/C2SP/wycheproof/main/testvectors_v1
+mkdir -p wycheproof
+for name in \
+    rsa_pkcs1_2048_test \
+    rsa_oaep_2048_sha256_mgf1sha256_test \
+    rsa_pss_2048_sha1_mgf1_20_test \
+    rsa_pss_2048_sha256_mgf1_0_test \
+    rsa_pss_2048_sha256_mgf1_32_test \
+    rsa_signature_2048_sha256_test \
+    rsa_signature_2048_sha384_test \
+    rsa_signature_2048_sha512_test
+do
+    curl -fsSL -o "wycheproof/$name.json" "$WYCHEPROOF/$name.json"
+done
@@ -0,0 +1,113 @@
+//! Authenticated encryption with associated data (AEAD) for TLS record
+//! protection.
+
+use core::fmt;
+use alloc::vec::Vec;
+use crate::ct;
+
+mod chacha20poly1305;
+mod gcm;
+
+pub use chacha20poly1305::ChaCha20Poly1305;
+pub use gcm::AesGcm;
+
+/// Tag size in bytes of the AEADs of TLS.
+pub const TAG_SIZE: usize = 16;
+
+/// Size in bytes of the per-record nonce of TLS.
+pub const RECORD_NONCE_SIZE: usize = 12;
+
+/// Get the nonce of a record from the IV of the connection and the sequence
+/// number, padded on the left to the IV length and XORed with the IV.
+///
+/// This is the nonce of every AEAD of TLS 1.3 (RFC 8446 5.3) and of
+/// ChaCha20-Poly1305 of TLS 1.2 (RFC 7905 2).
+pub fn record_nonce(iv: &[u8; RECORD_NONCE_SIZE], sequence_number: u64) -> [u8; RECORD_NONCE_SIZE] {
+    let mut nonce = *iv;
+    let offset = RECORD_NONCE_SIZE - 8;
+    nonce[offset..].iter_mut().zip(sequence_number.to_be_bytes().iter()).for_each(|(byte, n)| { *byte ^= n });
+    nonce
+}
+
+/// Authenticated encryption with associated data.
+///
+/// Opening verifies the tag before decrypting anything, so no plaintext of a
+/// forged ciphertext is released.
+pub trait Aead {
+    /// Nonce size in bytes used by TLS.
+    const NONCE_SIZE: usize;
+
+    /// Encrypt the data in place and get the tag.
+    fn seal_detached(&self, nonce: &[u8], aad: &[u8], data: &mut [u8]) -> Result<[u8; TAG_SIZE], AeadError>;
+
+    /// Verify the tag and decrypt the data in place.
+    /// The data is left as it is if the tag doesn't match.
+    fn open_detached(&self, nonce: &[u8], aad: &[u8], data: &mut [u8], tag: &[u8]) -> Result<(), AeadError>;
+
+    /// Encrypt the plaintext and get the ciphertext followed by the tag.
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, AeadError> {
+        let mut output = Vec::with_capacity(plaintext.len() + TAG_SIZE);
+        output.extend_from_slice(plaintext);
+        let tag = self.seal_detached(nonce, aad, &mut output)?;
+        output.extend_from_slice(&tag);
+        Ok(output)
+    }
+
+    /// Verify and decrypt the ciphertext followed by the tag.
+    fn open(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, AeadError> {
+        if ciphertext.len() < TAG_SIZE {
+            return Err(AeadError::InvalidLength(ciphertext.len()));
+        }
+        let (data, tag) = ciphertext.split_at(ciphertext.len() - TAG_SIZE);
+        let mut output = data.to_vec();
+        self.open_detached(nonce, aad, &mut output, tag)?;
+        Ok(output)
+    }
+}
+
+/// Errors of AEADs.
+#[derive(Debug, PartialEq)]
+pub enum AeadError {
+    /// The nonce has the length which the AEAD doesn't support.
+    InvalidNonceLength(usize),
+    /// The data is too long, or the ciphertext is shorter than the tag.
+    InvalidLength(usize),
+    /// The tag doesn't match, i.e. the ciphertext or the associated data is
+    /// forged or corrupted.
+    InvalidTag,
+}
+
+impl fmt::Display for AeadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AeadError::InvalidNonceLength(len) => write!(f, "invalid nonce length {}", len),
+            AeadError::InvalidLength(len) => write!(f, "invalid data length {}", len),
+            AeadError::InvalidTag => write!(f, "authentication tag mismatch"),
+        }
+    }
+}
+
+impl core::error::Error for AeadError {}
+
+/// Compare the tags in constant time.
+fn verify_tag(expected: &[u8; TAG_SIZE], tag: &[u8]) -> Result<(), AeadError> {
+    if ct::eq(expected, tag) {
+        Ok(())
+    } else {
+        Err(AeadError::InvalidTag)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_record_nonce() {
+        let iv = [0x55; RECORD_NONCE_SIZE];
+        assert_eq!(record_nonce(&iv, 0), iv);
+        assert_eq!(record_nonce(&iv, 0x0102), [0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x54, 0x57]);
+        assert_eq!(record_nonce(&iv, u64::MAX)[..4], [0x55; 4]);
+        assert_eq!(record_nonce(&iv, u64::MAX)[4..], [0xAA; 8]);
+    }
+}
@@ -0,0 +1,132 @@
+//! ChaCha20-Poly1305 (RFC 8439) for `TLS_*_WITH_CHACHA20_POLY1305_SHA256`
+//! cipher suites of TLS 1.2 (RFC 7905) and TLS 1.3.
+
+use core::convert::TryInto;
+use crate::cipher::KeyError;
+use crate::cipher::chacha20::{self, ChaCha20};
+use crate::mac::Poly1305;
+use super::{Aead, AeadError, TAG_SIZE, verify_tag};
+
+/// Maximum length of the plaintext in bytes, i.e. `2 ^ 32 - 1` blocks of
+/// the key stream.
+const MAX_DATA_LEN: u64 = (1 << 38) - 64;
+
+/// ChaCha20-Poly1305 with the 96-bit nonce.
+#[derive(Clone)]
+pub struct ChaCha20Poly1305 {
+    key: [u8; chacha20::KEY_SIZE],
+}
+
+impl ChaCha20Poly1305 {
+    /// Create with the key of 32 bytes.
+    pub fn new(key: &[u8]) -> Result<ChaCha20Poly1305, KeyError> {
+        let key = key.try_into().map_err(|_| { KeyError::InvalidLength(key.len()) })?;
+        Ok(ChaCha20Poly1305 { key })
+    }
+
+    /// Get the cipher with the key stream from the block 1 and the Poly1305
+    /// key from the block 0.
+    fn init(&self, nonce: &[u8], data: &[u8]) -> Result<(ChaCha20, Poly1305), AeadError> {
+        let nonce = nonce.try_into().map_err(|_| { AeadError::InvalidNonceLength(nonce.len()) })?;
+        if data.len() as u64 > MAX_DATA_LEN {
+            return Err(AeadError::InvalidLength(data.len()));
+        }
+        let mut chacha20 = ChaCha20::new(&self.key, nonce, 0);
+        let mut block = [0; chacha20::BLOCK_SIZE];
+        chacha20.apply_key_stream(&mut block);
+        let poly1305 = Poly1305::new(block[..32].try_into().unwrap());
+        Ok((chacha20, poly1305))
+    }
+}
+
+impl Aead for ChaCha20Poly1305 {
+    const NONCE_SIZE: usize = chacha20::NONCE_SIZE;
+
+    fn seal_detached(&self, nonce: &[u8], aad: &[u8], data: &mut [u8]) -> Result<[u8; TAG_SIZE], AeadError> {
+        let (mut chacha20, poly1305) = self.init(nonce, data)?;
+        chacha20.apply_key_stream(data);
+        Ok(compute_tag(poly1305, aad, data))
+    }
+
+    fn open_detached(&self, nonce: &[u8], aad: &[u8], data: &mut [u8], tag: &[u8]) -> Result<(), AeadError> {
+        let (mut chacha20, poly1305) = self.init(nonce, data)?;
+        verify_tag(&compute_tag(poly1305, aad, data), tag)?;
+        chacha20.apply_key_stream(data);
+        Ok(())
+    }
+}
+
+fn compute_tag(mut poly1305: Poly1305, aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_SIZE] {
+    const ZEROS: [u8; 16] = [0; 16];
+    poly1305.update(aad);
+    poly1305.update(&ZEROS[..(16 - aad.len() % 16) % 16]);
+    poly1305.update(ciphertext);
+    poly1305.update(&ZEROS[..(16 - ciphertext.len() % 16) % 16]);
+    poly1305.update(&(aad.len() as u64).to_le_bytes());
+    poly1305.update(&(ciphertext.len() as u64).to_le_bytes());
+    poly1305.finalize()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::hex;
+
+    // RFC 8439 2.8.2
+    const KEY: &str = "808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f";
+    const NONCE: &str = "070000004041424344454647";
+    const AAD: &str = "50515253c0c1c2c3c4c5c6c7";
+    const PLAINTEXT: &[u8] = b"Ladies and Gentlemen of the class of '99: \
+        If I could offer you only one tip for the future, sunscreen would be it.";
+    const CIPHERTEXT: &str = concat!(
+        "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6",
+        "3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36",
+        "92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc",
+        "3ff4def08e4b7a9de576d26586cec64b6116",
+    );
+    const TAG: &str = "1ae10b594f09e26a7e902ecbd0600691";
+
+    #[test]
+    fn test_rfc8439() {
+        let aead = ChaCha20Poly1305::new(&hex(KEY)).unwrap();
+        let mut data = PLAINTEXT.to_vec();
+        let tag = aead.seal_detached(&hex(NONCE), &hex(AAD), &mut data).unwrap();
+        assert_eq!(data, hex(CIPHERTEXT));
+        assert_eq!(tag[..], hex(TAG)[..]);
+
+        aead.open_detached(&hex(NONCE), &hex(AAD), &mut data, &tag).unwrap();
+        assert_eq!(data, PLAINTEXT);
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let aead = ChaCha20Poly1305::new(&hex(KEY)).unwrap();
+        let sealed = aead.seal(&hex(NONCE), &hex(AAD), PLAINTEXT).unwrap();
+        assert_eq!(sealed, [hex(CIPHERTEXT), hex(TAG)].concat());
+        assert_eq!(aead.open(&hex(NONCE), &hex(AAD), &sealed).unwrap(), PLAINTEXT);
+    }
+
+    #[test]
+    fn test_open_forged() {
+        let aead = ChaCha20Poly1305::new(&hex(KEY)).unwrap();
+        let mut sealed = aead.seal(&hex(NONCE), &hex(AAD), PLAINTEXT).unwrap();
+        assert_eq!(aead.open(&hex(NONCE), b"", &sealed), Err(AeadError::InvalidTag));
+        let mut nonce = hex(NONCE);
+        nonce[11] ^= 1;
+        assert_eq!(aead.open(&nonce, &hex(AAD), &sealed), Err(AeadError::InvalidTag));
+
+        // the data stays encrypted if the tag doesn't match
+        let (data, tag) = sealed.split_at_mut(PLAINTEXT.len());
+        tag[0] ^= 1;
+        let result = aead.open_detached(&hex(NONCE), &hex(AAD), data, tag);
+        assert_eq!(result, Err(AeadError::InvalidTag));
+        assert_eq!(data, &hex(CIPHERTEXT)[..]);
+    }
+
+    #[test]
+    fn test_invalid_lengths() {
+        assert_eq!(ChaCha20Poly1305::new(&[0; 16]).err(), Some(KeyError::InvalidLength(16)));
+        let aead = ChaCha20Poly1305::new(&hex(KEY)).unwrap();
+        assert_eq!(aead.seal(&[0; 8], b"", b"data"), Err(AeadError::InvalidNonceLength(8)));
+    }
+}
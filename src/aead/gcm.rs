@@ -0,0 +1,269 @@
+//! AES-GCM (SP 800-38D) for `TLS_*_WITH_AES_*_GCM_*` cipher suites.
+
+use core::convert::TryInto;
+use crate::cipher::{Aes, BlockCipher, KeyError};
+use super::{Aead, AeadError, TAG_SIZE, verify_tag};
+
+mod ghash;
+
+use ghash::Ghash;
+
+/// Maximum length of the plaintext in bytes, i.e. `2 ^ 39 - 256` bits.
+const MAX_DATA_LEN: u64 = (1 << 36) - 32;
+
+/// AES in Galois/Counter Mode.
+///
+/// The nonce is usually of 12 bytes as TLS uses, but any nonempty length is
+/// accepted as the specification allows.
+#[derive(Clone)]
+pub struct AesGcm {
+    aes: Aes,
+    ghash_key: [u8; 16],
+}
+
+impl AesGcm {
+    /// Create with the key of 16, 24 or 32 bytes.
+    pub fn new(key: &[u8]) -> Result<AesGcm, KeyError> {
+        let aes = Aes::new(key)?;
+        let mut ghash_key = [0; 16];
+        aes.encrypt_block(&mut ghash_key);
+        Ok(AesGcm { aes, ghash_key })
+    }
+
+    /// Get the pre-counter block J0 of the nonce.
+    fn pre_counter_block(&self, nonce: &[u8]) -> Result<[u8; 16], AeadError> {
+        match nonce.len() {
+            0 => Err(AeadError::InvalidNonceLength(0)),
+            12 => {
+                let mut block = [0; 16];
+                block[..12].copy_from_slice(nonce);
+                block[15] = 1;
+                Ok(block)
+            },
+            len => {
+                let mut ghash = Ghash::new(&self.ghash_key);
+                ghash.update_padded(nonce);
+                ghash.update_lengths(0, len as u64 * 8);
+                Ok(ghash.finalize())
+            },
+        }
+    }
+
+    /// XOR the data with the key stream from the block next to J0.
+    fn apply_key_stream(&self, pre_counter_block: &[u8; 16], data: &mut [u8]) {
+        let mut counter = *pre_counter_block;
+        for chunk in data.chunks_mut(16) {
+            increment32(&mut counter);
+            let mut key_stream = counter;
+            self.aes.encrypt_block(&mut key_stream);
+            chunk.iter_mut().zip(key_stream.iter()).for_each(|(byte, key)| { *byte ^= key });
+        }
+    }
+
+    fn compute_tag(&self, pre_counter_block: &[u8; 16], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_SIZE] {
+        let mut ghash = Ghash::new(&self.ghash_key);
+        ghash.update_padded(aad);
+        ghash.update_padded(ciphertext);
+        ghash.update_lengths(aad.len() as u64 * 8, ciphertext.len() as u64 * 8);
+        let mut tag = ghash.finalize();
+
+        let mut mask = *pre_counter_block;
+        self.aes.encrypt_block(&mut mask);
+        tag.iter_mut().zip(mask.iter()).for_each(|(byte, mask)| { *byte ^= mask });
+        tag
+    }
+}
+
+impl Aead for AesGcm {
+    const NONCE_SIZE: usize = 12;
+
+    fn seal_detached(&self, nonce: &[u8], aad: &[u8], data: &mut [u8]) -> Result<[u8; TAG_SIZE], AeadError> {
+        check_data_length(data)?;
+        let pre_counter_block = self.pre_counter_block(nonce)?;
+        self.apply_key_stream(&pre_counter_block, data);
+        Ok(self.compute_tag(&pre_counter_block, aad, data))
+    }
+
+    fn open_detached(&self, nonce: &[u8], aad: &[u8], data: &mut [u8], tag: &[u8]) -> Result<(), AeadError> {
+        check_data_length(data)?;
+        let pre_counter_block = self.pre_counter_block(nonce)?;
+        verify_tag(&self.compute_tag(&pre_counter_block, aad, data), tag)?;
+        self.apply_key_stream(&pre_counter_block, data);
+        Ok(())
+    }
+}
+
+fn check_data_length(data: &[u8]) -> Result<(), AeadError> {
+    if data.len() as u64 > MAX_DATA_LEN {
+        return Err(AeadError::InvalidLength(data.len()));
+    }
+    Ok(())
+}
+
+/// Increment the last 32 bits of the counter block, wrapping around to 0.
+fn increment32(counter: &mut [u8; 16]) {
+    let count = u32::from_be_bytes(counter[12..].try_into().unwrap());
+    counter[12..].copy_from_slice(&count.wrapping_add(1).to_be_bytes());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::hex;
+
+    const PLAINTEXT: &str = concat!(
+        "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72",
+        "1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255",
+    );
+    const CIPHERTEXT_128: &str = concat!(
+        "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e",
+        "21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091473f5985",
+    );
+    const CIPHERTEXT_256: &str = concat!(
+        "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa",
+        "8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662898015ad",
+    );
+    const AAD: &str = "feedfacedeadbeeffeedfacedeadbeefabaddad2";
+
+    /// Test cases of the GCM specification by McGrew and Viega, which NIST
+    /// refers to, as (key, nonce, plaintext, aad, ciphertext, tag).
+    const VECTORS: [(&str, &str, &str, &str, &str, &str); 10] = [
+        // test case 1 to 6 for AES-128
+        (
+            "00000000000000000000000000000000", "000000000000000000000000",
+            "", "", "",
+            "58e2fccefa7e3061367f1d57a4e7455a",
+        ),
+        (
+            "00000000000000000000000000000000", "000000000000000000000000",
+            "00000000000000000000000000000000", "", "0388dace60b6a392f328c2b971b2fe78",
+            "ab6e47d42cec13bdf53a67b21257bddf",
+        ),
+        (
+            "feffe9928665731c6d6a8f9467308308", "cafebabefacedbaddecaf888",
+            PLAINTEXT, "", CIPHERTEXT_128,
+            "4d5c2af327cd64a62cf35abd2ba6fab4",
+        ),
+        (
+            "feffe9928665731c6d6a8f9467308308", "cafebabefacedbaddecaf888",
+            PLAINTEXT.split_at(120).0, AAD, CIPHERTEXT_128.split_at(120).0,
+            "5bc94fbc3221a5db94fae95ae7121a47",
+        ),
+        (
+            "feffe9928665731c6d6a8f9467308308", "cafebabefacedbad",
+            PLAINTEXT.split_at(120).0, AAD,
+            concat!(
+                "61353b4c2806934a777ff51fa22a4755699b2a714fcdc6f83766e5f97b6c7423",
+                "73806900e49f24b22b097544d4896b424989b5e1ebac0f07c23f4598",
+            ),
+            "3612d2e79e3b0785561be14aaca2fccb",
+        ),
+        (
+            "feffe9928665731c6d6a8f9467308308",
+            concat!(
+                "9313225df88406e555909c5aff5269aa6a7a9538534f7da1e4c303d2a318a728",
+                "c3c0c95156809539fcf0e2429a6b525416aedbf5a0de6a57a637b39b",
+            ),
+            PLAINTEXT.split_at(120).0, AAD,
+            concat!(
+                "8ce24998625615b603a033aca13fb894be9112a5c3a211a8ba262a3cca7e2ca7",
+                "01e4a9a4fba43c90ccdcb281d48c7c6fd62875d2aca417034c34aee5",
+            ),
+            "619cc5aefffe0bfa462af43c1699d050",
+        ),
+        // test case 13 to 16 for AES-256
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "000000000000000000000000",
+            "", "", "",
+            "530f8afbc74536b9a963b4f1c4cb738b",
+        ),
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "000000000000000000000000",
+            "00000000000000000000000000000000", "", "cea7403d4d606b6e074ec5d3baf39d18",
+            "d0d1c8a799996bf0265b98b5d48ab919",
+        ),
+        (
+            "feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308",
+            "cafebabefacedbaddecaf888",
+            PLAINTEXT, "", CIPHERTEXT_256,
+            "b094dac5d93471bdec1a502270e3cc6c",
+        ),
+        (
+            "feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308",
+            "cafebabefacedbaddecaf888",
+            PLAINTEXT.split_at(120).0, AAD, CIPHERTEXT_256.split_at(120).0,
+            "76fc6ece0f4e1768cddf8853bb2d551b",
+        ),
+    ];
+
+    #[test]
+    fn test_vectors() {
+        for (key, nonce, plaintext, aad, ciphertext, tag) in VECTORS {
+            let gcm = AesGcm::new(&hex(key)).unwrap();
+            let mut data = hex(plaintext);
+            let computed_tag = gcm.seal_detached(&hex(nonce), &hex(aad), &mut data).unwrap();
+            assert_eq!(data, hex(ciphertext), "key {} nonce {}", key, nonce);
+            assert_eq!(computed_tag[..], hex(tag)[..], "key {} nonce {}", key, nonce);
+
+            gcm.open_detached(&hex(nonce), &hex(aad), &mut data, &hex(tag)).unwrap();
+            assert_eq!(data, hex(plaintext), "key {} nonce {}", key, nonce);
+        }
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let (key, nonce, plaintext, aad, ciphertext, tag) = VECTORS[3];
+        let gcm = AesGcm::new(&hex(key)).unwrap();
+        let sealed = gcm.seal(&hex(nonce), &hex(aad), &hex(plaintext)).unwrap();
+        assert_eq!(sealed, [hex(ciphertext), hex(tag)].concat());
+        assert_eq!(gcm.open(&hex(nonce), &hex(aad), &sealed).unwrap(), hex(plaintext));
+    }
+
+    #[test]
+    fn test_open_forged() {
+        let (key, nonce, _, aad, ciphertext, tag) = VECTORS[3];
+        let gcm = AesGcm::new(&hex(key)).unwrap();
+
+        // the data stays encrypted if the tag doesn't match
+        let mut data = hex(ciphertext);
+        data[0] ^= 1;
+        let result = gcm.open_detached(&hex(nonce), &hex(aad), &mut data, &hex(tag));
+        assert_eq!(result, Err(AeadError::InvalidTag));
+        data[0] ^= 1;
+        assert_eq!(data, hex(ciphertext));
+
+        let mut forged_tag = hex(tag);
+        forged_tag[15] ^= 0x80;
+        let result = gcm.open_detached(&hex(nonce), &hex(aad), &mut data, &forged_tag);
+        assert_eq!(result, Err(AeadError::InvalidTag));
+        assert_eq!(data, hex(ciphertext));
+
+        let result = gcm.open_detached(&hex(nonce), b"", &mut data, &hex(tag));
+        assert_eq!(result, Err(AeadError::InvalidTag));
+        let result = gcm.open_detached(&hex(nonce), &hex(aad), &mut data, &hex(tag)[..12]);
+        assert_eq!(result, Err(AeadError::InvalidTag));
+        assert_eq!(data, hex(ciphertext));
+    }
+
+    #[test]
+    fn test_open_too_short() {
+        let gcm = AesGcm::new(&[0; 16]).unwrap();
+        assert_eq!(gcm.open(&[0; 12], b"", &[0; 15]), Err(AeadError::InvalidLength(15)));
+    }
+
+    #[test]
+    fn test_empty_nonce() {
+        let gcm = AesGcm::new(&[0; 16]).unwrap();
+        assert_eq!(gcm.seal(&[], b"", b"data"), Err(AeadError::InvalidNonceLength(0)));
+    }
+
+    #[test]
+    fn test_increment32_wraps_around() {
+        let mut counter = [0xFF; 16];
+        increment32(&mut counter);
+        assert_eq!(counter[..12], [0xFF; 12]);
+        assert_eq!(counter[12..], [0; 4]);
+    }
+}
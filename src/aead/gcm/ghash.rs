@@ -0,0 +1,84 @@
+//! GHASH, the universal hash of GCM over GF(2^128).
+//!
+//! The multiplication processes each bit of the operand with masks instead of
+//! branches or lookup tables, so that it runs in constant time.
+
+/// The reduction polynomial x^128 + x^7 + x^2 + x + 1 in the reflected bit
+/// order of GCM.
+const R: u128 = 0xE1 << 120;
+
+pub(super) struct Ghash {
+    key: u128,
+    state: u128,
+}
+
+impl Ghash {
+    pub(super) fn new(key: &[u8; 16]) -> Ghash {
+        Ghash { key: u128::from_be_bytes(*key), state: 0 }
+    }
+
+    /// Process the data padded with zeros to a multiple of 16 bytes.
+    pub(super) fn update_padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block = [0; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.update_block(u128::from_be_bytes(block));
+        }
+    }
+
+    /// Process the block of the lengths in bits.
+    pub(super) fn update_lengths(&mut self, aad_bits: u64, data_bits: u64) {
+        self.update_block(((aad_bits as u128) << 64) | data_bits as u128);
+    }
+
+    pub(super) fn finalize(self) -> [u8; 16] {
+        self.state.to_be_bytes()
+    }
+
+    fn update_block(&mut self, block: u128) {
+        self.state = multiply(self.state ^ block, self.key);
+    }
+}
+
+/// Multiply in GF(2^128) of GCM (SP 800-38D Algorithm 1), where the most
+/// significant bit is the coefficient of x^0.
+fn multiply(x: u128, y: u128) -> u128 {
+    let mut product = 0;
+    let mut v = y;
+    for index in (0..128).rev() {
+        let bit = (x >> index) & 1;
+        product ^= v & bit.wrapping_neg();
+        let carry = v & 1;
+        v = (v >> 1) ^ (R & carry.wrapping_neg());
+    }
+    product
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::convert::TryInto;
+
+    fn block(hex: &str) -> [u8; 16] {
+        crate::base16::decode(hex).unwrap()[..].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_ghash() {
+        // GCM specification test case 2, GHASH(H, {}, C)
+        let mut ghash = Ghash::new(&block("66e94bd4ef8a2c3b884cfa59ca342b2e"));
+        ghash.update_padded(&block("0388dace60b6a392f328c2b971b2fe78"));
+        ghash.update_lengths(0, 128);
+        assert_eq!(ghash.finalize(), block("f38cbb1ad69223dcc3457ae5b6b0f885"));
+    }
+
+    #[test]
+    fn test_multiply_identity() {
+        // x^0 is the most significant bit
+        let one = 1 << 127;
+        let value = u128::from_be_bytes(block("66e94bd4ef8a2c3b884cfa59ca342b2e"));
+        assert_eq!(multiply(value, one), value);
+        assert_eq!(multiply(one, value), value);
+        assert_eq!(multiply(value, 0), 0);
+    }
+}
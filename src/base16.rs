@@ -0,0 +1,160 @@
+use core::fmt;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::table::{generate_encode_table_from, generate_decode_table_from, INVALID_VALUE};
+
+const BASE16_ENCODE_TABLE: [u8; 16] = generate_encode_table_from("0123456789ABCDEF");
+const BASE16_LOWER_ENCODE_TABLE: [u8; 16] = generate_encode_table_from("0123456789abcdef");
+const BASE16_DECODE_TABLE: [u8; 256] = merge_decode_tables(
+    &generate_decode_table_from(&BASE16_ENCODE_TABLE),
+    &generate_decode_table_from(&BASE16_LOWER_ENCODE_TABLE),
+);
+
+const LOW_4_BITS: u8 = 0x0F;
+
+/// Encode bytes by BASE16 with upper case symbols (RFC 4648 section 8).
+pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
+    encode_with_table(input.as_ref(), &BASE16_ENCODE_TABLE)
+}
+
+/// Encode bytes by BASE16 with lower case symbols, e.g. for fingerprints and
+/// key log files.
+pub fn encode_lower<T: AsRef<[u8]>>(input: T) -> String {
+    encode_with_table(input.as_ref(), &BASE16_LOWER_ENCODE_TABLE)
+}
+
+/// Decode errors.
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    InvalidLength,
+    InvalidByte(usize, u8),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength => write!(f, "invalid BASE16 length"),
+            DecodeError::InvalidByte(index, byte) => {
+                write!(f, "invalid BASE16 symbol 0x{:02X} at index {}", byte, index)
+            },
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+/// Decode bytes by BASE16.
+/// Both upper and lower case symbols are accepted.
+pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, DecodeError> {
+    let symbols = input.as_ref();
+    validate_decoding_target(symbols)?;
+
+    let bytes = symbols.chunks_exact(2)
+        .map(|pair| {
+            let high = BASE16_DECODE_TABLE[pair[0] as usize];
+            let low = BASE16_DECODE_TABLE[pair[1] as usize];
+            (high << 4) | low
+        })
+        .collect();
+
+    Ok(bytes)
+}
+
+fn encode_with_table(bytes: &[u8], encode_table: &[u8; 16]) -> String {
+    let mut symbols = Vec::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        symbols.push(encode_table[(byte >> 4) as usize]);
+        symbols.push(encode_table[(byte & LOW_4_BITS) as usize]);
+    }
+
+    String::from_utf8(symbols).unwrap()
+}
+
+/// Merge decode tables so that the symbols of both tables are accepted.
+const fn merge_decode_tables(table1: &[u8; 256], table2: &[u8; 256]) -> [u8; 256] {
+    let mut decode_table = *table1;
+    let mut index = 0;
+    while index < 256 {
+        if decode_table[index] == INVALID_VALUE {
+            decode_table[index] = table2[index];
+        }
+        index += 1;
+    }
+    decode_table
+}
+
+/// Validate whether the input violates BASE16 encoded string.
+/// This function check the below:
+///     - the length of input is the multiple of 2
+///     - the input doesn't contain invalid symbol
+fn validate_decoding_target(input: &[u8]) -> Result<(), DecodeError> {
+    if !input.len().is_multiple_of(2) {
+        return Err(DecodeError::InvalidLength);
+    }
+
+    let invalid_value = input.iter()
+        .enumerate()
+        .find(|(_, value)| { BASE16_DECODE_TABLE[**value as usize] == INVALID_VALUE });
+    if let Some((index, value)) = invalid_value {
+        return Err(DecodeError::InvalidByte(index, *value));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Test vectors of RFC 4648 section 10.
+    const RFC4648_VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "66"),
+        ("fo", "666F"),
+        ("foo", "666F6F"),
+        ("foob", "666F6F62"),
+        ("fooba", "666F6F6261"),
+        ("foobar", "666F6F626172"),
+    ];
+
+    #[test]
+    fn test_encode_rfc4648_vectors() {
+        for (input, expected) in RFC4648_VECTORS {
+            assert_eq!(encode(input), expected);
+        }
+    }
+
+    #[test]
+    fn test_decode_rfc4648_vectors() {
+        for (expected, input) in RFC4648_VECTORS {
+            assert_eq!(decode(input).unwrap(), expected.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_encode_lower() {
+        assert_eq!(encode_lower([0x01, 0xAB, 0xFF]), "01abff");
+    }
+
+    #[test]
+    fn test_decode_mixed_case() {
+        assert_eq!(decode("01aBfF").unwrap(), [0x01, 0xAB, 0xFF]);
+    }
+
+    #[test]
+    fn test_decode_invalid_length() {
+        assert_eq!(decode("abc"), Err(DecodeError::InvalidLength));
+    }
+
+    #[test]
+    fn test_decode_invalid_byte() {
+        assert_eq!(decode("abcg"), Err(DecodeError::InvalidByte(3, b'g')));
+    }
+
+    #[test]
+    fn test_inverse_property_encoding_to_decoding() {
+        let input: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode(encode(&input)).unwrap(), input);
+        assert_eq!(decode(encode_lower(&input)).unwrap(), input);
+    }
+}
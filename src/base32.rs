@@ -0,0 +1,259 @@
+use core::fmt;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::table::{generate_encode_table_from, generate_decode_table_from, INVALID_VALUE};
+
+const BASE32_ENCODE_TABLE: [u8; 32] = generate_encode_table_from(
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567"
+);
+const BASE32_DECODE_TABLE: [u8; 256] = generate_decode_table_from(
+    &BASE32_ENCODE_TABLE
+);
+const BASE32HEX_ENCODE_TABLE: [u8; 32] = generate_encode_table_from(
+    "0123456789ABCDEFGHIJKLMNOPQRSTUV"
+);
+const BASE32HEX_DECODE_TABLE: [u8; 256] = generate_decode_table_from(
+    &BASE32HEX_ENCODE_TABLE
+);
+const PADDING: u8 = b'=';
+
+const LOW_5_BITS: u64 = 0x1F;
+
+/// Encode bytes by BASE32 (RFC 4648 section 6).
+pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
+    encode_with_table(input.as_ref(), &BASE32_ENCODE_TABLE)
+}
+
+/// Encode bytes by BASE32 with extended hex alphabet (RFC 4648 section 7).
+pub fn encode_hex<T: AsRef<[u8]>>(input: T) -> String {
+    encode_with_table(input.as_ref(), &BASE32HEX_ENCODE_TABLE)
+}
+
+/// Decode errors.
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    InvalidLength,
+    InvalidByte(usize, u8),
+    InvalidLastSymbol(usize, u8),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength => write!(f, "invalid BASE32 length"),
+            DecodeError::InvalidByte(index, byte) => {
+                write!(f, "invalid BASE32 symbol 0x{:02X} at index {}", byte, index)
+            },
+            DecodeError::InvalidLastSymbol(index, byte) => {
+                write!(f, "invalid BASE32 last symbol 0x{:02X} at index {}", byte, index)
+            },
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+/// Decode bytes by BASE32 (RFC 4648 section 6).
+pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, DecodeError> {
+    decode_with_table(input.as_ref(), &BASE32_DECODE_TABLE)
+}
+
+/// Decode bytes by BASE32 with extended hex alphabet (RFC 4648 section 7).
+pub fn decode_hex<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, DecodeError> {
+    decode_with_table(input.as_ref(), &BASE32HEX_DECODE_TABLE)
+}
+
+fn encode_with_table(bytes: &[u8], encode_table: &[u8; 32]) -> String {
+    let mut symbols = Vec::with_capacity(bytes.len().div_ceil(5) * 8);
+
+    for chunk in bytes.chunks(5) {
+        let bit5s = into_8_bit5(chunk);
+        let symbols_len = symbols_len_of(chunk.len());
+        for bit5 in &bit5s[..symbols_len] {
+            symbols.push(encode_table[*bit5 as usize]);
+        }
+        symbols.resize(symbols.len() + 8 - symbols_len, PADDING);
+    }
+
+    String::from_utf8(symbols).unwrap()
+}
+
+fn decode_with_table(symbols: &[u8], decode_table: &[u8; 256]) -> Result<Vec<u8>, DecodeError> {
+    if symbols.is_empty() {
+        return Ok(Vec::new());
+    }
+    validate_decoding_target(symbols, decode_table)?;
+
+    let padding = count_padding(symbols);
+    let symbols = &symbols[..symbols.len()-padding];
+    let mut bytes = Vec::with_capacity(symbols.len() * 5 / 8);
+    for group in symbols.chunks(8) {
+        let mut bit5s = [0; 8];
+        for (bit5, symbol) in bit5s.iter_mut().zip(group) {
+            *bit5 = decode_table[*symbol as usize];
+        }
+        let bytes_len = group.len() * 5 / 8;
+        bytes.extend_from_slice(&into_5_byte(&bit5s)[..bytes_len]);
+    }
+
+    Ok(bytes)
+}
+
+/// Get the number of symbols (without padding) encoded from 1-5 bytes.
+fn symbols_len_of(bytes_len: usize) -> usize {
+    (bytes_len * 8).div_ceil(5)
+}
+
+/// Convert up to 5 bytes to eight 5-bits.
+/// Missing bytes are treated as zeros.
+fn into_8_bit5(bytes: &[u8]) -> [u8; 8] {
+    let mut buf = [0; 8];
+    buf[3..3+bytes.len()].copy_from_slice(bytes);
+    let bit64 = u64::from_be_bytes(buf);
+
+    let mut bit5s = [0; 8];
+    for (index, bit5) in bit5s.iter_mut().enumerate() {
+        *bit5 = ((bit64 >> (35 - index * 5)) & LOW_5_BITS) as u8;
+    }
+    bit5s
+}
+
+/// Convert eight 5-bits to 5 bytes.
+fn into_5_byte(bit5s: &[u8; 8]) -> [u8; 5] {
+    let bit64 = bit5s.iter()
+        .fold(0u64, |acc, bit5| { (acc << 5) | *bit5 as u64 });
+    let buf = bit64.to_be_bytes();
+    [buf[3], buf[4], buf[5], buf[6], buf[7]]
+}
+
+/// Validate whether the input violates BASE32 encoded string.
+/// This function check the below:
+///     - the length of input is the multiple of 8
+///     - the input doesn't contain invalid symbol
+///         (only the element of the encode table or PADDING are permitted)
+///     - PADDING is set only in the last 1, 3, 4 or 6 element
+///     - the input doesn't contain invalid last symbol
+///         (all of the extra bits in the symbol must be 0 if padding exist)
+fn validate_decoding_target(input: &[u8], decode_table: &[u8; 256]) -> Result<(), DecodeError> {
+    // nothing to do if empty
+    if input.is_empty() {
+        return Ok(());
+    }
+
+    // validate the length of the input bytes
+    if !input.len().is_multiple_of(8) {
+        return Err(DecodeError::InvalidLength);
+    }
+
+    // validate the input contains invalid symbol
+    let padding = count_padding(input);
+    let invalid_value = input[..input.len()-padding].iter()
+        .enumerate()
+        .find(|(_, value)| { decode_table[**value as usize] == INVALID_VALUE });
+    if let Some((index, value)) = invalid_value {
+        return Err(DecodeError::InvalidByte(index, *value));
+    }
+
+    // validate the input contains invalid last symbol
+    let last_non_pad_index = input.len() - padding - 1;
+    let last_non_pad_elem = input[last_non_pad_index];
+    let mask = match padding {
+        6 => 0b0000_0011,
+        4 => 0b0000_1111,
+        3 => 0b0000_0001,
+        1 => 0b0000_0111,
+        _ => 0b0000_0000,
+    };
+    if decode_table[last_non_pad_elem as usize] & mask != 0 {
+        return Err(DecodeError::InvalidLastSymbol(last_non_pad_index, last_non_pad_elem));
+    }
+
+    Ok(())
+}
+
+/// Count the padding at the end of the input.
+/// The count is rounded down to the valid one (1, 3, 4 or 6), and the rest of
+/// padding is left as invalid symbols.
+fn count_padding(input: &[u8]) -> usize {
+    let trailing = input.iter()
+        .rev()
+        .take(6)
+        .take_while(|symbol| { **symbol == PADDING })
+        .count();
+    match trailing {
+        6 => 6,
+        4 | 5 => 4,
+        3 => 3,
+        1 | 2 => 1,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Test vectors of RFC 4648 section 10.
+    const RFC4648_VECTORS: [(&str, &str, &str); 7] = [
+        ("", "", ""),
+        ("f", "MY======", "CO======"),
+        ("fo", "MZXQ====", "CPNG===="),
+        ("foo", "MZXW6===", "CPNMU==="),
+        ("foob", "MZXW6YQ=", "CPNMUOG="),
+        ("fooba", "MZXW6YTB", "CPNMUOJ1"),
+        ("foobar", "MZXW6YTBOI======", "CPNMUOJ1E8======"),
+    ];
+
+    #[test]
+    fn test_encode_rfc4648_vectors() {
+        for (input, expected, expected_hex) in RFC4648_VECTORS {
+            assert_eq!(encode(input), expected);
+            assert_eq!(encode_hex(input), expected_hex);
+        }
+    }
+
+    #[test]
+    fn test_decode_rfc4648_vectors() {
+        for (expected, input, input_hex) in RFC4648_VECTORS {
+            assert_eq!(decode(input).unwrap(), expected.as_bytes());
+            assert_eq!(decode_hex(input_hex).unwrap(), expected.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_decode_invalid_length() {
+        assert_eq!(decode("MZXW6"), Err(DecodeError::InvalidLength));
+    }
+
+    #[test]
+    fn test_decode_invalid_byte() {
+        assert_eq!(decode("MZXW1YTB"), Err(DecodeError::InvalidByte(4, b'1')));
+        assert_eq!(decode_hex("CPNMUOJW"), Err(DecodeError::InvalidByte(7, b'W')));
+    }
+
+    #[test]
+    fn test_decode_invalid_padding() {
+        // 2 padding symbols are invalid, so the first one is an invalid byte
+        assert_eq!(decode("MZXW6Y=="), Err(DecodeError::InvalidByte(6, b'=')));
+        assert_eq!(decode("MZX====="), Err(DecodeError::InvalidByte(3, b'=')));
+        assert_eq!(decode("MY======MY======"), Err(DecodeError::InvalidByte(2, b'=')));
+    }
+
+    #[test]
+    fn test_decode_invalid_last_symbol() {
+        assert_eq!(decode("MZ======"), Err(DecodeError::InvalidLastSymbol(1, b'Z')));
+        assert_eq!(decode("MZXR===="), Err(DecodeError::InvalidLastSymbol(3, b'R')));
+        assert_eq!(decode("MZXW7==="), Err(DecodeError::InvalidLastSymbol(4, b'7')));
+        assert_eq!(decode("MZXW6YR="), Err(DecodeError::InvalidLastSymbol(6, b'R')));
+    }
+
+    #[test]
+    fn test_inverse_property_encoding_to_decoding() {
+        let input: Vec<u8> = (0..=255).collect();
+        for size in 0..12 {
+            assert_eq!(decode(encode(&input[..size])).unwrap(), &input[..size]);
+            assert_eq!(decode_hex(encode_hex(&input[..size])).unwrap(), &input[..size]);
+        }
+        assert_eq!(decode(encode(&input)).unwrap(), input);
+    }
+}
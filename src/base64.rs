@@ -1,29 +1,39 @@
-use std::string::String;
-use std::vec::Vec;
-
-const BASE64_ENCODE_TABLE: [u8; 64] = generate_encode_table_from(
-    "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz01234567889+/"
-);
-const BASE64_DECODE_TABLE: [u8; 256] = generate_decode_table_from(
-    &BASE64_ENCODE_TABLE
-);
+use core::fmt;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::table::{generate_encode_table_from, generate_decode_table_from, INVALID_VALUE};
+
+pub mod ct;
+mod slice;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(test)]
+mod property_test;
+
+pub use slice::{EncodeError, encoded_len, decoded_len_estimate, encode_to_slice, decode_to_slice};
+#[cfg(feature = "std")]
+pub use stream::{Encoder, Decoder};
+
 const PADDING: u8 = b'=';
 
-const INVALID_VALUE: u8 = 0xFF;
 const LOW_6_BITS: u32 = 0x3F;
 
-/// Encode bytes by BASE64.
-pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
-    let bit6s = into_bit6s(input.as_ref());
-    let mut symbols: Vec<_> = bit6s.into_iter()
-        .map(|bit6| { BASE64_ENCODE_TABLE[bit6 as usize] })
-        .collect();
+/// Standard BASE64 with padding (RFC 4648 section 4).
+pub const STANDARD: Base64Engine = Base64Engine::new(Alphabet::STANDARD, Padding::Required);
+/// URL and filename safe BASE64 with padding (RFC 4648 section 5).
+pub const URL_SAFE: Base64Engine = Base64Engine::new(Alphabet::URL_SAFE, Padding::Required);
+/// URL and filename safe BASE64 without padding (RFC 4648 section 5).
+pub const URL_SAFE_NO_PAD: Base64Engine = Base64Engine::new(Alphabet::URL_SAFE, Padding::Forbidden);
 
-    while symbols.len() % 4 != 0 {
-        symbols.push(PADDING);
-    }
+/// Encode bytes by standard BASE64.
+pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
+    STANDARD.encode(input)
+}
 
-    String::from_utf8(symbols).unwrap()
+/// Encode bytes by standard BASE64 with line breaks every `line_len` symbols.
+/// See `Base64Engine::encode_wrapped()`.
+pub fn encode_wrapped<T: AsRef<[u8]>>(input: T, line_len: usize, line_ending: LineEnding) -> String {
+    STANDARD.encode_wrapped(input, line_len, line_ending)
 }
 
 /// Decode errors.
@@ -32,47 +42,267 @@ pub enum DecodeError {
     InvalidLength,
     InvalidByte(usize, u8),
     InvalidLastSymbol(usize, u8),
+    OutputTooSmall,
 }
 
-/// Decode bytes by BASE64.
-pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, DecodeError> {
-    let symbols = input.as_ref();
-    if symbols.is_empty() {
-        return Ok(vec!());
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength => write!(f, "invalid BASE64 length"),
+            DecodeError::InvalidByte(index, byte) => {
+                write!(f, "invalid BASE64 symbol 0x{:02X} at index {}", byte, index)
+            },
+            DecodeError::InvalidLastSymbol(index, byte) => {
+                write!(f, "invalid BASE64 last symbol 0x{:02X} at index {}", byte, index)
+            },
+            DecodeError::OutputTooSmall => write!(f, "output buffer too small for BASE64"),
+        }
     }
-    if let Err(e) = validate_decoding_target(symbols) {
-        return Err(e);
+}
+
+impl core::error::Error for DecodeError {}
+
+impl DecodeError {
+    /// Convert the index of the error by the function.
+    fn map_index<F: Fn(usize) -> usize>(self, f: F) -> DecodeError {
+        match self {
+            DecodeError::InvalidLength => DecodeError::InvalidLength,
+            DecodeError::InvalidByte(index, byte) => DecodeError::InvalidByte(f(index), byte),
+            DecodeError::InvalidLastSymbol(index, byte) => DecodeError::InvalidLastSymbol(f(index), byte),
+            DecodeError::OutputTooSmall => DecodeError::OutputTooSmall,
+        }
     }
+}
+
+/// Decode bytes by standard BASE64.
+pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, DecodeError> {
+    STANDARD.decode(input)
+}
 
-    let padding = count_padding(symbols);
-    let bit6s: Vec<_> = symbols[..symbols.len()-padding].iter()
-        .map(|symbol| { BASE64_DECODE_TABLE[*symbol as usize] })
-        .collect();
+/// Decode bytes by standard BASE64 with the options.
+/// See `Base64Engine::decode_with()`.
+pub fn decode_with<T: AsRef<[u8]>>(input: T, options: DecodeOptions) -> Result<Vec<u8>, DecodeError> {
+    STANDARD.decode_with(input, options)
+}
 
-    let bytes = into_bytes(bit6s.as_ref());
+/// Options to relax the validation of decoding.
+/// The default is the strict mode, i.e. all options are disabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// Skip ASCII whitespace including line breaks, as in MIME bodies.
+    pub ignore_whitespace: bool,
+    /// Accept the input without padding even if the engine requires it.
+    pub allow_missing_padding: bool,
+    /// Accept the last symbol having non-zero extra bits, which are discarded.
+    pub allow_trailing_bits: bool,
+}
 
-    Ok(bytes)
+/// Line ending of wrapped output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`
+    Lf,
+    /// `\r\n`, as used in MIME.
+    CrLf,
 }
 
-const fn generate_encode_table_from(symbols: &str) -> [u8; 64] {
-    let symbols = symbols.as_bytes();
-    let mut encode_table: [u8; 64] = [0; 64];
-    let mut index = 0;
-    while index < 64 {
-        encode_table[index] = symbols[index];
-        index += 1;
+impl LineEnding {
+    /// Get the line ending as string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
     }
-    encode_table
 }
 
-const fn generate_decode_table_from(encode_table: &[u8; 64]) -> [u8; 256] {
-    let mut decode_table = [INVALID_VALUE; 256];
-    let mut index = 0;
-    while index < 64 {
-        decode_table[encode_table[index] as usize] = index as u8;
-        index += 1;
+/// The 64 symbols used by BASE64, with the decode table for them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alphabet {
+    encode_table: [u8; 64],
+    decode_table: [u8; 256],
+}
+
+impl Alphabet {
+    /// Alphabet of standard BASE64.
+    pub const STANDARD: Alphabet = Alphabet::new(
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/"
+    );
+    /// Alphabet of URL and filename safe BASE64.
+    pub const URL_SAFE: Alphabet = Alphabet::new(
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"
+    );
+
+    /// Create an alphabet from 64 symbols ordered by their values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the symbols are not 64 distinct ASCII characters or contain
+    /// the padding symbol `=`. The panic becomes a compile error when this is
+    /// called in a constant.
+    pub const fn new(symbols: &str) -> Alphabet {
+        let bytes = symbols.as_bytes();
+        if bytes.len() != 64 {
+            panic!("BASE64 alphabet must have 64 symbols");
+        }
+        let mut index = 0;
+        while index < 64 {
+            if !bytes[index].is_ascii() || bytes[index] == PADDING {
+                panic!("BASE64 alphabet must consist of ASCII characters except '='");
+            }
+            index += 1;
+        }
+
+        let encode_table = generate_encode_table_from(symbols);
+        let decode_table = generate_decode_table_from(&encode_table);
+        let mut index = 0;
+        while index < 64 {
+            if decode_table[encode_table[index] as usize] != index as u8 {
+                panic!("BASE64 alphabet must not contain duplicate symbols");
+            }
+            index += 1;
+        }
+
+        Alphabet { encode_table, decode_table }
+    }
+
+    /// Get the symbol of a 6-bit value.
+    fn symbol(&self, bit6: u8) -> u8 {
+        self.encode_table[bit6 as usize]
+    }
+
+    /// Get the 6-bit value of a symbol, or `INVALID_VALUE` if it's not in the
+    /// alphabet.
+    fn value(&self, symbol: u8) -> u8 {
+        self.decode_table[symbol as usize]
+    }
+}
+
+/// Policy for the padding symbols.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// Write padding when encoding, and require it when decoding.
+    Required,
+    /// Write padding when encoding, but accept the input without it when
+    /// decoding.
+    Optional,
+    /// Never write padding when encoding, and reject it when decoding.
+    Forbidden,
+}
+
+/// BASE64 encoder and decoder with an alphabet and a padding policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Base64Engine {
+    alphabet: Alphabet,
+    padding: Padding,
+}
+
+impl Base64Engine {
+    /// Create an engine.
+    pub const fn new(alphabet: Alphabet, padding: Padding) -> Base64Engine {
+        Base64Engine { alphabet, padding }
+    }
+
+    /// Get the alphabet.
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// Get the padding policy.
+    pub fn padding(&self) -> Padding {
+        self.padding
+    }
+
+    /// Encode bytes by BASE64.
+    pub fn encode<T: AsRef<[u8]>>(&self, input: T) -> String {
+        let bit6s = into_bit6s(input.as_ref());
+        let mut symbols: Vec<_> = bit6s.into_iter()
+            .map(|bit6| { self.alphabet.symbol(bit6) })
+            .collect();
+
+        if self.padding != Padding::Forbidden {
+            while !symbols.len().is_multiple_of(4) {
+                symbols.push(PADDING);
+            }
+        }
+
+        String::from_utf8(symbols).unwrap()
+    }
+
+    /// Encode bytes by BASE64 with line breaks every `line_len` symbols, e.g.
+    /// 76 for MIME and 64 for PEM.
+    /// The line ending is put only between lines, not at the end of output.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line_len` is 0.
+    pub fn encode_wrapped<T: AsRef<[u8]>>(&self, input: T, line_len: usize, line_ending: LineEnding) -> String {
+        assert!(line_len > 0, "line length must be positive");
+        let symbols = self.encode(input);
+        let line_ending = line_ending.as_str();
+        let lines = symbols.len().div_ceil(line_len);
+        let mut wrapped = String::with_capacity(symbols.len() + lines.saturating_sub(1) * line_ending.len());
+
+        for (index, line) in symbols.as_bytes().chunks(line_len).enumerate() {
+            if index > 0 {
+                wrapped.push_str(line_ending);
+            }
+            // BASE64 symbols are all ASCII
+            wrapped.push_str(core::str::from_utf8(line).unwrap());
+        }
+
+        wrapped
+    }
+
+    /// Decode bytes by BASE64.
+    pub fn decode<T: AsRef<[u8]>>(&self, input: T) -> Result<Vec<u8>, DecodeError> {
+        self.decode_with(input, DecodeOptions::default())
+    }
+
+    /// Decode bytes by BASE64 with the options.
+    /// The index of errors points to the original input even if whitespace
+    /// is ignored.
+    pub fn decode_with<T: AsRef<[u8]>>(&self, input: T, options: DecodeOptions) -> Result<Vec<u8>, DecodeError> {
+        let input = input.as_ref();
+        if !options.ignore_whitespace {
+            return self.decode_symbols(input, &options);
+        }
+
+        let (symbols, indices): (Vec<u8>, Vec<usize>) = input.iter()
+            .enumerate()
+            .filter(|(_, symbol)| { !symbol.is_ascii_whitespace() })
+            .map(|(index, symbol)| { (*symbol, index) })
+            .unzip();
+        self.decode_symbols(&symbols, &options)
+            .map_err(|e| { e.map_index(|index| { indices[index] }) })
+    }
+
+    /// Decode symbols which don't contain whitespace.
+    fn decode_symbols(&self, symbols: &[u8], options: &DecodeOptions) -> Result<Vec<u8>, DecodeError> {
+        if symbols.is_empty() {
+            return Ok(Vec::new());
+        }
+        validate_decoding_target(symbols, self, options)?;
+
+        let padding = self.count_padding(symbols);
+        let bit6s: Vec<_> = symbols[..symbols.len()-padding].iter()
+            .map(|symbol| { self.alphabet.value(*symbol) })
+            .collect();
+
+        let bytes = into_bytes(bit6s.as_ref());
+
+        Ok(bytes)
+    }
+
+    /// Count the padding symbols at the end of the input.
+    /// Padding symbols are not counted if padding is forbidden since they are
+    /// just invalid bytes then.
+    fn count_padding(&self, input: &[u8]) -> usize {
+        match self.padding {
+            Padding::Forbidden => 0,
+            _ => count_padding(input),
+        }
     }
-    decode_table
 }
 
 /// Convert bytes to 6-bits.
@@ -87,7 +317,7 @@ const fn generate_decode_table_from(encode_table: &[u8; 64]) -> [u8; 256] {
 fn into_bit6s(bytes: &[u8]) -> Vec<u8> {
     // The actual element is 0-2 less than the capacity, but these are consumed
     // for padding later.
-    let bit6s_size = (bytes.len() + 2) / 3 * 4;
+    let bit6s_size = bytes.len().div_ceil(3) * 4;
     let mut bit6s = Vec::with_capacity(bit6s_size);
 
     let mut rest = bytes;
@@ -151,28 +381,40 @@ fn into_2_bit6(byte: u8) -> (u8, u8) {
 /// Validate whether the input violates BASE64 encoded string.
 /// This function check the below:
 ///     - the length of input is the multiple of 4
+///         (or the remainder is 2 or 3 if padding is not required or
+///         allowed to be missing)
 ///     - the input doesn't contain invalid symbol
-///         (only the element of BASE64_ENCODE_TABLE or PADDING are permitted)
+///         (only the symbols of the alphabet or PADDING are permitted)
 ///     - PADDING is set only in the last 1 or 2 element
+///         (and never set if padding is forbidden)
 ///     - the input doesn't contain invalid last symbol
-///         (all of the extra bits in the symbol must be 0 if padding exist)
-fn validate_decoding_target(input: &[u8]) -> Result<(), DecodeError> {
+///         (all of the extra bits in the last symbol must be 0 unless trailing
+///         bits are allowed)
+fn validate_decoding_target(
+    input: &[u8],
+    engine: &Base64Engine,
+    options: &DecodeOptions,
+) -> Result<(), DecodeError> {
     // nothing to do if empty
     if input.is_empty() {
         return Ok(());
     }
 
     // validate the length of the input bytes
-    if input.len() % 4 != 0 {
+    let unpadded_length_allowed = engine.padding != Padding::Required
+        || options.allow_missing_padding;
+    if input.len() % 4 == 1 || (!unpadded_length_allowed && !input.len().is_multiple_of(4)) {
+        return Err(DecodeError::InvalidLength);
+    }
+    let padding = engine.count_padding(input);
+    if padding > 0 && !input.len().is_multiple_of(4) {
         return Err(DecodeError::InvalidLength);
     }
 
     // validate the input contains invalid symbol
-    let padding = count_padding(input);
-    let invalid_value = input[..input.len()-padding].into_iter()
+    let invalid_value = input[..input.len()-padding].iter()
         .zip(0..input.len())
-        .filter(|(value, _)| { BASE64_DECODE_TABLE[**value as usize] == INVALID_VALUE })
-        .nth(0);
+        .find(|(value, _)| { engine.alphabet.value(**value) == INVALID_VALUE });
     if let Some((value, index)) = invalid_value {
         return Err(DecodeError::InvalidByte(index, *value));
     }
@@ -180,12 +422,12 @@ fn validate_decoding_target(input: &[u8]) -> Result<(), DecodeError> {
     // validate the input contains invalid last symbol
     let last_non_pad_index = input.len() - padding - 1;
     let last_non_pad_elem = input[last_non_pad_index];
-    let mask = match padding {
+    let mask = match (input.len() - padding) % 4 {
         2 => 0b0000_1111,
-        1 => 0b0000_0011,
+        3 => 0b0000_0011,
         _ => 0b0000_0000,
     };
-    if BASE64_DECODE_TABLE[last_non_pad_elem as usize] & mask != 0 {
+    if !options.allow_trailing_bits && engine.alphabet.value(last_non_pad_elem) & mask != 0 {
         return Err(DecodeError::InvalidLastSymbol(last_non_pad_index, last_non_pad_elem));
     }
 
@@ -216,7 +458,7 @@ fn into_bytes(bit6s: &[u8]) -> Vec<u8> {
     // But 6-bits is excluded max 2 padding, so we need to calculate:
     //      roundUp((6-bits-size) * 3 / 4) - (padding-count)
     let padding = (4 - (bit6s.len() % 4)) % 4;
-    let bytes_size = ((bit6s.len() + 3) / 4 * 3) - padding;
+    let bytes_size = (bit6s.len().div_ceil(4) * 3) - padding;
     let mut bytes = Vec::with_capacity(bytes_size);
 
     let mut rest = bit6s;
@@ -251,7 +493,7 @@ fn into_bytes(bit6s: &[u8]) -> Vec<u8> {
 fn into_3_byte(bit6_1: u8, bit6_2: u8, bit6_3: u8, bit6_4: u8) -> (u8, u8, u8) {
     ( (bit6_1 << 2) + (bit6_2 >> 4)
     , (bit6_2 << 4) + (bit6_3 >> 2)
-    , (bit6_3 << 6) + (bit6_4 >> 0)
+    , (bit6_3 << 6) + bit6_4
     )
 }
 
@@ -268,6 +510,7 @@ fn into_1_byte(bit6_1: u8, bit6_2: u8) -> u8 {
 }
 
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]  // group by 6-bits for readability
 mod test {
     use super::*;
     use std::fmt::Display;
@@ -279,7 +522,7 @@ mod test {
 
         // check length
         if left.len() != right.len() {
-            assert!(false, concat!(
+            panic!(concat!(
                 "assertion failed `(left.len() == right.len())`\n",
                 "  left: {}\n",
                 " right: {}",
@@ -291,10 +534,9 @@ mod test {
         let result = left.iter()
             .zip(right)
             .zip(index)
-            .filter(|((a, b), _)| { a != b })
-            .nth(0);
+            .find(|((a, b), _)| { a != b });
         if let Some(((a, b), i)) = result {
-            assert!(false, concat!(
+            panic!(concat!(
                 "assertion failed `(left == right)`\n",
                 " index: {}\n",
                 "  left: {}\n",
@@ -303,6 +545,40 @@ mod test {
         }
     }
 
+    /// Test vectors of RFC 4648 section 10.
+    const RFC4648_VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    #[test]
+    fn test_encode_rfc4648_vectors() {
+        for (input, expected) in RFC4648_VECTORS {
+            assert_eq!(encode(input), expected);
+        }
+    }
+
+    #[test]
+    fn test_decode_rfc4648_vectors() {
+        for (expected, input) in RFC4648_VECTORS {
+            assert_eq!(decode(input).unwrap(), expected.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_url_safe_no_pad_rfc4648_vectors() {
+        for (input, expected) in RFC4648_VECTORS {
+            let expected = expected.trim_end_matches('=');
+            assert_eq!(URL_SAFE_NO_PAD.encode(input), expected);
+            assert_eq!(URL_SAFE_NO_PAD.decode(expected).unwrap(), input.as_bytes());
+        }
+    }
+
     #[test]
     fn test_encode_9_bytes() {
         let input = [
@@ -443,6 +719,138 @@ mod test {
         assert_eq!(output, DecodeError::InvalidLastSymbol(5, b'I'));
     }
 
+    #[test]
+    fn test_inverse_property_all_symbols() {
+        let input: Vec<u8> = (0..64).collect();
+        let symbols: Vec<u8> = input.iter()
+            .map(|bit6| { Alphabet::STANDARD.symbol(*bit6) })
+            .collect();
+        let decoded = decode(&symbols).unwrap();
+        assert_eq!(encode(decoded).as_bytes(), &symbols[..]);
+    }
+
+    #[test]
+    fn test_alphabet_standard_last_symbols() {
+        let input = [0b111110_11, 0b1111_0000];  // 62, 63, 0
+        assert_eq!(encode(input), "+/A=");
+    }
+
+    #[test]
+    fn test_engine_url_safe() {
+        let input = [0b111110_11, 0b1111_0000];  // 62, 63, 0
+        assert_eq!(URL_SAFE.encode(input), "-_A=");
+        assert_eq!(URL_SAFE.decode("-_A=").unwrap(), input);
+        assert_eq!(URL_SAFE.decode("+/A="), Err(DecodeError::InvalidByte(0, b'+')));
+    }
+
+    #[test]
+    fn test_engine_padding_forbidden() {
+        let input = [0b111110_11, 0b1111_0000];  // 62, 63, 0
+        assert_eq!(URL_SAFE_NO_PAD.encode(input), "-_A");
+        assert_eq!(URL_SAFE_NO_PAD.decode("-_A").unwrap(), input);
+        assert_eq!(URL_SAFE_NO_PAD.decode("-_A="), Err(DecodeError::InvalidByte(3, b'=')));
+        assert_eq!(URL_SAFE_NO_PAD.decode("-_"), Err(DecodeError::InvalidLastSymbol(1, b'_')));
+    }
+
+    #[test]
+    fn test_engine_padding_optional() {
+        let engine = Base64Engine::new(Alphabet::STANDARD, Padding::Optional);
+        assert_eq!(engine.encode([0b110100_11]), "0w==");
+        assert_eq!(engine.decode("0w==").unwrap(), [0b110100_11]);
+        assert_eq!(engine.decode("0w").unwrap(), [0b110100_11]);
+        assert_eq!(engine.decode("abcd010").unwrap(), decode("abcd010=").unwrap());
+        assert_eq!(engine.decode("abcde"), Err(DecodeError::InvalidLength));
+        assert_eq!(engine.decode("0w="), Err(DecodeError::InvalidLength));
+        assert_eq!(engine.decode("0x"), Err(DecodeError::InvalidLastSymbol(1, b'x')));
+    }
+
+    #[test]
+    fn test_engine_padding_required() {
+        assert_eq!(STANDARD.decode("0w"), Err(DecodeError::InvalidLength));
+    }
+
+    #[test]
+    fn test_engine_custom_alphabet() {
+        const REVERSED: Alphabet = Alphabet::new(
+            "/+9876543210zyxwvutsrqponmlkjihgfedcbaZYXWVUTSRQPONMLKJIHGFEDCBA"
+        );
+        let engine = Base64Engine::new(REVERSED, Padding::Required);
+        let input = [0b000000_00, 0b0001_0000, 0b10_000011];  // 0, 1, 2, 3,
+        assert_eq!(engine.encode(input), "/+98");
+        assert_eq!(engine.decode("/+98").unwrap(), input);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_alphabet_duplicate_symbols() {
+        Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz01234567889+");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_alphabet_padding_symbol() {
+        Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+=");
+    }
+
+    #[test]
+    fn test_encode_wrapped() {
+        let input = [0u8; 12];  // 16 symbols
+        assert_eq!(encode_wrapped(input, 6, LineEnding::CrLf), "AAAAAA\r\nAAAAAA\r\nAAAA");
+        assert_eq!(encode_wrapped(input, 8, LineEnding::Lf), "AAAAAAAA\nAAAAAAAA");
+        assert_eq!(encode_wrapped(input, 76, LineEnding::CrLf), "AAAAAAAAAAAAAAAA");
+        assert_eq!(encode_wrapped([], 76, LineEnding::CrLf), "");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_encode_wrapped_zero_line_length() {
+        encode_wrapped([0u8; 3], 0, LineEnding::Lf);
+    }
+
+    #[test]
+    fn test_decode_with_default_is_strict() {
+        let input = "abcd\r\n010=";
+        assert_eq!(decode_with(input, DecodeOptions::default()), decode(input));
+    }
+
+    #[test]
+    fn test_decode_with_ignore_whitespace() {
+        let options = DecodeOptions { ignore_whitespace: true, ..DecodeOptions::default() };
+        let input = " abcd\r\n01\t0=\n";
+        assert_eq!(decode_with(input, options), decode("abcd010="));
+    }
+
+    #[test]
+    fn test_decode_with_ignore_whitespace_error_index() {
+        let options = DecodeOptions { ignore_whitespace: true, ..DecodeOptions::default() };
+        assert_eq!(decode_with("ab\r\nc!", options), Err(DecodeError::InvalidByte(5, b'!')));
+        assert_eq!(decode_with("ab\r\ncdefC=", options), Err(DecodeError::InvalidLastSymbol(8, b'C')));
+    }
+
+    #[test]
+    fn test_inverse_property_wrapping_to_decoding() {
+        let input: Vec<u8> = (0..=255).collect();
+        let options = DecodeOptions { ignore_whitespace: true, ..DecodeOptions::default() };
+        let wrapped = encode_wrapped(&input, 76, LineEnding::CrLf);
+        assert_eq!(decode_with(wrapped, options).unwrap(), input);
+    }
+
+    #[test]
+    fn test_decode_with_allow_missing_padding() {
+        let options = DecodeOptions { allow_missing_padding: true, ..DecodeOptions::default() };
+        assert_eq!(decode_with("0w", options), decode("0w=="));
+        assert_eq!(decode_with("abcd010", options), decode("abcd010="));
+        assert_eq!(decode_with("0w==", options), decode("0w=="));
+        assert_eq!(decode_with("abcde", options), Err(DecodeError::InvalidLength));
+    }
+
+    #[test]
+    fn test_decode_with_allow_trailing_bits() {
+        let options = DecodeOptions { allow_trailing_bits: true, ..DecodeOptions::default() };
+        assert_eq!(decode_with("abcdefC=", options), decode("abcdefA="));
+        assert_eq!(decode_with("abcdeI==", options), decode("abcdeA=="));
+    }
+
     #[test]
     fn test_inverse_property_encoding_to_decoding() {
         let input = [
@@ -0,0 +1,168 @@
+//! Constant-time BASE64 decoding for secret material such as private keys.
+//!
+//! Symbols are mapped to 6-bit values by arithmetic instead of the decode
+//! table, so the memory access pattern doesn't depend on the input. Invalid
+//! symbols are recorded without branching and reported only after the whole
+//! input is processed. The length of input and the number of padding symbols
+//! are not treated as secret.
+
+use super::*;
+
+/// Decode bytes by standard BASE64 in constant time.
+///
+/// The result including errors is the same as `base64::decode()`. The output
+/// buffer is zeroed before returning an error.
+pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, DecodeError> {
+    let input = input.as_ref();
+    if input.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !input.len().is_multiple_of(4) {
+        return Err(DecodeError::InvalidLength);
+    }
+
+    let padding = count_padding(input);
+    let symbols = &input[..input.len()-padding];
+    let mut bytes = Vec::with_capacity(symbols.len() * 3 / 4);
+
+    // masks are all ones for true and all zeros for false
+    let mut invalid: usize = 0;
+    let mut invalid_index: usize = 0;
+    let mut invalid_byte: usize = 0;
+    let mut bit6s = [0; 4];
+    for (index, symbol) in symbols.iter().enumerate() {
+        let value = decode_symbol(*symbol);
+        let is_invalid = ((value >> 31) as isize) as usize;
+        let is_first_invalid = is_invalid & !invalid;
+        invalid_index = select(is_first_invalid, index, invalid_index);
+        invalid_byte = select(is_first_invalid, *symbol as usize, invalid_byte);
+        invalid |= is_invalid;
+
+        bit6s[index % 4] = (value as u8) & (LOW_6_BITS as u8);
+        if index % 4 == 3 {
+            let (byte1, byte2, byte3) = into_3_byte(bit6s[0], bit6s[1], bit6s[2], bit6s[3]);
+            bytes.push(byte1);
+            bytes.push(byte2);
+            bytes.push(byte3);
+        }
+    }
+
+    // the extra bits of the last symbol must be 0
+    let mask = match symbols.len() % 4 {
+        2 => {
+            bytes.push(into_1_byte(bit6s[0], bit6s[1]));
+            0b0000_1111
+        },
+        3 => {
+            let (byte1, byte2) = into_2_byte(bit6s[0], bit6s[1], bit6s[2]);
+            bytes.push(byte1);
+            bytes.push(byte2);
+            0b0000_0011
+        },
+        _ => 0b0000_0000,
+    };
+    let last_index = symbols.len() - 1;
+    let extra_bits = (bit6s[last_index % 4] & mask) as usize;
+    let has_extra_bits = (extra_bits.wrapping_neg() as isize >> (usize::BITS - 1)) as usize;
+
+    // the results are inspected only after processing the whole input
+    if invalid | has_extra_bits != 0 {
+        zeroize(&mut bytes);
+        if invalid != 0 {
+            return Err(DecodeError::InvalidByte(invalid_index, invalid_byte as u8));
+        }
+        return Err(DecodeError::InvalidLastSymbol(last_index, symbols[last_index]));
+    }
+
+    Ok(bytes)
+}
+
+/// Get the 6-bit value of a standard BASE64 symbol, or -1 if it's invalid.
+///
+/// Each range check produces a mask by the sign of subtractions, and only the
+/// matched range adds its offset to the initial -1.
+fn decode_symbol(symbol: u8) -> i32 {
+    let symbol = symbol as i32;
+    let mut value = -1;
+    // 'A'-'Z' => 0-25
+    value += in_range(symbol, b'A', b'Z') & (symbol - b'A' as i32 + 1);
+    // 'a'-'z' => 26-51
+    value += in_range(symbol, b'a', b'z') & (symbol - b'a' as i32 + 26 + 1);
+    // '0'-'9' => 52-61
+    value += in_range(symbol, b'0', b'9') & (symbol - b'0' as i32 + 52 + 1);
+    // '+' => 62
+    value += in_range(symbol, b'+', b'+') & (62 + 1);
+    // '/' => 63
+    value += in_range(symbol, b'/', b'/') & (63 + 1);
+    value
+}
+
+/// Get all ones if `low <= x <= high`, otherwise 0.
+fn in_range(x: i32, low: u8, high: u8) -> i32 {
+    ((low as i32 - 1 - x) & (x - high as i32 - 1)) >> 31
+}
+
+/// Select `a` if the mask is all ones, or `b` if the mask is 0.
+fn select(mask: usize, a: usize, b: usize) -> usize {
+    (a & mask) | (b & !mask)
+}
+
+/// Overwrite bytes by zeros so that partially decoded secret is not left.
+fn zeroize(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        // volatile write is not removed by optimization
+        unsafe { core::ptr::write_volatile(byte, 0) };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_symbol_same_as_table() {
+        for symbol in 0..=255 {
+            let expected = match Alphabet::STANDARD.value(symbol) {
+                INVALID_VALUE => -1,
+                value => value as i32,
+            };
+            assert_eq!(decode_symbol(symbol), expected, "symbol: {}", symbol);
+        }
+    }
+
+    #[test]
+    fn test_decode_every_byte_value_same_as_table() {
+        for symbol in 0..=255u8 {
+            let inputs = [
+                [symbol, symbol, symbol, symbol],
+                [symbol, b'A', b'B', b'C'],
+                [b'A', b'B', b'C', symbol],
+                [b'A', b'B', symbol, PADDING],
+                [b'A', symbol, PADDING, PADDING],
+                [symbol, b'A', PADDING, PADDING],
+            ];
+            for input in inputs {
+                assert_eq!(decode(input), super::super::decode(input), "input: {:?}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_same_as_table() {
+        let inputs = [
+            "", "ABCDabcd0123", "abcd010=", "0w==", "+/+/", "abcdefg", "a!cd",
+            "abcdef=h", "abcdefC=", "abcdeI==", "a!c!", "!bcdeI==",
+        ];
+        for input in inputs {
+            assert_eq!(decode(input), super::super::decode(input), "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_inverse_property_encoding_to_decoding() {
+        let input: Vec<u8> = (0..=255).collect();
+        for size in 0..input.len() {
+            assert_eq!(decode(encode(&input[..size])).unwrap(), &input[..size]);
+        }
+    }
+}
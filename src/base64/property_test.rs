@@ -0,0 +1,127 @@
+//! Property-based tests over random inputs and all alphabets.
+
+use proptest::prelude::*;
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
+use super::*;
+
+/// Strategy of engines including random custom alphabets.
+fn engines() -> impl Strategy<Value = Base64Engine> {
+    let paddings = prop_oneof![
+        Just(Padding::Required),
+        Just(Padding::Optional),
+        Just(Padding::Forbidden),
+    ];
+    let standard_symbols: Vec<u8> = Alphabet::STANDARD.encode_table.to_vec();
+    let alphabets = prop_oneof![
+        Just(Alphabet::STANDARD),
+        Just(Alphabet::URL_SAFE),
+        Just(standard_symbols).prop_shuffle().prop_map(|symbols| {
+            Alphabet::new(std::str::from_utf8(&symbols).unwrap())
+        }),
+    ];
+    (alphabets, paddings).prop_map(|(alphabet, padding)| { Base64Engine::new(alphabet, padding) })
+}
+
+/// Strategy of strings which look like BASE64 with a few invalid symbols.
+fn base64_like() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(
+        prop_oneof![
+            8 => prop::sample::select(Alphabet::STANDARD.encode_table.to_vec()),
+            1 => Just(PADDING),
+            1 => any::<u8>(),
+        ],
+        0..40,
+    )
+}
+
+proptest! {
+    #[test]
+    fn prop_inverse_property_encoding_to_decoding(
+        engine in engines(),
+        input in prop::collection::vec(any::<u8>(), 0..200),
+    ) {
+        let encoded = engine.encode(&input);
+        prop_assert_eq!(encoded.len(), engine.encoded_len(input.len()));
+        prop_assert_eq!(engine.decode(&encoded)?, input);
+    }
+
+    #[test]
+    fn prop_decode_accepts_only_canonical(engine in engines(), input in base64_like()) {
+        // strict decoding must never panic, and accepted input must re-encode
+        // to itself unless padding is optional
+        if let Ok(decoded) = engine.decode(&input) {
+            let encoded = engine.encode(&decoded);
+            match engine.padding() {
+                Padding::Optional => {
+                    prop_assert_eq!(encoded.trim_end_matches('='), std::str::from_utf8(&input).unwrap().trim_end_matches('='));
+                },
+                _ => prop_assert_eq!(encoded.as_bytes(), &input[..]),
+            }
+        }
+    }
+
+    #[test]
+    fn prop_decode_with_never_panics(
+        input in prop::collection::vec(any::<u8>(), 0..60),
+        ignore_whitespace in any::<bool>(),
+        allow_missing_padding in any::<bool>(),
+        allow_trailing_bits in any::<bool>(),
+    ) {
+        let options = DecodeOptions { ignore_whitespace, allow_missing_padding, allow_trailing_bits };
+        let _ = decode_with(&input, options);
+    }
+
+    #[test]
+    fn prop_all_decoders_agree(input in base64_like()) {
+        let expected = decode(&input);
+
+        let mut output = vec![0; decoded_len_estimate(input.len())];
+        let sliced = decode_to_slice(&input, &mut output)
+            .map(|written| { output[..written].to_vec() });
+        prop_assert_eq!(&sliced, &expected);
+
+        prop_assert_eq!(&ct::decode(&input), &expected);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn prop_stream_decoder_agrees(input in base64_like()) {
+        let expected = decode(&input);
+
+        let mut streamed = Vec::new();
+        let result = Decoder::new(&input[..]).read_to_end(&mut streamed);
+        prop_assert_eq!(result.is_ok(), expected.is_ok());
+        if let Ok(expected) = expected {
+            prop_assert_eq!(streamed, expected);
+        }
+    }
+
+    #[test]
+    fn prop_all_encoders_agree(
+        engine in engines(),
+        input in prop::collection::vec(any::<u8>(), 0..200),
+    ) {
+        let expected = engine.encode(&input);
+
+        let mut output = vec![0; engine.encoded_len(input.len())];
+        let written = engine.encode_to_slice(&input, &mut output).unwrap();
+        prop_assert_eq!(&output[..written], expected.as_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn prop_stream_encoder_agrees(
+        engine in engines(),
+        input in prop::collection::vec(any::<u8>(), 0..200),
+        step in 1usize..10,
+    ) {
+        let expected = engine.encode(&input);
+
+        let mut encoder = Encoder::with_engine(Vec::new(), engine);
+        for chunk in input.chunks(step) {
+            encoder.write_all(chunk).unwrap();
+        }
+        prop_assert_eq!(encoder.finish().unwrap(), expected.as_bytes());
+    }
+}
@@ -0,0 +1,229 @@
+use super::*;
+
+/// Encode errors.
+#[derive(Debug, PartialEq)]
+pub enum EncodeError {
+    OutputTooSmall,
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeError::OutputTooSmall => write!(f, "output buffer too small for BASE64"),
+        }
+    }
+}
+
+impl core::error::Error for EncodeError {}
+
+/// Get the length of standard BASE64 symbols encoded from `n` bytes.
+pub fn encoded_len(n: usize) -> usize {
+    STANDARD.encoded_len(n)
+}
+
+/// Get the maximum length of bytes decoded from `n` symbols.
+/// The actual length is 0-2 bytes less than this if the symbols contain
+/// padding.
+pub fn decoded_len_estimate(n: usize) -> usize {
+    n.div_ceil(4) * 3
+}
+
+/// Encode bytes by standard BASE64 into the output buffer.
+/// See `Base64Engine::encode_to_slice()`.
+pub fn encode_to_slice(input: &[u8], output: &mut [u8]) -> Result<usize, EncodeError> {
+    STANDARD.encode_to_slice(input, output)
+}
+
+/// Decode bytes by standard BASE64 into the output buffer.
+/// See `Base64Engine::decode_to_slice()`.
+pub fn decode_to_slice(input: &[u8], output: &mut [u8]) -> Result<usize, DecodeError> {
+    STANDARD.decode_to_slice(input, output)
+}
+
+impl Base64Engine {
+    /// Get the length of symbols encoded from `n` bytes.
+    pub fn encoded_len(&self, n: usize) -> usize {
+        match self.padding {
+            Padding::Forbidden => n / 3 * 4 + match n % 3 {
+                1 => 2,
+                2 => 3,
+                _ => 0,
+            },
+            _ => n.div_ceil(3) * 4,
+        }
+    }
+
+    /// Encode bytes by BASE64 into the output buffer without allocation.
+    /// Returns the number of symbols written, which is `encoded_len()` of the
+    /// input length.
+    pub fn encode_to_slice(&self, input: &[u8], output: &mut [u8]) -> Result<usize, EncodeError> {
+        let symbols_len = self.encoded_len(input.len());
+        if output.len() < symbols_len {
+            return Err(EncodeError::OutputTooSmall);
+        }
+
+        let alphabet = &self.alphabet;
+        let mut out = output.chunks_exact_mut(4);
+        let mut bytes = input.chunks_exact(3);
+        for (bytes, out) in (&mut bytes).zip(&mut out) {
+            let (bit6_1, bit6_2, bit6_3, bit6_4) = into_4_bit6(bytes[0], bytes[1], bytes[2]);
+            out[0] = alphabet.symbol(bit6_1);
+            out[1] = alphabet.symbol(bit6_2);
+            out[2] = alphabet.symbol(bit6_3);
+            out[3] = alphabet.symbol(bit6_4);
+        }
+
+        let offset = input.len() / 3 * 4;
+        let out = &mut output[offset..symbols_len];
+        match *bytes.remainder() {
+            [byte] => {
+                let (bit6_1, bit6_2) = into_2_bit6(byte);
+                out[0] = alphabet.symbol(bit6_1);
+                out[1] = alphabet.symbol(bit6_2);
+                out[2..].fill(PADDING);
+            },
+            [byte1, byte2] => {
+                let (bit6_1, bit6_2, bit6_3) = into_3_bit6(byte1, byte2);
+                out[0] = alphabet.symbol(bit6_1);
+                out[1] = alphabet.symbol(bit6_2);
+                out[2] = alphabet.symbol(bit6_3);
+                out[3..].fill(PADDING);
+            },
+            _ => {},
+        }
+
+        Ok(symbols_len)
+    }
+
+    /// Decode bytes by BASE64 into the output buffer without allocation.
+    /// Returns the number of bytes written.
+    ///
+    /// The input is validated with the same rules as `decode()`. The output
+    /// buffer needs the exact decoded length, and `decoded_len_estimate()` is
+    /// always enough.
+    pub fn decode_to_slice(&self, input: &[u8], output: &mut [u8]) -> Result<usize, DecodeError> {
+        if input.is_empty() {
+            return Ok(0);
+        }
+        validate_decoding_target(input, self, &DecodeOptions::default())?;
+
+        let padding = self.count_padding(input);
+        let symbols = &input[..input.len()-padding];
+        let bytes_len = symbols.len() * 3 / 4;
+        if output.len() < bytes_len {
+            return Err(DecodeError::OutputTooSmall);
+        }
+
+        let alphabet = &self.alphabet;
+        let mut groups = symbols.chunks_exact(4);
+        for (group, out) in (&mut groups).zip(output.chunks_exact_mut(3)) {
+            let (byte1, byte2, byte3) = into_3_byte(
+                alphabet.value(group[0]),
+                alphabet.value(group[1]),
+                alphabet.value(group[2]),
+                alphabet.value(group[3]),
+            );
+            out[0] = byte1;
+            out[1] = byte2;
+            out[2] = byte3;
+        }
+
+        let offset = symbols.len() / 4 * 3;
+        match *groups.remainder() {
+            [symbol1, symbol2] => {
+                output[offset] = into_1_byte(alphabet.value(symbol1), alphabet.value(symbol2));
+            },
+            [symbol1, symbol2, symbol3] => {
+                let (byte1, byte2) = into_2_byte(
+                    alphabet.value(symbol1),
+                    alphabet.value(symbol2),
+                    alphabet.value(symbol3),
+                );
+                output[offset] = byte1;
+                output[offset + 1] = byte2;
+            },
+            _ => {},
+        }
+
+        Ok(bytes_len)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample_bytes(size: usize) -> Vec<u8> {
+        (0..size).map(|i| { (i * 31 + 7) as u8 }).collect()
+    }
+
+    #[test]
+    fn test_encoded_len() {
+        for size in 0..20 {
+            let input = sample_bytes(size);
+            assert_eq!(encoded_len(size), encode(&input).len());
+            assert_eq!(URL_SAFE_NO_PAD.encoded_len(size), URL_SAFE_NO_PAD.encode(&input).len());
+        }
+    }
+
+    #[test]
+    fn test_decoded_len_estimate() {
+        for size in 0..20 {
+            let encoded = encode(sample_bytes(size));
+            let estimate = decoded_len_estimate(encoded.len());
+            assert!(size <= estimate && estimate <= size + 2);
+        }
+    }
+
+    #[test]
+    fn test_encode_to_slice_same_as_encode() {
+        for size in 0..20 {
+            let input = sample_bytes(size);
+            for engine in [STANDARD, URL_SAFE_NO_PAD] {
+                let mut output = [0; 32];
+                let written = engine.encode_to_slice(&input, &mut output).unwrap();
+                assert_eq!(&output[..written], engine.encode(&input).as_bytes());
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_to_slice_output_too_small() {
+        let mut output = [0; 7];
+        assert_eq!(encode_to_slice(&[0; 4], &mut output), Err(EncodeError::OutputTooSmall));
+    }
+
+    #[test]
+    fn test_decode_to_slice_same_as_decode() {
+        for size in 0..20 {
+            let input = sample_bytes(size);
+            for engine in [STANDARD, URL_SAFE_NO_PAD] {
+                let encoded = engine.encode(&input);
+                let mut output = [0; 20];
+                let written = engine.decode_to_slice(encoded.as_bytes(), &mut output).unwrap();
+                assert_eq!(&output[..written], &input[..]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_to_slice_exact_output() {
+        let mut output = [0; 5];
+        assert_eq!(decode_to_slice(b"abcd010=", &mut output), Ok(5));
+        assert_eq!(output, &decode("abcd010=").unwrap()[..]);
+    }
+
+    #[test]
+    fn test_decode_to_slice_output_too_small() {
+        let mut output = [0; 4];
+        assert_eq!(decode_to_slice(b"abcd010=", &mut output), Err(DecodeError::OutputTooSmall));
+    }
+
+    #[test]
+    fn test_decode_to_slice_invalid() {
+        let mut output = [0; 16];
+        assert_eq!(decode_to_slice(b"abcdefg", &mut output), Err(DecodeError::InvalidLength));
+        assert_eq!(decode_to_slice(b"a!cd", &mut output), Err(DecodeError::InvalidByte(1, b'!')));
+        assert_eq!(decode_to_slice(b"abcdeI==", &mut output), Err(DecodeError::InvalidLastSymbol(5, b'I')));
+    }
+}
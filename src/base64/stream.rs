@@ -0,0 +1,365 @@
+use std::io::{self, Read, Write, ErrorKind};
+use std::vec::Vec;
+use super::*;
+
+/// Maximum number of bytes processed at once.
+/// This is a multiple of 3 so that the chunk is encoded without leftover.
+const MAX_CHUNK_SIZE: usize = 768;
+
+/// Streaming BASE64 encoder which writes symbols to the inner writer.
+///
+/// Up to 2 bytes which cannot be encoded yet are carried to the next call of
+/// `write()`, and the padding is flushed by `finish()`. So `finish()` must be
+/// called after writing all bytes, otherwise the last symbols are lost.
+pub struct Encoder<W: Write> {
+    inner: W,
+    engine: Base64Engine,
+    leftover: [u8; 2],
+    leftover_len: usize,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Create an encoder of standard BASE64 writing to `inner`.
+    pub fn new(inner: W) -> Encoder<W> {
+        Encoder::with_engine(inner, STANDARD)
+    }
+
+    /// Create an encoder of the engine writing to `inner`.
+    pub fn with_engine(inner: W, engine: Base64Engine) -> Encoder<W> {
+        Encoder { inner, engine, leftover: [0; 2], leftover_len: 0 }
+    }
+
+    /// Encode the leftover bytes with padding, and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let alphabet = &self.engine.alphabet;
+        let mut symbols = [PADDING; 4];
+        let symbols_len = match self.leftover_len {
+            1 => {
+                let (bit6_1, bit6_2) = into_2_bit6(self.leftover[0]);
+                symbols[0] = alphabet.symbol(bit6_1);
+                symbols[1] = alphabet.symbol(bit6_2);
+                2
+            },
+            2 => {
+                let (bit6_1, bit6_2, bit6_3) = into_3_bit6(self.leftover[0], self.leftover[1]);
+                symbols[0] = alphabet.symbol(bit6_1);
+                symbols[1] = alphabet.symbol(bit6_2);
+                symbols[2] = alphabet.symbol(bit6_3);
+                3
+            },
+            _ => 0,
+        };
+        if symbols_len > 0 {
+            let symbols = match self.engine.padding {
+                Padding::Forbidden => &symbols[..symbols_len],
+                _ => &symbols[..],
+            };
+            self.inner.write_all(symbols)?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        // join the leftover and the head of the input
+        let mut chunk = [0; MAX_CHUNK_SIZE];
+        let consumed = (MAX_CHUNK_SIZE - self.leftover_len).min(buf.len());
+        chunk[..self.leftover_len].copy_from_slice(&self.leftover[..self.leftover_len]);
+        chunk[self.leftover_len..self.leftover_len+consumed].copy_from_slice(&buf[..consumed]);
+        let chunk = &chunk[..self.leftover_len+consumed];
+
+        // encode the bytes of the multiple of 3
+        let encodable = chunk.len() / 3 * 3;
+        let alphabet = &self.engine.alphabet;
+        let mut symbols = [0; MAX_CHUNK_SIZE / 3 * 4];
+        for (bytes, out) in chunk[..encodable].chunks_exact(3).zip(symbols.chunks_exact_mut(4)) {
+            let (bit6_1, bit6_2, bit6_3, bit6_4) = into_4_bit6(bytes[0], bytes[1], bytes[2]);
+            out[0] = alphabet.symbol(bit6_1);
+            out[1] = alphabet.symbol(bit6_2);
+            out[2] = alphabet.symbol(bit6_3);
+            out[3] = alphabet.symbol(bit6_4);
+        }
+        self.inner.write_all(&symbols[..encodable / 3 * 4])?;
+
+        // carry the rest to the next call
+        let rest = &chunk[encodable..];
+        self.leftover[..rest.len()].copy_from_slice(rest);
+        self.leftover_len = rest.len();
+
+        Ok(consumed)
+    }
+
+    /// Flush the inner writer.
+    /// This doesn't write the leftover bytes, use `finish()` instead.
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Streaming BASE64 decoder which reads symbols from the inner reader.
+///
+/// The input is validated with the same rules as `Base64Engine::decode()`.
+/// Since padding is permitted only in the last 4 symbols, the last group is
+/// held back until the inner reader reaches EOF.
+pub struct Decoder<R: Read> {
+    inner: R,
+    engine: Base64Engine,
+    /// Symbols read but not decoded yet.
+    symbols: Vec<u8>,
+    /// Decoded bytes not returned yet.
+    bytes: Vec<u8>,
+    /// Number of symbols already decoded, used for error positions.
+    position: usize,
+    finished: bool,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Create a decoder of standard BASE64 reading from `inner`.
+    pub fn new(inner: R) -> Decoder<R> {
+        Decoder::with_engine(inner, STANDARD)
+    }
+
+    /// Create a decoder of the engine reading from `inner`.
+    pub fn with_engine(inner: R, engine: Base64Engine) -> Decoder<R> {
+        Decoder {
+            inner,
+            engine,
+            symbols: Vec::with_capacity(MAX_CHUNK_SIZE + 4),
+            bytes: Vec::with_capacity(MAX_CHUNK_SIZE),
+            position: 0,
+            finished: false,
+        }
+    }
+
+    /// Return the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Decode the groups of 4 symbols except for the last group.
+    fn decode_middle(&mut self) -> Result<(), DecodeError> {
+        // the last group (or the incomplete group) may be the end of input
+        let alphabet = &self.engine.alphabet;
+        let decodable = (self.symbols.len().saturating_sub(1)) / 4 * 4;
+        for group in self.symbols[..decodable].chunks_exact(4) {
+            // padding is also an invalid byte in the middle of input
+            let invalid_value = group.iter()
+                .enumerate()
+                .find(|(_, value)| { alphabet.value(**value) == INVALID_VALUE });
+            if let Some((index, value)) = invalid_value {
+                return Err(DecodeError::InvalidByte(self.position + index, *value));
+            }
+
+            let (byte1, byte2, byte3) = into_3_byte(
+                alphabet.value(group[0]),
+                alphabet.value(group[1]),
+                alphabet.value(group[2]),
+                alphabet.value(group[3]),
+            );
+            self.bytes.push(byte1);
+            self.bytes.push(byte2);
+            self.bytes.push(byte3);
+            self.position += 4;
+        }
+        self.symbols.drain(..decodable);
+        Ok(())
+    }
+
+    /// Decode the last group at the end of input.
+    fn decode_last(&mut self) -> Result<(), DecodeError> {
+        if self.symbols.is_empty() {
+            return Ok(());
+        }
+        validate_decoding_target(&self.symbols, &self.engine, &DecodeOptions::default())
+            .map_err(|e| { e.map_index(|index| { index + self.position }) })?;
+
+        let padding = self.engine.count_padding(&self.symbols);
+        let bit6s: Vec<_> = self.symbols[..self.symbols.len()-padding].iter()
+            .map(|symbol| { self.engine.alphabet.value(*symbol) })
+            .collect();
+        self.bytes.extend(into_bytes(&bit6s));
+        self.position += self.symbols.len();
+        self.symbols.clear();
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            // return decoded bytes first
+            if !self.bytes.is_empty() {
+                let size = buf.len().min(self.bytes.len());
+                buf[..size].copy_from_slice(&self.bytes[..size]);
+                self.bytes.drain(..size);
+                return Ok(size);
+            }
+            if self.finished || buf.is_empty() {
+                return Ok(0);
+            }
+
+            let mut chunk = [0; MAX_CHUNK_SIZE];
+            let read_size = match self.inner.read(&mut chunk) {
+                Ok(size) => size,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            let result = if read_size == 0 {
+                self.finished = true;
+                self.decode_middle().and_then(|_| { self.decode_last() })
+            } else {
+                self.symbols.extend_from_slice(&chunk[..read_size]);
+                self.decode_middle()
+            };
+            result.map_err(|e| {
+                io::Error::new(ErrorKind::InvalidData, e)
+            })?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Reader returning at most `limit` bytes per call.
+    struct SlowReader<'a> {
+        data: &'a [u8],
+        limit: usize,
+    }
+
+    impl<'a> Read for SlowReader<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let size = self.limit.min(buf.len()).min(self.data.len());
+            buf[..size].copy_from_slice(&self.data[..size]);
+            self.data = &self.data[size..];
+            Ok(size)
+        }
+    }
+
+    /// Encode the input by writing it `step` bytes at a time.
+    fn encode_by_step(input: &[u8], step: usize) -> String {
+        let mut encoder = Encoder::new(Vec::new());
+        for chunk in input.chunks(step) {
+            encoder.write_all(chunk).unwrap();
+        }
+        String::from_utf8(encoder.finish().unwrap()).unwrap()
+    }
+
+    /// Decode the input by reading it `step` bytes at a time.
+    fn decode_by_step(input: &[u8], step: usize) -> io::Result<Vec<u8>> {
+        let mut decoder = Decoder::new(SlowReader { data: input, limit: step });
+        let mut output = Vec::new();
+        decoder.read_to_end(&mut output)?;
+        Ok(output)
+    }
+
+    fn sample_bytes(size: usize) -> Vec<u8> {
+        (0..size).map(|i| { (i * 7 + i / 256) as u8 }).collect()
+    }
+
+    #[test]
+    fn test_encoder_same_as_encode() {
+        for size in [0, 1, 2, 3, 4, 5, 767, 768, 769, 3000] {
+            let input = sample_bytes(size);
+            for step in [1, 2, 3, 5, 1000] {
+                assert_eq!(encode_by_step(&input, step), encode(&input));
+            }
+        }
+    }
+
+    #[test]
+    fn test_encoder_empty() {
+        let encoder = Encoder::new(Vec::new());
+        assert_eq!(encoder.finish().unwrap(), b"");
+    }
+
+    #[test]
+    fn test_decoder_same_as_decode() {
+        for size in [0, 1, 2, 3, 4, 5, 767, 768, 769, 3000] {
+            let input = encode(sample_bytes(size));
+            for step in [1, 2, 3, 4, 5, 1000] {
+                let output = decode_by_step(input.as_bytes(), step).unwrap();
+                assert_eq!(output, decode(&input).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_decoder_small_buffer() {
+        let input = sample_bytes(100);
+        let encoded = encode(&input);
+        let mut decoder = Decoder::new(encoded.as_bytes());
+        let mut output = Vec::new();
+        let mut buf = [0; 7];
+        loop {
+            let size = decoder.read(&mut buf).unwrap();
+            if size == 0 {
+                break;
+            }
+            output.extend_from_slice(&buf[..size]);
+        }
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_stream_with_engine() {
+        for size in [0, 1, 2, 3, 100] {
+            let input = sample_bytes(size);
+            let mut encoder = Encoder::with_engine(Vec::new(), URL_SAFE_NO_PAD);
+            encoder.write_all(&input).unwrap();
+            let encoded = encoder.finish().unwrap();
+            assert_eq!(encoded, URL_SAFE_NO_PAD.encode(&input).as_bytes());
+
+            let mut decoder = Decoder::with_engine(&encoded[..], URL_SAFE_NO_PAD);
+            let mut output = Vec::new();
+            decoder.read_to_end(&mut output).unwrap();
+            assert_eq!(output, input);
+        }
+    }
+
+    /// Get the decode error wrapped in the I/O error.
+    fn decode_error_by_step(input: &str, step: usize) -> DecodeError {
+        let error = decode_by_step(input.as_bytes(), step).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        *error.into_inner().unwrap().downcast::<DecodeError>().unwrap()
+    }
+
+    #[test]
+    fn test_decoder_invalid_length() {
+        for step in [1, 3, 100] {
+            let output = decode_error_by_step("abcdefg", step);
+            assert_eq!(output, DecodeError::InvalidLength);
+        }
+    }
+
+    #[test]
+    fn test_decoder_invalid_byte() {
+        for step in [1, 3, 100] {
+            let output = decode_error_by_step("abcdefgha!cd", step);
+            assert_eq!(output, DecodeError::InvalidByte(9, b'!'));
+        }
+    }
+
+    #[test]
+    fn test_decoder_invalid_padding_in_middle() {
+        for step in [1, 3, 100] {
+            let output = decode_error_by_step("abc=abcd", step);
+            assert_eq!(output, DecodeError::InvalidByte(3, b'='));
+        }
+    }
+
+    #[test]
+    fn test_decoder_invalid_last_symbol() {
+        for step in [1, 3, 100] {
+            let output = decode_error_by_step("abcdabcdeI==", step);
+            assert_eq!(output, DecodeError::InvalidLastSymbol(9, b'I'));
+        }
+    }
+}
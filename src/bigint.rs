@@ -0,0 +1,945 @@
+//! Arbitrary-precision unsigned integers for public-key cryptography.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, Sub, Mul, Div, Rem, Shl, Shr};
+use crate::rng::Rng;
+
+mod montgomery;
+mod prime;
+#[cfg(test)]
+mod property_test;
+
+pub(crate) use montgomery::Montgomery;
+pub(crate) use prime::generate_prime;
+
+type Limb = u64;
+type DoubleLimb = u128;
+
+const LIMB_BITS: usize = 64;
+const LIMB_BYTES: usize = 8;
+
+/// Arbitrary-precision unsigned integer.
+///
+/// The value is held as 64-bit limbs in little-endian order, and the most
+/// significant limb is never 0 (zero has no limbs).
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    limbs: Vec<Limb>,
+}
+
+impl BigUint {
+    /// Get 0.
+    pub fn zero() -> BigUint {
+        BigUint { limbs: Vec::new() }
+    }
+
+    /// Get 1.
+    pub fn one() -> BigUint {
+        BigUint::from(1u64)
+    }
+
+    /// Create from limbs in little-endian order.
+    fn from_limbs(mut limbs: Vec<Limb>) -> BigUint {
+        normalize(&mut limbs);
+        BigUint { limbs }
+    }
+
+    /// Create from big-endian bytes.
+    pub fn from_bytes_be(bytes: &[u8]) -> BigUint {
+        let limbs = bytes.rchunks(LIMB_BYTES)
+            .map(|chunk| {
+                chunk.iter().fold(0, |limb, byte| { (limb << 8) | *byte as Limb })
+            })
+            .collect();
+        BigUint::from_limbs(limbs)
+    }
+
+    /// Create from a hexadecimal string.
+    /// Whitespace is ignored, e.g. to write long constants in multiple lines.
+    /// Returns `None` if the string contains other characters.
+    pub fn from_hex(hex: &str) -> Option<BigUint> {
+        let digits: Vec<u8> = hex.bytes()
+            .filter(|digit| { !digit.is_ascii_whitespace() })
+            .collect();
+        let mut limbs = Vec::with_capacity(digits.len().div_ceil(16));
+        for chunk in digits.rchunks(16) {
+            let mut limb = 0;
+            for digit in chunk {
+                let value = (*digit as char).to_digit(16)?;
+                limb = (limb << 4) | value as Limb;
+            }
+            limbs.push(limb);
+        }
+        Some(BigUint::from_limbs(limbs))
+    }
+
+    /// Convert into big-endian bytes without leading zeros.
+    /// Zero is converted into a single zero byte.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let len = self.bits().div_ceil(8).max(1);
+        self.to_bytes_be_padded(len).unwrap()
+    }
+
+    /// Convert into big-endian bytes of the length with leading zeros, e.g. for
+    /// I2OSP of PKCS #1.
+    /// Returns `None` if the value doesn't fit in the length.
+    pub fn to_bytes_be_padded(&self, len: usize) -> Option<Vec<u8>> {
+        if self.bits() > len * 8 {
+            return None;
+        }
+        let mut bytes = vec![0; len];
+        for (index, byte) in bytes.iter_mut().rev().enumerate() {
+            let limb = self.limbs.get(index / LIMB_BYTES).copied().unwrap_or(0);
+            *byte = (limb >> ((index % LIMB_BYTES) * 8)) as u8;
+        }
+        Some(bytes)
+    }
+
+    /// Get the number of significant bits.
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(last) => self.limbs.len() * LIMB_BITS - last.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Get the bit at the index, where the index 0 is the least significant.
+    pub fn bit(&self, index: usize) -> bool {
+        self.limbs.get(index / LIMB_BITS)
+            .map(|limb| { (limb >> (index % LIMB_BITS)) & 1 == 1 })
+            .unwrap_or(false)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.limbs == [1]
+    }
+
+    pub fn is_even(&self) -> bool {
+        !self.bit(0)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.bit(0)
+    }
+
+    /// Get the value as `u64` if it fits.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.len() {
+            0 => Some(0),
+            1 => Some(self.limbs[0]),
+            _ => None,
+        }
+    }
+
+    /// Subtract, or return `None` if the result is negative.
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if self < other {
+            return None;
+        }
+        let mut limbs = self.limbs.clone();
+        sub_assign_limbs(&mut limbs, &other.limbs);
+        Some(BigUint::from_limbs(limbs))
+    }
+
+    /// Divide and get the quotient and the remainder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the divisor is 0.
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+        if self < divisor {
+            return (BigUint::zero(), self.clone());
+        }
+        if divisor.limbs.len() == 1 {
+            let (quotient, remainder) = div_rem_limb(&self.limbs, divisor.limbs[0]);
+            return (BigUint::from_limbs(quotient), BigUint::from(remainder));
+        }
+        let (quotient, remainder) = div_rem_limbs(&self.limbs, &divisor.limbs);
+        (BigUint::from_limbs(quotient), BigUint::from_limbs(remainder))
+    }
+
+    /// Calculate `self ^ exponent mod modulus`.
+    ///
+    /// Odd moduli, which all of RSA and DH moduli are, use Montgomery form with
+    /// a fixed window, whose memory access doesn't depend on the exponent bits.
+    /// Even moduli fall back to the plain square-and-multiply.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the modulus is 0.
+    pub fn modpow(&self, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+        assert!(!modulus.is_zero(), "attempt to calculate modulo zero");
+        if modulus.is_one() {
+            return BigUint::zero();
+        }
+        if modulus.is_odd() {
+            return Montgomery::new(modulus).modpow(self, exponent);
+        }
+
+        let mut result = BigUint::one();
+        let base = self % modulus;
+        for index in (0..exponent.bits()).rev() {
+            result = &(&result * &result) % modulus;
+            if exponent.bit(index) {
+                result = &(&result * &base) % modulus;
+            }
+        }
+        result
+    }
+
+    /// Get the greatest common divisor.
+    pub fn gcd(&self, other: &BigUint) -> BigUint {
+        let mut a = self.clone();
+        let mut b = other.clone();
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = b;
+            b = r;
+        }
+        a
+    }
+
+    /// Get the inverse modulo `modulus` by the extended Euclidean algorithm.
+    /// Returns `None` if the inverse doesn't exist, i.e. they are not coprime.
+    pub fn mod_inverse(&self, modulus: &BigUint) -> Option<BigUint> {
+        if modulus.is_zero() || modulus.is_one() {
+            return None;
+        }
+
+        // the coefficients are kept in [0, modulus) to avoid negative values
+        let (mut old_r, mut r) = (self % modulus, modulus.clone());
+        let (mut old_s, mut s) = (BigUint::one(), BigUint::zero());
+        while !r.is_zero() {
+            let (q, next_r) = old_r.div_rem(&r);
+            let qs = &(&q * &s) % modulus;
+            let next_s = &(&old_s + &(modulus - &qs)) % modulus;
+            old_r = core::mem::replace(&mut r, next_r);
+            old_s = core::mem::replace(&mut s, next_s);
+        }
+
+        if old_r.is_one() {
+            Some(old_s)
+        } else {
+            None
+        }
+    }
+
+    /// Get a uniformly random value less than the bound by rejection
+    /// sampling.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bound is 0.
+    pub fn random_below<R: Rng + ?Sized>(bound: &BigUint, rng: &mut R) -> BigUint {
+        assert!(!bound.is_zero(), "attempt to sample below zero");
+        let bits = bound.bits();
+        let mut bytes = vec![0; bits.div_ceil(8)];
+        loop {
+            rng.fill_bytes(&mut bytes);
+            // clear the bits above the bound, so that less than half are rejected
+            bytes[0] &= 0xff >> (bytes.len() * 8 - bits);
+            let value = BigUint::from_bytes_be(&bytes);
+            if &value < bound {
+                return value;
+            }
+        }
+    }
+
+    /// Get the limbs in little-endian order.
+    pub(crate) fn limbs(&self) -> &[Limb] {
+        &self.limbs
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> BigUint {
+        BigUint::from_limbs(vec![value])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering {
+        cmp_limbs(&self.limbs, &other.limbs)
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BigUint(0x{:x})", self)
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut hex = String::with_capacity(self.limbs.len() * 16);
+        for (index, limb) in self.limbs.iter().rev().enumerate() {
+            if index == 0 {
+                hex.push_str(&alloc::format!("{:x}", limb));
+            } else {
+                hex.push_str(&alloc::format!("{:016x}", limb));
+            }
+        }
+        if hex.is_empty() {
+            hex.push('0');
+        }
+        f.pad_integral(true, "0x", &hex)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // divide by the largest power of 10 in a limb
+        const CHUNK_DIVISOR: Limb = 10_000_000_000_000_000_000;
+        let mut chunks = Vec::new();
+        let mut limbs = self.limbs.clone();
+        while !limbs.is_empty() {
+            let (quotient, remainder) = div_rem_limb(&limbs, CHUNK_DIVISOR);
+            chunks.push(remainder);
+            limbs = quotient;
+            normalize(&mut limbs);
+        }
+
+        let mut decimal = String::with_capacity(chunks.len() * 19);
+        for (index, chunk) in chunks.iter().rev().enumerate() {
+            if index == 0 {
+                decimal.push_str(&alloc::format!("{}", chunk));
+            } else {
+                decimal.push_str(&alloc::format!("{:019}", chunk));
+            }
+        }
+        if decimal.is_empty() {
+            decimal.push('0');
+        }
+        f.pad_integral(true, "", &decimal)
+    }
+}
+
+/// Implement a binary operator for all combinations of owned and borrowed
+/// operands by the function on borrowed ones.
+macro_rules! impl_binary_op {
+    ($op:ident, $method:ident, $f:expr) => {
+        impl<'a, 'b> $op<&'b BigUint> for &'a BigUint {
+            type Output = BigUint;
+            fn $method(self, other: &'b BigUint) -> BigUint {
+                $f(self, other)
+            }
+        }
+
+        impl<'b> $op<&'b BigUint> for BigUint {
+            type Output = BigUint;
+            fn $method(self, other: &'b BigUint) -> BigUint {
+                $f(&self, other)
+            }
+        }
+
+        impl<'a> $op<BigUint> for &'a BigUint {
+            type Output = BigUint;
+            fn $method(self, other: BigUint) -> BigUint {
+                $f(self, &other)
+            }
+        }
+
+        impl $op<BigUint> for BigUint {
+            type Output = BigUint;
+            fn $method(self, other: BigUint) -> BigUint {
+                $f(&self, &other)
+            }
+        }
+    };
+}
+
+impl_binary_op!(Add, add, |a: &BigUint, b: &BigUint| {
+    let (long, short) = if a.limbs.len() >= b.limbs.len() { (a, b) } else { (b, a) };
+    let mut limbs = long.limbs.clone();
+    let carry = add_assign_limbs(&mut limbs, &short.limbs);
+    if carry != 0 {
+        limbs.push(carry);
+    }
+    BigUint::from_limbs(limbs)
+});
+
+impl_binary_op!(Sub, sub, |a: &BigUint, b: &BigUint| {
+    a.checked_sub(b).expect("attempt to subtract with overflow")
+});
+
+impl_binary_op!(Mul, mul, |a: &BigUint, b: &BigUint| {
+    BigUint::from_limbs(mul_limbs(&a.limbs, &b.limbs))
+});
+
+impl_binary_op!(Div, div, |a: &BigUint, b: &BigUint| {
+    a.div_rem(b).0
+});
+
+impl_binary_op!(Rem, rem, |a: &BigUint, b: &BigUint| {
+    a.div_rem(b).1
+});
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+    fn shl(self, shift: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0; shift / LIMB_BITS];
+        limbs.extend_from_slice(&self.limbs);
+        limbs.push(0);
+        shl_assign_bits(&mut limbs[shift / LIMB_BITS..], (shift % LIMB_BITS) as u32);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+    fn shl(self, shift: usize) -> BigUint {
+        &self << shift
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+    fn shr(self, shift: usize) -> BigUint {
+        if shift / LIMB_BITS >= self.limbs.len() {
+            return BigUint::zero();
+        }
+        let mut limbs = self.limbs[shift / LIMB_BITS..].to_vec();
+        shr_assign_bits(&mut limbs, (shift % LIMB_BITS) as u32);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+    fn shr(self, shift: usize) -> BigUint {
+        &self >> shift
+    }
+}
+
+/// Remove the most significant zero limbs.
+fn normalize(limbs: &mut Vec<Limb>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+/// Compare normalized limbs.
+fn cmp_limbs(a: &[Limb], b: &[Limb]) -> Ordering {
+    a.len().cmp(&b.len())
+        .then_with(|| { a.iter().rev().cmp(b.iter().rev()) })
+}
+
+/// Add `b` to `a` in place, where `a` is not shorter than `b`.
+/// Returns the carry out of `a`.
+fn add_assign_limbs(a: &mut [Limb], b: &[Limb]) -> Limb {
+    let mut carry = 0;
+    for (index, limb) in a.iter_mut().enumerate() {
+        if index >= b.len() && carry == 0 {
+            break;
+        }
+        let sum = *limb as DoubleLimb + b.get(index).copied().unwrap_or(0) as DoubleLimb + carry;
+        *limb = sum as Limb;
+        carry = sum >> LIMB_BITS;
+    }
+    carry as Limb
+}
+
+/// Subtract `b` from `a` in place, where `a` is not shorter than `b`.
+/// Returns the borrow out of `a`, which is 1 if `a < b`.
+fn sub_assign_limbs(a: &mut [Limb], b: &[Limb]) -> Limb {
+    let mut borrow = 0;
+    for (index, limb) in a.iter_mut().enumerate() {
+        if index >= b.len() && borrow == 0 {
+            break;
+        }
+        let (diff, borrow1) = limb.overflowing_sub(b.get(index).copied().unwrap_or(0));
+        let (diff, borrow2) = diff.overflowing_sub(borrow);
+        *limb = diff;
+        borrow = (borrow1 | borrow2) as Limb;
+    }
+    borrow
+}
+
+/// Multiply limbs by the schoolbook method.
+fn mul_limbs(a: &[Limb], b: &[Limb]) -> Vec<Limb> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut product = vec![0; a.len() + b.len()];
+    for (i, a_limb) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, b_limb) in b.iter().enumerate() {
+            let t = *a_limb as DoubleLimb * *b_limb as DoubleLimb
+                + product[i + j] as DoubleLimb
+                + carry;
+            product[i + j] = t as Limb;
+            carry = t >> LIMB_BITS;
+        }
+        product[i + b.len()] = carry as Limb;
+    }
+    product
+}
+
+/// Shift limbs to the left in place by less than a limb.
+/// The bits shifted out of the most significant limb are lost.
+fn shl_assign_bits(limbs: &mut [Limb], shift: u32) {
+    if shift == 0 {
+        return;
+    }
+    for index in (0..limbs.len()).rev() {
+        let low = if index > 0 { limbs[index - 1] >> (LIMB_BITS as u32 - shift) } else { 0 };
+        limbs[index] = (limbs[index] << shift) | low;
+    }
+}
+
+/// Shift limbs to the right in place by less than a limb.
+fn shr_assign_bits(limbs: &mut [Limb], shift: u32) {
+    if shift == 0 {
+        return;
+    }
+    for index in 0..limbs.len() {
+        let high = limbs.get(index + 1).map(|limb| { limb << (LIMB_BITS as u32 - shift) }).unwrap_or(0);
+        limbs[index] = (limbs[index] >> shift) | high;
+    }
+}
+
+/// Divide limbs by a single limb.
+fn div_rem_limb(a: &[Limb], divisor: Limb) -> (Vec<Limb>, Limb) {
+    let mut quotient = vec![0; a.len()];
+    let mut remainder: DoubleLimb = 0;
+    for (index, limb) in a.iter().enumerate().rev() {
+        let dividend = (remainder << LIMB_BITS) | *limb as DoubleLimb;
+        quotient[index] = (dividend / divisor as DoubleLimb) as Limb;
+        remainder = dividend % divisor as DoubleLimb;
+    }
+    (quotient, remainder as Limb)
+}
+
+/// Divide limbs by the Knuth's algorithm D (TAOCP vol. 2, 4.3.1).
+/// The divisor must have at least 2 limbs and not be greater than `a`.
+fn div_rem_limbs(a: &[Limb], divisor: &[Limb]) -> (Vec<Limb>, Vec<Limb>) {
+    let n = divisor.len();
+    let m = a.len() - n;
+
+    // normalize so that the most significant bit of the divisor is set
+    let shift = divisor[n - 1].leading_zeros();
+    let mut v = divisor.to_vec();
+    shl_assign_bits(&mut v, shift);
+    let mut u = a.to_vec();
+    u.push(0);
+    shl_assign_bits(&mut u, shift);
+
+    let base: DoubleLimb = 1 << LIMB_BITS;
+    let mut quotient = vec![0; m + 1];
+    for j in (0..=m).rev() {
+        // estimate the quotient digit from the top 2 limbs
+        let dividend = ((u[j + n] as DoubleLimb) << LIMB_BITS) | u[j + n - 1] as DoubleLimb;
+        let mut qhat = dividend / v[n - 1] as DoubleLimb;
+        let mut rhat = dividend % v[n - 1] as DoubleLimb;
+        while qhat >= base
+            || qhat * v[n - 2] as DoubleLimb > ((rhat << LIMB_BITS) | u[j + n - 2] as DoubleLimb)
+        {
+            qhat -= 1;
+            rhat += v[n - 1] as DoubleLimb;
+            if rhat >= base {
+                break;
+            }
+        }
+
+        // multiply and subtract
+        let mut borrow: i128 = 0;
+        let mut carry: DoubleLimb = 0;
+        for i in 0..n {
+            let product = qhat * v[i] as DoubleLimb + carry;
+            carry = product >> LIMB_BITS;
+            let t = u[i + j] as i128 - borrow - (product as Limb) as i128;
+            u[i + j] = t as Limb;
+            borrow = (t < 0) as i128;
+        }
+        let t = u[j + n] as i128 - borrow - carry as i128;
+        u[j + n] = t as Limb;
+
+        // add back if the estimation was too large by 1
+        if t < 0 {
+            qhat -= 1;
+            let carry = add_assign_limbs(&mut u[j..j + n], &v);
+            u[j + n] = u[j + n].wrapping_add(carry);
+        }
+        quotient[j] = qhat as Limb;
+    }
+
+    // unnormalize the remainder
+    u.truncate(n);
+    shr_assign_bits(&mut u, shift);
+    (quotient, u)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use crate::rng::ChaChaRng;
+    use crate::test_util::biguint;
+
+    #[test]
+    fn test_bytes_be_conversion() {
+        let bytes = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09];
+        let value = BigUint::from_bytes_be(&bytes);
+        assert_eq!(value, biguint("010203040506070809"));
+        assert_eq!(value.to_bytes_be(), bytes);
+        assert_eq!(value.to_bytes_be_padded(11).unwrap(), [0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(value.to_bytes_be_padded(8), None);
+    }
+
+    #[test]
+    fn test_bytes_be_leading_zeros() {
+        let value = BigUint::from_bytes_be(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0x12]);
+        assert_eq!(value, BigUint::from(0x12));
+        assert_eq!(BigUint::from_bytes_be(&[]), BigUint::zero());
+        assert_eq!(BigUint::zero().to_bytes_be(), [0]);
+    }
+
+    #[test]
+    fn test_from_hex() {
+        assert_eq!(biguint("0"), BigUint::zero());
+        assert_eq!(biguint("ff"), BigUint::from(255));
+        assert_eq!(biguint("1 0000000000000000"), &BigUint::one() << 64);
+        assert_eq!(BigUint::from_hex("xyz"), None);
+    }
+
+    #[test]
+    fn test_format() {
+        let value = biguint("123456789abcdef0123456789abcdef");
+        assert_eq!(alloc::format!("{:x}", value), "123456789abcdef0123456789abcdef");
+        assert_eq!(alloc::format!("{:#x}", value), "0x123456789abcdef0123456789abcdef");
+        assert_eq!(value.to_string(), "1512366075204170929049582354406559215");
+        assert_eq!(BigUint::zero().to_string(), "0");
+    }
+
+    #[test]
+    fn test_bits() {
+        assert_eq!(BigUint::zero().bits(), 0);
+        assert_eq!(BigUint::one().bits(), 1);
+        assert_eq!(biguint("10000000000000000").bits(), 65);
+        assert!(biguint("10000000000000000").bit(64));
+        assert!(!biguint("10000000000000000").bit(63));
+    }
+
+    #[test]
+    fn test_add_with_carry() {
+        let a = biguint("ffffffffffffffffffffffffffffffff");
+        assert_eq!(&a + &BigUint::one(), biguint("100000000000000000000000000000000"));
+        assert_eq!(BigUint::one() + &a, biguint("100000000000000000000000000000000"));
+    }
+
+    #[test]
+    fn test_sub_with_borrow() {
+        let a = biguint("100000000000000000000000000000000");
+        assert_eq!(&a - &BigUint::one(), biguint("ffffffffffffffffffffffffffffffff"));
+        assert_eq!(BigUint::one().checked_sub(&a), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sub_overflow() {
+        let _ = BigUint::one() - biguint("10000000000000000");
+    }
+
+    #[test]
+    fn test_mul() {
+        let a = biguint("ffffffffffffffffffffffffffffffff");
+        assert_eq!(&a * &a, biguint("fffffffffffffffffffffffffffffffe00000000000000000000000000000001"));
+        assert_eq!(&a * &BigUint::zero(), BigUint::zero());
+    }
+
+    #[test]
+    fn test_shift() {
+        let a = biguint("123456789abcdef0123456789abcdef");
+        assert_eq!(&a << 4, biguint("123456789abcdef0123456789abcdef0"));
+        assert_eq!(&a << 68, biguint("123456789abcdef0123456789abcdef00000000000000000"));
+        assert_eq!(&a >> 4, biguint("123456789abcdef0123456789abcde"));
+        assert_eq!(&a >> 68, biguint("123456789abcde"));
+        assert_eq!(&a >> 200, BigUint::zero());
+    }
+
+    #[test]
+    fn test_div_rem_single_limb() {
+        let (q, r) = biguint("123456789abcdef0123456789abcdef").div_rem(&BigUint::from(10));
+        assert_eq!(q, biguint("1d208a5a912e31801d208a5a912e31"));
+        assert_eq!(r, BigUint::from(5));
+    }
+
+    #[test]
+    fn test_div_rem_multiple_limbs() {
+        // generated by Python
+        let a = biguint(concat!(
+            "c8a7f2e1bd93a4d0f6e1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f7",
+            "08192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f7",
+        ));
+        let b = biguint("fedcba9876543210fedcba9876543210fedcba98765432");
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(q, biguint(concat!(
+            "c98d451be42328fa325223ab426c9d3b3920c4719dacf2ac936b7d9f52172c4e",
+            "5a1c1656b607d4eda5",
+        )));
+        assert_eq!(r, biguint("d57db96be97a0eb6fd5534b2534891dc8e7972dd3758bd"));
+        assert_eq!(&(&q * &b) + &r, a);
+    }
+
+    #[test]
+    fn test_div_rem_add_back() {
+        // the case where the estimated quotient digit is too large
+        // (TAOCP vol. 2, 4.3.1 exercise 21)
+        let a = biguint("7fffffffffffffff800000000000000000000000000000000000000000000000");
+        let b = biguint("800000000000000000000000000000000000000000000001");
+        let (q, r) = a.div_rem(&b);
+        assert!(r < b);
+        assert_eq!(&(&q * &b) + &r, a);
+    }
+
+    #[test]
+    fn test_div_rem_small_dividend() {
+        let (q, r) = BigUint::from(5).div_rem(&biguint("10000000000000000"));
+        assert_eq!(q, BigUint::zero());
+        assert_eq!(r, BigUint::from(5));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_div_by_zero() {
+        let _ = BigUint::one() / BigUint::zero();
+    }
+
+    #[test]
+    fn test_modpow_textbook_rsa() {
+        // p = 61, q = 53, e = 17, d = 2753
+        let n = BigUint::from(3233);
+        let c = BigUint::from(65).modpow(&BigUint::from(17), &n);
+        assert_eq!(c, BigUint::from(2790));
+        let m = c.modpow(&BigUint::from(2753), &n);
+        assert_eq!(m, BigUint::from(65));
+    }
+
+    #[test]
+    fn test_modpow_even_modulus() {
+        let result = BigUint::from(3).modpow(&BigUint::from(200), &BigUint::from(1 << 20));
+        assert_eq!(result, BigUint::from(0x8b0a1));
+        let result = BigUint::from(3).modpow(&BigUint::from(200), &biguint("100000000000000000000"));
+        assert_eq!(result, biguint("ae225bfaff1eaaf8b0a1"));
+    }
+
+    #[test]
+    fn test_modpow_edge_cases() {
+        let m = BigUint::from(97);
+        assert_eq!(BigUint::from(5).modpow(&BigUint::zero(), &m), BigUint::one());
+        assert_eq!(BigUint::zero().modpow(&BigUint::from(5), &m), BigUint::zero());
+        assert_eq!(BigUint::from(5).modpow(&BigUint::from(5), &BigUint::one()), BigUint::zero());
+        assert_eq!(BigUint::from(200).modpow(&BigUint::one(), &m), BigUint::from(6));
+    }
+
+    /// RSA-2048 key generated by OpenSSL, and "The quick brown fox jumps over
+    /// the lazy dog" encrypted by textbook RSA.
+    mod rsa2048 {
+        pub const E: u64 = 65537;
+        pub const N: &str = concat!(
+            "ceaac860296132f30782073f0ca78f0ec7a966987c3fca2e057a792dd669582f",
+            "19fd9ae9280f101df0b1097b3e0c1665adb8932b13f4c692e7566db4b0d32e58",
+            "99e1f5751356f306ada3e981cd534dbd3360cee65af282241b85a4123675d0aa",
+            "8d1893ec868127d2e0ec8d45a483289e1793e3c2ff6a24c4ce7da6ed7c9408b4",
+            "f7edede8bfe140de055c1abfe4eeba03341d4e96f08d42c1ab3801055a385051",
+            "4d5133722dc480119a45c51fb9ede8243ec5066d2f1cfee67481e6e51b151fb4",
+            "5391f2c435c3d20441ba5647dbe18cbf1bc88097f75310555e6b2cad8a1ce88c",
+            "1da3f5589f0a30f37f0a098ee10c6e458280b6126938f7dba932f72360f4b251",
+        );
+        pub const D: &str = concat!(
+            "3dc6b6c97595125c9f5bd130385b33375c5f223faf769eecc148c2766b1b1241",
+            "1a83ccb22e78091027070da9ede2290084f976a2e3c1f21396070f0d310b13e7",
+            "e217cdb53d8c9b92943ca7e58693232fd8e2a98862e433274298ed10e62a69e0",
+            "2fd31cc61d258a84f89eb109b29120ab76792c52e0b116c680a9d1c237d05174",
+            "1848a625295cd5944bb8eeb7339fc1c29632732a29a88975e8e5d5c46500bb3b",
+            "8484b9604a401dbbc2d08bc3bd7f51096b26635cff91d9d60be1bda170ec52a4",
+            "1837ff7bfa00870c4e4e325b01aee5cd84c4ee56088c9f044d575f3365cb4daa",
+            "d57e4351573d4d19b16b1bd232cb72d59a5f8bda8802c7ca18bad06e8dc73c1",
+        );
+        pub const P: &str = concat!(
+            "fc0c9decffabc460853b8d688be3e8ee1e375d3c5193f31d38c5495883d2603c",
+            "284a9b2a5aac7ca5673fc8d55c17ff36389decf15334a2b5e2c6e18df102ddea",
+            "4a0ea003ce54579eac3c4e5160637ff86775a66198bcf958f727fd43ada5938b",
+            "f047bc50ae0171efa21c2ef64bdc5aa342b5141cb25a6f2ed5537d0ea9dd2a33",
+        );
+        pub const Q: &str = concat!(
+            "d1e81042cbe4416c7f6fc232bcbea63819117830449a931b8b88c231e2519a8b",
+            "c9f0ead565c9647df96f0df26e0135adea20bca5426782a863d6d07d6d09a5db",
+            "1eb10d554738e73140acefed5bfaf1faff31a59bce29cde2373ab7b741ccc349",
+            "a2638b8b93baedfcdf7eafbd26a46f0a42c3ce1fd450d39411b2b14be09ab56b",
+        );
+        pub const QINV: &str = concat!(
+            "d2c1a8ee60a998856979b90a13caaf358cda34cf56f798f79113531b76b00e3a",
+            "ba69b1c8b53d96203b87fbb5c5d02c64b3b723c9ea68704cf8c724dd0fc60ed9",
+            "74343db3b344260eea6de53d70c8e12da5834efb56c82ae6927d8d5584f13579",
+            "66ebfbbbaa4d33a06c20ceb37be78a8e9c0f6c007594f8629e86bd7ee17617f2",
+        );
+        pub const MESSAGE: &[u8] = b"The quick brown fox jumps over the lazy dog";
+        pub const CIPHERTEXT: &str = concat!(
+            "6241db9ae161cfc756df80fbf1bb80a44f54aa24738d745d170657668b212500",
+            "0d043a6e3e64b7690815d5e8276e33d1412e9bf740e38df05b1fc48f741e5cea",
+            "45a1fc5613e4512d1013434112d98631b4507e9b5187ca29e78ba376a35b992f",
+            "864683be4715d2e41b99a390be9e8574ea628f41087879a5c198b3e0571d7b2d",
+            "a15bfc758f14470e3cefa9fb2e4b063b7ceab673496da8429c7ad211fe6b0c75",
+            "dd08d99d59786aaba463c88338bf7db78ae6016dddd25cd6ed439a8581922af1",
+            "9b32d9c8b20c59596d4bbd26f6ff31b709e2507313d600e241c52034382ba6c5",
+            "e22a80d4a4b8cde5eebcf9c007b22692a49e67e16de99d8700995aeb921b4d8d",
+        );
+    }
+
+    #[test]
+    fn test_rsa2048_encrypt() {
+        let n = biguint(rsa2048::N);
+        let m = BigUint::from_bytes_be(rsa2048::MESSAGE);
+        let c = m.modpow(&BigUint::from(rsa2048::E), &n);
+        assert_eq!(c, biguint(rsa2048::CIPHERTEXT));
+        assert_eq!(c.to_bytes_be_padded(256).unwrap().len(), 256);
+    }
+
+    #[test]
+    fn test_rsa2048_decrypt() {
+        let n = biguint(rsa2048::N);
+        let c = biguint(rsa2048::CIPHERTEXT);
+        let m = c.modpow(&biguint(rsa2048::D), &n);
+        assert_eq!(m.to_bytes_be(), rsa2048::MESSAGE);
+    }
+
+    #[test]
+    fn test_rsa2048_key() {
+        let p = biguint(rsa2048::P);
+        let q = biguint(rsa2048::Q);
+        assert_eq!(&p * &q, biguint(rsa2048::N));
+        assert_eq!(&biguint(rsa2048::N) / &p, q);
+        assert_eq!(&biguint(rsa2048::N) % &q, BigUint::zero());
+
+        // d = e^-1 mod lcm(p - 1, q - 1)
+        let p1 = &p - &BigUint::one();
+        let q1 = &q - &BigUint::one();
+        let lambda = &(&p1 * &q1) / &p1.gcd(&q1);
+        assert_eq!(BigUint::from(rsa2048::E).mod_inverse(&lambda), Some(biguint(rsa2048::D)));
+        assert_eq!(q.mod_inverse(&p), Some(biguint(rsa2048::QINV)));
+    }
+
+    /// Diffie-Hellman in the 2048-bit MODP group of RFC 3526.
+    mod modp2048 {
+        pub const P: &str = concat!(
+            "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74",
+            "020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f1437",
+            "4fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7ed",
+            "ee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf05",
+            "98da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb",
+            "9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3b",
+            "e39e772c180e86039b2783a2ec07a28fb5c55df06f4c52c9de2bcbf695581718",
+            "3995497cea956ae515d2261898fa051015728e5a8aacaa68ffffffffffffffff",
+        );
+        pub const A: &str = concat!(
+            "2bd806c97f0e00af1a1fc3328fa763a9269723c8db8fac4f93af71db186d6e90",
+            "2bd806c97f0e00af1a1fc3328fa763a9269723c8db8fac4f93af71db186d6e90",
+        );
+        pub const B: &str = concat!(
+            "81b637d8fcd2c6da6359e6963113a1170de795e4b725b84d1e0b4cfd9ec58ce9",
+            "81b637d8fcd2c6da6359e6963113a1170de795e4b725b84d1e0b4cfd9ec58ce9",
+        );
+        pub const GA: &str = concat!(
+            "dc5ea62084bd5b5d5e21027abedf90105edafef0bbb5551d26801cf7cffb8388",
+            "9e59db71c22bc5cb7a10c4a5fe2cd3381efb4231a4f1d49464ad5137bad3d06a",
+            "e19d993b7d0912731c1228aeb1b90e6c29700f37be49fe44080c101f7f261b13",
+            "17a2c3b6063c277e8570b3ad51f2b8294682edb393ed928b1d4e1a0641893569",
+            "22dbd6014fb6f522c7aaff3f86bec43178089ed186307bc36f19f1efd25a8005",
+            "7e187f1bb773df2f4cbecf0145a6947ec918b2f8839094fbfc0703833a8bcde9",
+            "f3216aa4a50878e9220fc8497f5243aa320a875346eddcbeff71ee7a61b8694f",
+            "9378b755c732e6b13dc53a544f09f5c410e63c8a84b736dae5e4f0190b70ff3",
+        );
+        pub const GB: &str = concat!(
+            "3888cfc22a1317856929ec4d927c533c0a87c26b0f16fb7e90d6a307b6609ff4",
+            "5d3f2af765e4ab83a78dc4187d7552456f28466898c31b7c35b40e7a80097bf8",
+            "8b8a8d88e6dadb2d35be4dae894d60543dd6151316386fa1660e56dc1d5d8784",
+            "aeebbfd5dcf346d54734cf8e431faacfc0c60ee644615dd43136b4997ac29160",
+            "348c831d142e8100648d08186734db7c875828f96ab3597d92225a06b3468f59",
+            "db89cfe79087f478e637b5f59890b2a6dcad09c06a9b0ca68f2e61a20c96633c",
+            "dedb6f8f2a302f0a602ee855264e27a8b486384e03d15f1b99e91588ec2dfcec",
+            "f2925a9056a04796e45126b41fc3b0936e6ec430aa752617af8d72eb9f6351bb",
+        );
+        pub const GAB: &str = concat!(
+            "a62c27f825e805b484d33fd03a3db6b2dbde38fc80dcc498e4f2fed8a38b1bfa",
+            "95c8570bbfdf8b86ecce7185b63988227e3083626a467f10bbd930df4e110e30",
+            "46d3327154a71bab0dcc856c7be1757aea4a0ef66214d41e227265ff21e1d4ad",
+            "88ec9662ff6ad85bff680e2e4c5df4ba59c9f539a28fceafffa1ea0051d2f8b8",
+            "7eb15e82e3d95bbf954b0deee7f7c0aef3b6a4df3caf4f0d8ff7899ea7ab3f7f",
+            "ab3230fbcf0fb662acb953f855e40f14aaca6e6dbd59649a06bbead051e8c2b7",
+            "e6ccd06dcc77f7e54772fe3a68ad8fdab10ae75ea12959f1761eb596530d39f8",
+            "4bd12e35917c6e2767c205cd63e0461bebfaa15381b403e62f3bad64348f2702",
+        );
+    }
+
+    #[test]
+    fn test_modp2048_key_exchange() {
+        let p = biguint(modp2048::P);
+        let g = BigUint::from(2);
+        let ga = g.modpow(&biguint(modp2048::A), &p);
+        let gb = g.modpow(&biguint(modp2048::B), &p);
+        assert_eq!(ga, biguint(modp2048::GA));
+        assert_eq!(gb, biguint(modp2048::GB));
+        assert_eq!(gb.modpow(&biguint(modp2048::A), &p), biguint(modp2048::GAB));
+        assert_eq!(ga.modpow(&biguint(modp2048::B), &p), biguint(modp2048::GAB));
+    }
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(BigUint::from(48).gcd(&BigUint::from(18)), BigUint::from(6));
+        assert_eq!(BigUint::from(17).gcd(&BigUint::from(5)), BigUint::one());
+        assert_eq!(BigUint::zero().gcd(&BigUint::from(5)), BigUint::from(5));
+    }
+
+    #[test]
+    fn test_mod_inverse_textbook_rsa() {
+        // d = e^-1 mod lcm(p - 1, q - 1) = 17^-1 mod 780
+        let d = BigUint::from(17).mod_inverse(&BigUint::from(780)).unwrap();
+        assert_eq!(d, BigUint::from(413));
+        let d = BigUint::from(17).mod_inverse(&BigUint::from(3120)).unwrap();
+        assert_eq!(d, BigUint::from(2753));
+    }
+
+    #[test]
+    fn test_mod_inverse_not_coprime() {
+        assert_eq!(BigUint::from(6).mod_inverse(&BigUint::from(9)), None);
+        assert_eq!(BigUint::from(6).mod_inverse(&BigUint::one()), None);
+    }
+
+    #[test]
+    fn test_random_below() {
+        let mut rng = ChaChaRng::from_seed(&[0; 32]);
+        // every value below the bound of 5 bits appears, and none above
+        let bound = BigUint::from(24);
+        let mut seen = [false; 24];
+        for _ in 0..1000 {
+            let value = BigUint::random_below(&bound, &mut rng).to_u64().unwrap();
+            seen[value as usize] = true;
+        }
+        assert!(seen.iter().all(|seen| { *seen }));
+
+        let bound = biguint("10000000000000000000000000000001");
+        for _ in 0..100 {
+            assert!(BigUint::random_below(&bound, &mut rng) < bound);
+        }
+        assert!(BigUint::random_below(&BigUint::one(), &mut rng).is_zero());
+    }
+}
@@ -0,0 +1,203 @@
+//! Montgomery multiplication modulo an odd modulus.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use super::{cmp_limbs, BigUint, DoubleLimb, Limb, LIMB_BITS};
+
+/// Window size of the modular exponentiation in bits.
+const WINDOW_BITS: usize = 4;
+
+/// Precomputed values for the Montgomery multiplication modulo odd `m`.
+///
+/// A value `x` in Montgomery form is `x * R mod m`, where `R = 2 ^ (64 * n)`
+/// for `m` of `n` limbs, and is held as exactly `n` limbs.
+/// The multiplication doesn't branch on the values, so that it can be used
+/// with secrets.
+#[derive(Clone, Debug)]
+pub(crate) struct Montgomery {
+    modulus: Vec<Limb>,
+    /// `-m ^ -1 mod 2 ^ 64`
+    m_inv: Limb,
+    /// `R ^ 2 mod m`
+    r2: Vec<Limb>,
+}
+
+impl Montgomery {
+    /// # Panics
+    ///
+    /// Panics if the modulus is even.
+    pub(crate) fn new(modulus: &BigUint) -> Montgomery {
+        assert!(modulus.is_odd(), "Montgomery form requires an odd modulus");
+        let m = modulus.limbs().to_vec();
+
+        // Newton's method doubles the correct low bits of the inverse each step
+        let mut inv: Limb = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul((2 as Limb).wrapping_sub(m[0].wrapping_mul(inv)));
+        }
+
+        let r2 = &(&BigUint::one() << (2 * LIMB_BITS * m.len())) % modulus;
+        let r2 = pad(&r2, m.len());
+        Montgomery { modulus: m, m_inv: inv.wrapping_neg(), r2 }
+    }
+
+    /// Convert into Montgomery form, reducing the value first if necessary.
+    pub(crate) fn to_montgomery(&self, value: &BigUint) -> Vec<Limb> {
+        let value = if cmp_limbs(value.limbs(), &self.modulus) == Ordering::Less {
+            value.clone()
+        } else {
+            value % &BigUint::from_limbs(self.modulus.clone())
+        };
+        self.mul(&pad(&value, self.modulus.len()), &self.r2)
+    }
+
+    /// Convert from Montgomery form by the Montgomery reduction.
+    pub(crate) fn reduce(&self, value: &[Limb]) -> BigUint {
+        let mut one = vec![0; self.modulus.len()];
+        one[0] = 1;
+        BigUint::from_limbs(self.mul(value, &one))
+    }
+
+    /// Get `a * b * R ^ -1 mod m` by the CIOS method.
+    pub(crate) fn mul(&self, a: &[Limb], b: &[Limb]) -> Vec<Limb> {
+        let m = &self.modulus;
+        let n = m.len();
+        let mut t = vec![0; n + 2];
+        for b_limb in b.iter() {
+            // t += a * b[i]
+            let mut carry: DoubleLimb = 0;
+            for j in 0..n {
+                let sum = t[j] as DoubleLimb + a[j] as DoubleLimb * *b_limb as DoubleLimb + carry;
+                t[j] = sum as Limb;
+                carry = sum >> LIMB_BITS;
+            }
+            let sum = t[n] as DoubleLimb + carry;
+            t[n] = sum as Limb;
+            t[n + 1] = (sum >> LIMB_BITS) as Limb;
+
+            // t = (t + u * m) / 2 ^ 64, where u makes the lowest limb 0
+            let u = t[0].wrapping_mul(self.m_inv);
+            let sum = t[0] as DoubleLimb + u as DoubleLimb * m[0] as DoubleLimb;
+            let mut carry = sum >> LIMB_BITS;
+            for j in 1..n {
+                let sum = t[j] as DoubleLimb + u as DoubleLimb * m[j] as DoubleLimb + carry;
+                t[j - 1] = sum as Limb;
+                carry = sum >> LIMB_BITS;
+            }
+            let sum = t[n] as DoubleLimb + carry;
+            t[n - 1] = sum as Limb;
+            t[n] = t[n + 1] + (sum >> LIMB_BITS) as Limb;
+        }
+
+        // t < 2m here, so subtract m once if t >= m
+        let mut diff = vec![0; n];
+        let mut borrow = 0;
+        for j in 0..n {
+            let (d, borrow1) = t[j].overflowing_sub(m[j]);
+            let (d, borrow2) = d.overflowing_sub(borrow);
+            diff[j] = d;
+            borrow = (borrow1 | borrow2) as Limb;
+        }
+        let mask = (t[n] | (borrow ^ 1)).wrapping_neg();
+        for j in 0..n {
+            diff[j] = (diff[j] & mask) | (t[j] & !mask);
+        }
+        diff
+    }
+
+    /// Calculate `base ^ exponent mod m` by a fixed window.
+    /// Every window multiplies by an entry of the table, which is selected
+    /// without a memory access depending on the exponent.
+    pub(crate) fn modpow(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        let n = self.modulus.len();
+        let mut table = Vec::with_capacity(1 << WINDOW_BITS);
+        table.push(self.to_montgomery(&BigUint::one()));
+        table.push(self.to_montgomery(base));
+        for index in 2..(1 << WINDOW_BITS) {
+            let entry = self.mul(&table[index - 1], &table[1]);
+            table.push(entry);
+        }
+
+        let mut result = table[0].clone();
+        let windows = (exponent.limbs().len() * LIMB_BITS).div_ceil(WINDOW_BITS);
+        let mut entry = vec![0; n];
+        for window in (0..windows).rev() {
+            for _ in 0..WINDOW_BITS {
+                result = self.mul(&result, &result);
+            }
+
+            let limb = exponent.limbs()[window * WINDOW_BITS / LIMB_BITS];
+            let value = (limb >> (window * WINDOW_BITS % LIMB_BITS)) as usize & ((1 << WINDOW_BITS) - 1);
+            for (index, candidate) in table.iter().enumerate() {
+                let mask = eq_mask(index, value);
+                for (limb, candidate_limb) in entry.iter_mut().zip(candidate.iter()) {
+                    *limb = (*limb & !mask) | (candidate_limb & mask);
+                }
+            }
+            result = self.mul(&result, &entry);
+        }
+        self.reduce(&result)
+    }
+}
+
+/// Get all 1 bits if they are equal, or 0 without branching.
+fn eq_mask(a: usize, b: usize) -> Limb {
+    let x = (a ^ b) as Limb;
+    ((x | x.wrapping_neg()) >> (LIMB_BITS - 1)).wrapping_sub(1)
+}
+
+/// Get the limbs of the value padded with zeros to the length.
+fn pad(value: &BigUint, len: usize) -> Vec<Limb> {
+    let mut limbs = value.limbs().to_vec();
+    limbs.resize(len, 0);
+    limbs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let modulus = BigUint::from_hex("fffffffffffffffffffffffffffffffeffffffffffffffff").unwrap();
+        let montgomery = Montgomery::new(&modulus);
+        let value = BigUint::from_hex("123456789abcdef0123456789abcdef").unwrap();
+        let converted = montgomery.to_montgomery(&value);
+        assert_eq!(montgomery.reduce(&converted), value);
+    }
+
+    #[test]
+    fn test_unreduced_value() {
+        let modulus = BigUint::from_hex("fffffffffffffffffffffffffffffffeffffffffffffffff").unwrap();
+        let montgomery = Montgomery::new(&modulus);
+        // the lowest limb is less than the modulus, but the value isn't
+        let value = BigUint::from_hex("1000000000000000000000000000000000000000000000000").unwrap();
+        let converted = montgomery.to_montgomery(&value);
+        assert_eq!(montgomery.reduce(&converted), &value % &modulus);
+    }
+
+    #[test]
+    fn test_mul() {
+        let modulus = BigUint::from_hex("fffffffffffffffffffffffffffffffeffffffffffffffff").unwrap();
+        let montgomery = Montgomery::new(&modulus);
+        let a = BigUint::from_hex("fffffffffffffffffffffffffffffffefffffffffffffffe").unwrap();
+        let b = BigUint::from_hex("123456789abcdef0123456789abcdef").unwrap();
+        let product = montgomery.mul(&montgomery.to_montgomery(&a), &montgomery.to_montgomery(&b));
+        assert_eq!(montgomery.reduce(&product), &(&a * &b) % &modulus);
+    }
+
+    #[test]
+    fn test_eq_mask() {
+        assert_eq!(eq_mask(3, 3), Limb::MAX);
+        assert_eq!(eq_mask(3, 4), 0);
+        assert_eq!(eq_mask(0, usize::MAX), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_even_modulus() {
+        Montgomery::new(&BigUint::from(10));
+    }
+}
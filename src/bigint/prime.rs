@@ -0,0 +1,206 @@
+//! Primality testing and the search of random primes for key generation.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::rng::Rng;
+use super::{BigUint, Montgomery};
+
+/// Bound of the small primes which candidates are divided by before the
+/// Miller-Rabin test.
+const SIEVE_LIMIT: usize = 1 << 12;
+/// Distance from the random start searched before starting over, which is
+/// far beyond the average gap between primes of the key sizes.
+const SEARCH_WINDOW: u64 = 1 << 16;
+
+impl BigUint {
+    /// Test the primality by trial division and the rounds of the
+    /// Miller-Rabin test with random bases.
+    ///
+    /// A composite passes a round with the probability at most 1/4 even if
+    /// an attacker chose it, so untrusted values need many rounds, e.g. 64.
+    pub fn is_probable_prime<R: Rng + ?Sized>(&self, rounds: usize, rng: &mut R) -> bool {
+        if self.is_even() {
+            return self == &BigUint::from(2);
+        }
+        for prime in small_primes() {
+            let prime = BigUint::from(prime);
+            if self == &prime {
+                return true;
+            }
+            if (self % &prime).is_zero() {
+                return false;
+            }
+        }
+        if self.is_one() {
+            return false;
+        }
+
+        // self - 1 = d * 2 ^ s with odd d
+        let one = BigUint::one();
+        let minus_one = self - &one;
+        let s = (0..).find(|index| { minus_one.bit(*index) }).unwrap();
+        let d = &minus_one >> s;
+        let montgomery = Montgomery::new(self);
+        let base_range = self - &BigUint::from(3);
+        'rounds: for _ in 0..rounds {
+            // base in [2, self - 2]
+            let base = &BigUint::random_below(&base_range, rng) + &BigUint::from(2);
+            let mut x = montgomery.modpow(&base, &d);
+            if x == one || x == minus_one {
+                continue;
+            }
+            for _ in 1..s {
+                x = &(&x * &x) % self;
+                if x == minus_one {
+                    continue 'rounds;
+                }
+            }
+            return false;
+        }
+        true
+    }
+}
+
+/// Get the random prime of exactly the bits, which also passes the filter,
+/// e.g. `gcd(p - 1, e) = 1` of RSA.
+///
+/// The two most significant bits are set, so that the product of two such
+/// primes has exactly twice the bits, and the prime is at least
+/// `sqrt(2) * 2 ^ (bits - 1)` as FIPS 186-4 B.3.3 requires. Candidates
+/// following a random start are sieved by the small primes through their
+/// residues, and only the rest get the Miller-Rabin test.
+///
+/// # Panics
+///
+/// Panics if the bits are less than 16.
+pub(crate) fn generate_prime<R, F>(bits: usize, rng: &mut R, filter: F) -> BigUint
+where
+    R: Rng + ?Sized,
+    F: Fn(&BigUint) -> bool,
+{
+    assert!(bits >= 16, "primes of {} bits are too small", bits);
+    let small_primes = small_primes();
+    let rounds = miller_rabin_rounds(bits);
+    let top_bits = BigUint::from(3) << (bits - 2);
+    loop {
+        let mut start = &top_bits + &BigUint::random_below(&(BigUint::one() << (bits - 2)), rng);
+        if start.is_even() {
+            start = &start + &BigUint::one();
+        }
+        let residues: Vec<u64> = small_primes.iter()
+            .map(|prime| { (&start % &BigUint::from(*prime)).to_u64().unwrap() })
+            .collect();
+
+        for delta in (0..SEARCH_WINDOW).step_by(2) {
+            let divisible = residues.iter().zip(small_primes.iter())
+                .any(|(residue, prime)| { (residue + delta) % prime == 0 });
+            if divisible {
+                continue;
+            }
+            let candidate = &start + &BigUint::from(delta);
+            if candidate.bits() != bits {
+                break;
+            }
+            if filter(&candidate) && candidate.is_probable_prime(rounds, rng) {
+                return candidate;
+            }
+        }
+    }
+}
+
+/// Get the number of the Miller-Rabin rounds for random candidates of the
+/// bits, which keeps the error probability below `2 ^ -100` by the bounds of
+/// Damgård, Landrock and Pomerance (FIPS 186-4 C.3).
+/// Most composites fail the first round, so the rounds cost little but for
+/// the prime found.
+fn miller_rabin_rounds(bits: usize) -> usize {
+    match bits {
+        1536.. => 4,
+        1024.. => 5,
+        512.. => 8,
+        256.. => 16,
+        _ => 40,
+    }
+}
+
+/// Get the odd primes less than `SIEVE_LIMIT` by the sieve of Eratosthenes.
+fn small_primes() -> Vec<u64> {
+    let mut composite = vec![false; SIEVE_LIMIT];
+    let mut primes = Vec::new();
+    for n in 3..SIEVE_LIMIT {
+        if composite[n] || n % 2 == 0 {
+            continue;
+        }
+        primes.push(n as u64);
+        for multiple in (n * n..SIEVE_LIMIT).step_by(n) {
+            composite[multiple] = true;
+        }
+    }
+    primes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::ChaChaRng;
+
+    fn test_rng() -> ChaChaRng {
+        ChaChaRng::from_seed(&[5; 32])
+    }
+
+    #[test]
+    fn test_small_primes() {
+        let primes = small_primes();
+        assert_eq!(primes[..8], [3, 5, 7, 11, 13, 17, 19, 23]);
+        assert_eq!(primes.len(), 563);
+        assert_eq!(primes.last(), Some(&4093));
+    }
+
+    #[test]
+    fn test_small_values() {
+        let mut rng = test_rng();
+        let primes: Vec<u64> = (0..30).filter(|n| { BigUint::from(*n).is_probable_prime(10, &mut rng) }).collect();
+        assert_eq!(primes, [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        // beyond the trial division
+        assert!(BigUint::from(4099).is_probable_prime(10, &mut rng));
+        assert!(!BigUint::from(4099 * 4111).is_probable_prime(10, &mut rng));
+    }
+
+    #[test]
+    fn test_mersenne_primes() {
+        let mut rng = test_rng();
+        for exponent in [61, 89, 107, 127, 521, 607] {
+            let prime = &(BigUint::one() << exponent) - &BigUint::one();
+            assert!(prime.is_probable_prime(20, &mut rng), "2^{} - 1", exponent);
+        }
+        // 2 ^ 67 - 1 = 193707721 * 761838257287
+        let composite = &(BigUint::one() << 67) - &BigUint::one();
+        assert!(!composite.is_probable_prime(20, &mut rng));
+    }
+
+    #[test]
+    fn test_pseudoprimes() {
+        let mut rng = test_rng();
+        // Carmichael numbers, which the trial division catches
+        for n in [561, 17236801, 29111881] {
+            assert!(!BigUint::from(n).is_probable_prime(20, &mut rng), "{}", n);
+        }
+        // 4261 * 8521 * 12781, a Carmichael number and strong pseudoprime to
+        // the bases 2 and 7 whose factors are beyond the trial division
+        let n = BigUint::from(464052305161);
+        assert!(!n.is_probable_prime(20, &mut rng));
+    }
+
+    #[test]
+    fn test_generate_prime() {
+        let mut rng = test_rng();
+        for bits in [16, 64, 65, 256] {
+            let prime = generate_prime(bits, &mut rng, |_| { true });
+            assert_eq!(prime.bits(), bits);
+            assert!(prime.bit(bits - 2));
+            assert!(prime.is_probable_prime(40, &mut test_rng()));
+        }
+        let prime = generate_prime(128, &mut rng, |candidate| { (candidate % &BigUint::from(3)).to_u64() == Some(2) });
+        assert_eq!((&prime % &BigUint::from(3)).to_u64(), Some(2));
+    }
+}
@@ -0,0 +1,80 @@
+//! Property-based tests over random operands of various lengths.
+
+use proptest::prelude::*;
+use super::*;
+
+/// Strategy of operands up to 640 bits, biased to limb boundaries.
+fn operands() -> impl Strategy<Value = BigUint> {
+    prop::collection::vec(
+        prop_oneof![
+            2 => any::<u64>(),
+            1 => Just(0),
+            1 => Just(u64::MAX),
+            1 => Just(1 << 63),
+        ],
+        0..10,
+    ).prop_map(BigUint::from_limbs)
+}
+
+/// Strategy of nonzero operands.
+fn nonzero_operands() -> impl Strategy<Value = BigUint> {
+    operands().prop_filter("nonzero", |value| { !value.is_zero() })
+}
+
+proptest! {
+    #[test]
+    fn prop_div_rem_identity(a in operands(), b in nonzero_operands()) {
+        let (q, r) = a.div_rem(&b);
+        prop_assert!(r < b);
+        prop_assert_eq!(&(&q * &b) + &r, a);
+    }
+
+    #[test]
+    fn prop_add_sub_inverse(a in operands(), b in operands()) {
+        let sum = &a + &b;
+        prop_assert_eq!(&sum - &b, a.clone());
+        prop_assert_eq!(&sum - &a, b);
+    }
+
+    #[test]
+    fn prop_shift_inverse(a in operands(), shift in 0usize..200) {
+        let shifted = &a << shift;
+        prop_assert_eq!(shifted.bits(), if a.is_zero() { 0 } else { a.bits() + shift });
+        prop_assert_eq!(&shifted >> shift, a);
+    }
+
+    #[test]
+    fn prop_bytes_be_round_trip(a in operands()) {
+        prop_assert_eq!(BigUint::from_bytes_be(&a.to_bytes_be()), a.clone());
+        prop_assert_eq!(BigUint::from_hex(&alloc::format!("{:x}", a)), Some(a));
+    }
+
+    #[test]
+    fn prop_montgomery_modpow_matches_plain(
+        base in operands(),
+        exponent in operands(),
+        modulus in nonzero_operands(),
+    ) {
+        // compare the Montgomery form with the square-and-multiply of an odd modulus
+        let modulus = if modulus.is_even() { &modulus + &BigUint::one() } else { modulus };
+        let mut expected = &BigUint::one() % &modulus;
+        for index in (0..exponent.bits()).rev() {
+            expected = &(&expected * &expected) % &modulus;
+            if exponent.bit(index) {
+                expected = &(&expected * &base) % &modulus;
+            }
+        }
+        prop_assert_eq!(base.modpow(&exponent, &modulus), expected);
+    }
+
+    #[test]
+    fn prop_mod_inverse(a in operands(), modulus in nonzero_operands()) {
+        match a.mod_inverse(&modulus) {
+            Some(inverse) => {
+                prop_assert!(inverse < modulus);
+                prop_assert!((&(&a * &inverse) % &modulus).is_one());
+            },
+            None => prop_assert!(modulus.is_one() || !a.gcd(&modulus).is_one()),
+        }
+    }
+}
@@ -1,8 +1,9 @@
 // Read and Write need to use stream.read() and stream.write()
-use std::io::{Write, Read, Result, Error, ErrorKind};
+use std::io::{self, Write, Read};
 use std::net::TcpStream;
 use url::Url;
 use structopt::StructOpt;
+use impl_ssl_tls::{Error, Result};
 
 /// Struct for CLI arguments.
 #[derive(Debug, StructOpt)]
@@ -30,16 +31,17 @@ fn main() {
         exit_with_message!("Unsupported shceme: {}", url.scheme());
     }
     let proxy = opt.proxy;
-    if proxy.is_some() && proxy.as_ref().unwrap().scheme() != "http" {
-        exit_with_message!("Unsupported proxy shceme: {}", proxy.unwrap().scheme());
+    if let Some(proxy_url) = &proxy {
+        if proxy_url.scheme() != "http" {
+            exit_with_message!("Unsupported proxy shceme: {}", proxy_url.scheme());
+        }
     }
 
     // request HTTP GET
     let print_bytes = |bytes: &[u8]| {
         print!("{}", std::str::from_utf8(bytes).unwrap());
     };
-    if proxy.is_some() {
-        let proxy_url = proxy.unwrap();
+    if let Some(proxy_url) = proxy {
         request_http_get_with_proxy(&url, &proxy_url, print_bytes).unwrap_or_else(|e| {
             exit_with_message!("{}", e);
         });
@@ -53,11 +55,12 @@ fn main() {
 /// Unwrap Ok value or terminate function with Err as return value
 /// i.e. this macro is only for functions that have Result(..) as return type.
 /// This macro is to avoid deep nesting by `match` expression.
+/// The error is converted into the return type like `?` operator.
 macro_rules! unwrap_or_return_err {
     ($e:expr) => {
         match $e {
             Ok(x) => x,
-            Err(e) => return Err(e),
+            Err(e) => return Err(e.into()),
         }
     };
 }
@@ -104,7 +107,7 @@ fn request_http_get_with_proxy(
     // send HTTP GET request
     let host = url.host_str().unwrap();
     let path = url.as_str();  // need to use the full URL when using proxy
-    let auth = unwrap_or_return_err!(get_proxy_auth(&proxy_url))
+    let auth = unwrap_or_return_err!(get_proxy_auth(proxy_url))
         .map(|auth| { auth.as_tag() })
         .unwrap_or(String::new());
     let request = format!(concat!(
@@ -124,21 +127,21 @@ fn request_http_get_with_proxy(
 // Connect to host server.
 fn connect(host: &str, port: u16) -> Result<TcpStream> {
     TcpStream::connect((host, port)).map_err(|e| {
-        Error::new(e.kind(), format!("Failed to connect host: {}", e))
+        Error::Io(io::Error::new(e.kind(), format!("Failed to connect host: {}", e)))
     })
 }
 
 /// Send a request string to socket.
 fn send_request(mut stream: &TcpStream, request: &str) -> Result<usize> {
     stream.write(request.as_bytes()).map_err(|e| {
-        Error::new(e.kind(), format!("Failed to send request: {}", e))
+        Error::Io(io::Error::new(e.kind(), format!("Failed to send request: {}", e)))
     })
 }
 
 /// Recieve response chunk by chunk.
 fn recieve_response(stream: &TcpStream, callback: fn(&[u8])) -> Result<()> {
     read_chunks(stream, callback).map_err(|e| {
-        Error::new(e.kind(), format!("Failed to recieve response: {}", e))
+        Error::Io(io::Error::new(e.kind(), format!("Failed to recieve response: {}", e)))
     })
 }
 
@@ -146,7 +149,7 @@ fn recieve_response(stream: &TcpStream, callback: fn(&[u8])) -> Result<()> {
 const MAX_CHUNK_SIZE: usize = 1024;
 
 /// Read bytes from a stream chunk by chunk and process it.
-fn read_chunks(mut stream: &TcpStream, f: fn(&[u8])) -> Result<()> {
+fn read_chunks(mut stream: &TcpStream, f: fn(&[u8])) -> io::Result<()> {
     let mut buf: [u8; MAX_CHUNK_SIZE] = [0; MAX_CHUNK_SIZE];
     loop {
         let read_size = unwrap_or_return_err!(stream.read(&mut buf));
@@ -188,7 +191,7 @@ fn get_proxy_auth(proxy: &Url) -> Result<Option<ProxyAuth>> {
     // only supplying both username and password or not both is allowed
     if !user.is_empty() && pass.is_none() {
         // return something error
-        return Err(Error::new(ErrorKind::Other,
+        return Err(Error::Config(
             format!("Invalid proxy credentials: Expected password in {}", proxy)
         ));
     }
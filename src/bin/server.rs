@@ -2,7 +2,7 @@ use std::io::{Write, Read};
 use std::cmp::Ordering;
 use std::net::{TcpStream, TcpListener, SocketAddrV4, Ipv4Addr};
 
-const PORT_KEY: &'static str = "PORT";
+const PORT_KEY: &str = "PORT";
 const DEFAULT_PORT: u16 = 7878;
 
 fn main() {
@@ -31,8 +31,8 @@ const MAX_CHUNK_SIZE: usize = 1024;
 fn handle_connection(mut stream: TcpStream) {
     let mut buffer = [0; MAX_CHUNK_SIZE];
 
-    stream.read(&mut buffer).unwrap();
-    println!("{}", String::from_utf8_lossy(&buffer[..]));
+    let size = stream.read(&mut buffer).unwrap();
+    println!("{}", String::from_utf8_lossy(&buffer[..size]));
 
     let response = match buffer[..4].cmp(b"GET ") {
         Ordering::Equal => {
@@ -45,6 +45,6 @@ fn handle_connection(mut stream: TcpStream) {
         },
     };
 
-    stream.write(response.as_bytes()).unwrap();
+    stream.write_all(response.as_bytes()).unwrap();
     stream.flush().unwrap();
 }
@@ -0,0 +1,56 @@
+//! Symmetric ciphers of TLS cipher suites.
+
+use core::fmt;
+
+pub mod aes;
+pub mod chacha20;
+pub mod des;
+pub mod modes;
+#[cfg(feature = "legacy-insecure")]
+pub mod rc4;
+
+pub use aes::Aes;
+pub use chacha20::ChaCha20;
+pub use des::{Des, TripleDes};
+#[cfg(feature = "legacy-insecure")]
+pub use rc4::Rc4;
+
+/// Block cipher which encrypts and decrypts blocks of a fixed size in place.
+pub trait BlockCipher {
+    /// Block size in bytes.
+    const BLOCK_SIZE: usize;
+
+    /// Encrypt a block in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the block is not `BLOCK_SIZE`.
+    fn encrypt_block(&self, block: &mut [u8]);
+
+    /// Decrypt a block in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the block is not `BLOCK_SIZE`.
+    fn decrypt_block(&self, block: &mut [u8]);
+}
+
+/// Errors of keys given to ciphers.
+#[derive(Debug, PartialEq)]
+pub enum KeyError {
+    /// The key has the length which the cipher doesn't support.
+    InvalidLength(usize),
+    /// The key is known to be weak for the cipher.
+    WeakKey,
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyError::InvalidLength(len) => write!(f, "invalid key length {}", len),
+            KeyError::WeakKey => write!(f, "weak key"),
+        }
+    }
+}
+
+impl core::error::Error for KeyError {}
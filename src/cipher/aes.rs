@@ -0,0 +1,339 @@
+//! AES-128, AES-192 and AES-256 (FIPS 197).
+//!
+//! By default blocks are processed with lookup tables, which are fast but
+//! indexed by secret values. The `aes-ct` feature selects the constant-time
+//! implementation, which computes the S-box arithmetically instead.
+
+use core::convert::TryInto;
+use super::{BlockCipher, KeyError};
+
+#[cfg(not(feature = "aes-ct"))]
+mod table;
+#[cfg(not(feature = "aes-ct"))]
+use table as backend;
+#[cfg(feature = "aes-ct")]
+mod ct;
+#[cfg(feature = "aes-ct")]
+use ct as backend;
+
+/// Block size of AES in bytes.
+pub const BLOCK_SIZE: usize = 16;
+
+/// Maximum number of round keys in 32-bit words, which AES-256 uses.
+const MAX_ROUND_KEY_WORDS: usize = 4 * (14 + 1);
+
+/// Round constants of the key expansion.
+const RCON: [u32; 10] = [
+    0x01000000, 0x02000000, 0x04000000, 0x08000000, 0x10000000,
+    0x20000000, 0x40000000, 0x80000000, 0x1B000000, 0x36000000,
+];
+
+/// AES block cipher with a key of 16, 24 or 32 bytes.
+#[derive(Clone)]
+pub struct Aes {
+    rounds: usize,
+    /// Round keys of the cipher as big-endian words of columns.
+    encrypt_keys: [u32; MAX_ROUND_KEY_WORDS],
+    /// Round keys of the equivalent inverse cipher.
+    decrypt_keys: [u32; MAX_ROUND_KEY_WORDS],
+}
+
+impl Aes {
+    /// Create with the key of 16, 24 or 32 bytes for AES-128, AES-192 or
+    /// AES-256 respectively.
+    pub fn new(key: &[u8]) -> Result<Aes, KeyError> {
+        let rounds = match key.len() {
+            16 => 10,
+            24 => 12,
+            32 => 14,
+            len => return Err(KeyError::InvalidLength(len)),
+        };
+        let encrypt_keys = expand_key(key, rounds);
+        let decrypt_keys = invert_round_keys(&encrypt_keys, rounds);
+        Ok(Aes { rounds, encrypt_keys, decrypt_keys })
+    }
+
+    /// Get the key size in bytes.
+    pub fn key_size(&self) -> usize {
+        (self.rounds - 6) * 4
+    }
+}
+
+impl BlockCipher for Aes {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let state = read_block(block);
+        let state = backend::encrypt(state, &self.encrypt_keys[..4 * (self.rounds + 1)]);
+        write_block(block, state);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let state = read_block(block);
+        let state = backend::decrypt(state, &self.decrypt_keys[..4 * (self.rounds + 1)]);
+        write_block(block, state);
+    }
+}
+
+/// Read the block into the state of 4 columns.
+fn read_block(block: &[u8]) -> [u32; 4] {
+    assert_eq!(block.len(), BLOCK_SIZE, "invalid block length");
+    let mut state = [0; 4];
+    for (column, bytes) in state.iter_mut().zip(block.chunks(4)) {
+        *column = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+    state
+}
+
+fn write_block(block: &mut [u8], state: [u32; 4]) {
+    for (bytes, column) in block.chunks_mut(4).zip(state.iter()) {
+        bytes.copy_from_slice(&column.to_be_bytes());
+    }
+}
+
+/// Expand the key into the round keys (FIPS 197 5.2).
+fn expand_key(key: &[u8], rounds: usize) -> [u32; MAX_ROUND_KEY_WORDS] {
+    let nk = key.len() / 4;
+    let mut words = [0; MAX_ROUND_KEY_WORDS];
+    for (word, bytes) in words.iter_mut().zip(key.chunks(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+    for i in nk..4 * (rounds + 1) {
+        let mut temp = words[i - 1];
+        if i % nk == 0 {
+            temp = backend::sub_word(temp.rotate_left(8)) ^ RCON[i / nk - 1];
+        } else if nk > 6 && i % nk == 4 {
+            temp = backend::sub_word(temp);
+        }
+        words[i] = words[i - nk] ^ temp;
+    }
+    words
+}
+
+/// Get the round keys of the equivalent inverse cipher (FIPS 197 5.3.5), i.e.
+/// the round keys in the reverse order with InvMixColumns applied to all but
+/// the first and the last.
+fn invert_round_keys(keys: &[u32; MAX_ROUND_KEY_WORDS], rounds: usize) -> [u32; MAX_ROUND_KEY_WORDS] {
+    let mut inverted = [0; MAX_ROUND_KEY_WORDS];
+    for round in 0..=rounds {
+        for column in 0..4 {
+            let word = keys[4 * (rounds - round) + column];
+            inverted[4 * round + column] = if round == 0 || round == rounds {
+                word
+            } else {
+                inv_mix_column(word)
+            };
+        }
+    }
+    inverted
+}
+
+/// Multiply each byte of the word by x in GF(2^8).
+fn xtime(word: u32) -> u32 {
+    ((word & 0x7F7F7F7F) << 1) ^ (((word >> 7) & 0x01010101) * 0x1B)
+}
+
+/// MixColumns of a column.
+fn mix_column(column: u32) -> u32 {
+    // 2a0 + 3a1 + a2 + a3 for each rotation of the bytes
+    let doubled = xtime(column);
+    doubled ^ (doubled ^ column).rotate_left(8) ^ column.rotate_left(16) ^ column.rotate_left(24)
+}
+
+/// InvMixColumns of a column.
+fn inv_mix_column(column: u32) -> u32 {
+    // the inverse matrix is the product of MixColumns and {04, 00, 05, 00}
+    let quadrupled = xtime(xtime(column ^ column.rotate_left(16)));
+    mix_column(column ^ quadrupled)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use crate::base16;
+    use crate::test_util::hex;
+
+    fn assert_encryption(key: &[u8], plaintext: &[u8], ciphertext: &[u8]) {
+        let aes = Aes::new(key).unwrap();
+        let mut block = plaintext.to_vec();
+        aes.encrypt_block(&mut block);
+        assert_eq!(block, ciphertext, "key {}", base16::encode(key));
+        aes.decrypt_block(&mut block);
+        assert_eq!(block, plaintext, "key {}", base16::encode(key));
+    }
+
+    /// FIPS 197 Appendix C as (key, plaintext, ciphertext).
+    const FIPS197_VECTORS: [(&str, &str, &str); 4] = [
+        // Appendix B
+        (
+            "2b7e151628aed2a6abf7158809cf4f3c",
+            "3243f6a8885a308d313198a2e0370734",
+            "3925841d02dc09fbdc118597196a0b32",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f",
+            "00112233445566778899aabbccddeeff",
+            "69c4e0d86a7b0430d8cdb78070b4c55a",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f1011121314151617",
+            "00112233445566778899aabbccddeeff",
+            "dda97ca4864cdfe06eaf70a0ec0d7191",
+        ),
+        (
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            "00112233445566778899aabbccddeeff",
+            "8ea2b7ca516745bfeafc49904b496089",
+        ),
+    ];
+
+    #[test]
+    fn test_fips197() {
+        for (key, plaintext, ciphertext) in FIPS197_VECTORS {
+            assert_encryption(&hex(key), &hex(plaintext), &hex(ciphertext));
+        }
+    }
+
+    #[test]
+    fn test_key_expansion() {
+        // FIPS 197 Appendix A, the first word after the key and the last word
+        let key = hex("2b7e151628aed2a6abf7158809cf4f3c");
+        let words = expand_key(&key, 10);
+        assert_eq!(words[4], 0xa0fafe17);
+        assert_eq!(words[43], 0xb6630ca6);
+
+        let key = hex("8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b");
+        let words = expand_key(&key, 12);
+        assert_eq!(words[6], 0xfe0c91f7);
+        assert_eq!(words[51], 0x01002202);
+
+        let key = hex("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4");
+        let words = expand_key(&key, 14);
+        assert_eq!(words[8], 0x9ba35411);
+        assert_eq!(words[59], 0x706c631e);
+    }
+
+    /// AESAVS GFSbox tests with the zero key as (key size, plaintext, ciphertext).
+    const GFSBOX_VECTORS: [(usize, &str, &str); 11] = [
+        (16, "f34481ec3cc627bacd5dc3fb08f273e6", "0336763e966d92595a567cc9ce537f5e"),
+        (16, "9798c4640bad75c7c3227db910174e72", "a9a1631bf4996954ebc093957b234589"),
+        (16, "96ab5c2ff612d9dfaae8c31f30c42168", "ff4f8391a6a40ca5b25d23bedd44a597"),
+        (16, "6a118a874519e64e9963798a503f1d35", "dc43be40be0e53712f7e2bf5ca707209"),
+        (16, "cb9fceec81286ca3e989bd979b0cb284", "92beedab1895a94faa69b632e5cc47ce"),
+        (16, "b26aeb1874e47ca8358ff22378f09144", "459264f4798f6a78bacb89c15ed3d601"),
+        (16, "58c8e00b2631686d54eab84b91f0aca1", "08a4e2efec8a8e3312ca7460b9040bbf"),
+        (24, "1b077a6af4b7f98229de786d7516b639", "275cfc0413d8ccb70513c3859b1d0f72"),
+        (24, "9c2d8842e5f48f57648205d39a239af1", "c9b8135ff1b5adc413dfd053b21bd96d"),
+        (32, "014730f80ac625fe84f026c60bfd547d", "5c9d844ed46f9885085e5d6a4f94c7d7"),
+        (32, "0b24af36193ce4665f2825d7b4749c98", "a9ff75bd7cf6613d3731c77c3b6d0c04"),
+    ];
+
+    #[test]
+    fn test_aesavs_gfsbox() {
+        for (key_size, plaintext, ciphertext) in GFSBOX_VECTORS {
+            assert_encryption(&vec_of_zeros(key_size), &hex(plaintext), &hex(ciphertext));
+        }
+    }
+
+    /// AESAVS KeySbox tests with the zero plaintext as (key, ciphertext).
+    const KEYSBOX_VECTORS: [(&str, &str); 5] = [
+        ("10a58869d74be5a374cf867cfb473859", "6d251e6944b051e04eaa6fb4dbf78465"),
+        ("caea65cdbb75e9169ecd22ebe6e54675", "6e29201190152df4ee058139def610bb"),
+        ("a2e2fa9baf7d20822ca9f0542f764a41", "c3b44b95d9d2f25670eee9a0de099fa3"),
+        ("e9f065d7c13573587f7875357dfbb16c53489f6a4bd0f7cd", "0956259c9cd5cfd0181cca53380cde06"),
+        (
+            "c47b0294dbbbee0fec4757f22ffeee3587ca4730c3d33b691df38bab076bc558",
+            "46f2fb342d6f0ab477476fc501242c5f",
+        ),
+    ];
+
+    #[test]
+    fn test_aesavs_keysbox() {
+        for (key, ciphertext) in KEYSBOX_VECTORS {
+            assert_encryption(&hex(key), &vec_of_zeros(BLOCK_SIZE), &hex(ciphertext));
+        }
+    }
+
+    /// AESAVS VarTxt tests with the zero key and the plaintext of the leading
+    /// 1 bits as (key size, number of 1 bits, ciphertext).
+    const VARTXT_VECTORS: [(usize, usize, &str); 12] = [
+        (16, 1, "3ad78e726c1ec02b7ebfe92b23d9ec34"),
+        (16, 2, "aae5939c8efdf2f04e60b9fe7117b2c2"),
+        (16, 64, "f807c3e7985fe0f5a50e2cdb25c5109e"),
+        (16, 128, "3f5b8cc9ea855a0afa7347d23e8d664e"),
+        (24, 1, "6cd02513e8d4dc986b4afe087a60bd0c"),
+        (24, 2, "2ce1f8b7e30627c1c4519eada44bc436"),
+        (24, 64, "93baaffb35fbe739c17c6ac22eecf18f"),
+        (24, 128, "b13db4da1f718bc6904797c82bcf2d32"),
+        (32, 1, "ddc6bf790c15760d8d9aeb6f9a75fd4e"),
+        (32, 2, "0a6bdc6d4c1e6280301fd8e97ddbe601"),
+        (32, 64, "9b58dbfd77fe5aca9cfc190cd1b82d19"),
+        (32, 128, "acdace8078a32b1a182bfa4987ca1347"),
+    ];
+
+    #[test]
+    fn test_aesavs_vartxt() {
+        for (key_size, ones, ciphertext) in VARTXT_VECTORS {
+            assert_encryption(&vec_of_zeros(key_size), &leading_ones(BLOCK_SIZE, ones), &hex(ciphertext));
+        }
+    }
+
+    /// AESAVS VarKey tests with the zero plaintext and the key of the leading
+    /// 1 bits as (key size, number of 1 bits, ciphertext).
+    const VARKEY_VECTORS: [(usize, usize, &str); 12] = [
+        (16, 1, "0edd33d3c621e546455bd8ba1418bec8"),
+        (16, 2, "4bc3f883450c113c64ca42e1112a9e87"),
+        (16, 64, "84be19e053635f09f2665e7bae85b42d"),
+        (16, 128, "a1f6258c877d5fcd8964484538bfc92c"),
+        (24, 1, "de885dc87f5a92594082d02cc1e1b42c"),
+        (24, 2, "132b074e80f2a597bf5febd8ea5da55e"),
+        (24, 96, "03aa9058490eda306001a8a9f48d0ca7"),
+        (24, 192, "dd8a493514231cbf56eccee4c40889fb"),
+        (32, 1, "e35a6dcb19b201a01ebcfa8aa22b5759"),
+        (32, 2, "b29169cdcf2d83e838125a12ee6aa400"),
+        (32, 128, "6825a347ac479d4f9d95c5cb8d3fd7e9"),
+        (32, 256, "4bf85f1b5d54adbc307b0a048389adcb"),
+    ];
+
+    #[test]
+    fn test_aesavs_varkey() {
+        for (key_size, ones, ciphertext) in VARKEY_VECTORS {
+            assert_encryption(&leading_ones(key_size, ones), &vec_of_zeros(BLOCK_SIZE), &hex(ciphertext));
+        }
+    }
+
+    fn vec_of_zeros(len: usize) -> Vec<u8> {
+        alloc::vec![0; len]
+    }
+
+    /// Bytes whose leading bits are 1 and the rest are 0.
+    fn leading_ones(len: usize, ones: usize) -> Vec<u8> {
+        (0..len)
+            .map(|index| {
+                let bits = ones.saturating_sub(index * 8).min(8);
+                !(0xFFu8.checked_shr(bits as u32).unwrap_or(0))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_invalid_key_length() {
+        assert!(matches!(Aes::new(&[0; 15]), Err(KeyError::InvalidLength(15))));
+        assert!(matches!(Aes::new(&[0; 64]), Err(KeyError::InvalidLength(64))));
+        assert_eq!(Aes::new(&[0; 24]).unwrap().key_size(), 24);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_block_length() {
+        Aes::new(&[0; 16]).unwrap().encrypt_block(&mut [0; 8]);
+    }
+
+    #[test]
+    fn test_mix_column() {
+        // FIPS 197 Appendix B, the first column of round 1
+        assert_eq!(mix_column(0xd4bf5d30), 0x046681e5);
+        assert_eq!(inv_mix_column(0x046681e5), 0xd4bf5d30);
+    }
+}
@@ -0,0 +1,149 @@
+//! Constant-time AES without lookup tables.
+//!
+//! The S-box is computed as the multiplicative inverse in GF(2^8) followed by
+//! the affine transformation, on 8 bytes packed in a `u64` at once. Only
+//! shifts, masks, XOR and multiplications by constants are used, so neither
+//! the memory access pattern nor the branches depend on the data.
+
+use super::{mix_column, inv_mix_column};
+
+/// The byte 0x01 in every lane.
+const LANES_01: u64 = 0x0101010101010101;
+
+/// Multiply each byte by x in GF(2^8).
+fn xtime(x: u64) -> u64 {
+    ((x & (0x7F * LANES_01)) << 1) ^ (((x >> 7) & LANES_01) * 0x1B)
+}
+
+/// Multiply each pair of bytes in GF(2^8).
+fn gf_mul(mut a: u64, b: u64) -> u64 {
+    let mut product = 0;
+    for bit in 0..8 {
+        let mask = ((b >> bit) & LANES_01) * 0xFF;
+        product ^= a & mask;
+        a = xtime(a);
+    }
+    product
+}
+
+/// Get the multiplicative inverse of each byte as x^254, where 0 is mapped to 0.
+fn gf_inverse(x: u64) -> u64 {
+    let x2 = gf_mul(x, x);
+    let x3 = gf_mul(x2, x);
+    let x6 = gf_mul(x3, x3);
+    let x12 = gf_mul(x6, x6);
+    let x15 = gf_mul(x12, x3);
+    let x30 = gf_mul(x15, x15);
+    let x60 = gf_mul(x30, x30);
+    let x120 = gf_mul(x60, x60);
+    let x126 = gf_mul(x120, x6);
+    let x252 = gf_mul(x126, x126);
+    gf_mul(x252, x2)
+}
+
+/// Rotate each byte to the left.
+fn rotate_bytes_left(x: u64, shift: u32) -> u64 {
+    let high = ((0xFFu64 << shift) & 0xFF) * LANES_01;
+    ((x << shift) & high) | ((x >> (8 - shift)) & !high)
+}
+
+fn sbox(x: u64) -> u64 {
+    let inverse = gf_inverse(x);
+    inverse
+        ^ rotate_bytes_left(inverse, 1)
+        ^ rotate_bytes_left(inverse, 2)
+        ^ rotate_bytes_left(inverse, 3)
+        ^ rotate_bytes_left(inverse, 4)
+        ^ (0x63 * LANES_01)
+}
+
+fn inv_sbox(x: u64) -> u64 {
+    let affine = rotate_bytes_left(x, 1)
+        ^ rotate_bytes_left(x, 3)
+        ^ rotate_bytes_left(x, 6)
+        ^ (0x05 * LANES_01);
+    gf_inverse(affine)
+}
+
+/// Apply the packed S-box function to all bytes of the state.
+fn sub_bytes(state: [u32; 4], f: fn(u64) -> u64) -> [u32; 4] {
+    let low = f(((state[0] as u64) << 32) | state[1] as u64);
+    let high = f(((state[2] as u64) << 32) | state[3] as u64);
+    [(low >> 32) as u32, low as u32, (high >> 32) as u32, high as u32]
+}
+
+/// SubWord of the key expansion.
+pub(super) fn sub_word(word: u32) -> u32 {
+    sbox(word as u64) as u32
+}
+
+/// ShiftRows, where the row r of the column c comes from the column c + r.
+fn shift_rows(state: [u32; 4]) -> [u32; 4] {
+    let mut shifted = [0; 4];
+    for (c, column) in shifted.iter_mut().enumerate() {
+        *column = (state[c] & 0xFF000000)
+            | (state[(c + 1) % 4] & 0x00FF0000)
+            | (state[(c + 2) % 4] & 0x0000FF00)
+            | (state[(c + 3) % 4] & 0x000000FF);
+    }
+    shifted
+}
+
+/// InvShiftRows, where the row r of the column c comes from the column c - r.
+fn inv_shift_rows(state: [u32; 4]) -> [u32; 4] {
+    let mut shifted = [0; 4];
+    for (c, column) in shifted.iter_mut().enumerate() {
+        *column = (state[c] & 0xFF000000)
+            | (state[(c + 3) % 4] & 0x00FF0000)
+            | (state[(c + 2) % 4] & 0x0000FF00)
+            | (state[(c + 1) % 4] & 0x000000FF);
+    }
+    shifted
+}
+
+fn add_round_key(state: [u32; 4], key: &[u32]) -> [u32; 4] {
+    [state[0] ^ key[0], state[1] ^ key[1], state[2] ^ key[2], state[3] ^ key[3]]
+}
+
+/// Encrypt the state with the round keys.
+pub(super) fn encrypt(state: [u32; 4], keys: &[u32]) -> [u32; 4] {
+    let rounds = keys.len() / 4 - 1;
+    let mut state = add_round_key(state, &keys[..4]);
+    for key in keys[4..4 * rounds].chunks(4) {
+        state = shift_rows(sub_bytes(state, sbox));
+        state = add_round_key(state.map(mix_column), key);
+    }
+    state = shift_rows(sub_bytes(state, sbox));
+    add_round_key(state, &keys[4 * rounds..])
+}
+
+/// Decrypt the state with the round keys of the equivalent inverse cipher.
+pub(super) fn decrypt(state: [u32; 4], keys: &[u32]) -> [u32; 4] {
+    let rounds = keys.len() / 4 - 1;
+    let mut state = add_round_key(state, &keys[..4]);
+    for key in keys[4..4 * rounds].chunks(4) {
+        state = inv_shift_rows(sub_bytes(state, inv_sbox));
+        state = add_round_key(state.map(inv_mix_column), key);
+    }
+    state = inv_shift_rows(sub_bytes(state, inv_sbox));
+    add_round_key(state, &keys[4 * rounds..])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sbox() {
+        // FIPS 197 Figure 7 and 14 in different lanes
+        assert_eq!(sbox(0x0053ff0000000000), 0x63ed166363636363);
+        assert_eq!(inv_sbox(0x63ed166363636363), 0x0053ff0000000000);
+        assert_eq!(inv_sbox(0), 0x5252525252525252);
+    }
+
+    #[test]
+    fn test_rotate_bytes_left() {
+        assert_eq!(rotate_bytes_left(0x8001_0000_0000_00F0, 1), 0x0102_0000_0000_00E1);
+        assert_eq!(rotate_bytes_left(0x8001_0000_0000_00F0, 4), 0x0810_0000_0000_000F);
+    }
+}
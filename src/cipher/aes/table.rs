@@ -0,0 +1,183 @@
+//! AES with the S-box and round tables, which combine SubBytes and
+//! MixColumns of each byte into a word.
+
+/// S-box.
+const SBOX: [u8; 256] = generate_sbox();
+
+/// Inverse S-box.
+const INV_SBOX: [u8; 256] = generate_inv_sbox(&SBOX);
+
+/// Round table of encryption, whose other 3 tables are the rotations.
+const TE: [u32; 256] = generate_round_table(&SBOX, [2, 1, 1, 3]);
+
+/// Round table of decryption, whose other 3 tables are the rotations.
+const TD: [u32; 256] = generate_round_table(&INV_SBOX, [14, 9, 13, 11]);
+
+/// Multiply in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1.
+const fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        a = (a << 1) ^ if a & 0x80 != 0 { 0x1B } else { 0 };
+        b >>= 1;
+    }
+    product
+}
+
+/// Generate the S-box, the multiplicative inverse followed by the affine
+/// transformation (FIPS 197 5.1.1).
+const fn generate_sbox() -> [u8; 256] {
+    let mut sbox = [0; 256];
+    let mut x = 0;
+    while x < 256 {
+        // the inverse is x^254, where 0 is mapped to 0
+        let mut inverse = 1;
+        let mut exponent = 0;
+        while exponent < 254 {
+            inverse = gf_mul(inverse, x as u8);
+            exponent += 1;
+        }
+        sbox[x] = inverse
+            ^ inverse.rotate_left(1)
+            ^ inverse.rotate_left(2)
+            ^ inverse.rotate_left(3)
+            ^ inverse.rotate_left(4)
+            ^ 0x63;
+        x += 1;
+    }
+    sbox
+}
+
+const fn generate_inv_sbox(sbox: &[u8; 256]) -> [u8; 256] {
+    let mut inv_sbox = [0; 256];
+    let mut x = 0;
+    while x < 256 {
+        inv_sbox[sbox[x] as usize] = x as u8;
+        x += 1;
+    }
+    inv_sbox
+}
+
+/// Generate the table which maps a byte to the column of the S-box output
+/// multiplied by the coefficients.
+const fn generate_round_table(sbox: &[u8; 256], coefficients: [u8; 4]) -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut x = 0;
+    while x < 256 {
+        let s = sbox[x];
+        table[x] = u32::from_be_bytes([
+            gf_mul(s, coefficients[0]),
+            gf_mul(s, coefficients[1]),
+            gf_mul(s, coefficients[2]),
+            gf_mul(s, coefficients[3]),
+        ]);
+        x += 1;
+    }
+    table
+}
+
+/// SubWord of the key expansion.
+pub(super) fn sub_word(word: u32) -> u32 {
+    let bytes = word.to_be_bytes();
+    u32::from_be_bytes([
+        SBOX[bytes[0] as usize],
+        SBOX[bytes[1] as usize],
+        SBOX[bytes[2] as usize],
+        SBOX[bytes[3] as usize],
+    ])
+}
+
+/// Look up the round table for the bytes of 4 columns in the rows 0 to 3.
+fn round(table: &[u32; 256], columns: [u32; 4]) -> u32 {
+    table[(columns[0] >> 24) as usize]
+        ^ table[((columns[1] >> 16) & 0xFF) as usize].rotate_right(8)
+        ^ table[((columns[2] >> 8) & 0xFF) as usize].rotate_right(16)
+        ^ table[(columns[3] & 0xFF) as usize].rotate_right(24)
+}
+
+/// Look up the S-box for the bytes of 4 columns in the rows 0 to 3.
+fn final_round(sbox: &[u8; 256], columns: [u32; 4]) -> u32 {
+    u32::from_be_bytes([
+        sbox[(columns[0] >> 24) as usize],
+        sbox[((columns[1] >> 16) & 0xFF) as usize],
+        sbox[((columns[2] >> 8) & 0xFF) as usize],
+        sbox[(columns[3] & 0xFF) as usize],
+    ])
+}
+
+/// Encrypt the state with the round keys.
+pub(super) fn encrypt(state: [u32; 4], keys: &[u32]) -> [u32; 4] {
+    let [mut s0, mut s1, mut s2, mut s3] = state;
+    s0 ^= keys[0];
+    s1 ^= keys[1];
+    s2 ^= keys[2];
+    s3 ^= keys[3];
+
+    // ShiftRows takes the row r of the column c from the column c + r
+    let rounds = keys.len() / 4 - 1;
+    for key in keys[4..4 * rounds].chunks(4) {
+        let t0 = round(&TE, [s0, s1, s2, s3]) ^ key[0];
+        let t1 = round(&TE, [s1, s2, s3, s0]) ^ key[1];
+        let t2 = round(&TE, [s2, s3, s0, s1]) ^ key[2];
+        let t3 = round(&TE, [s3, s0, s1, s2]) ^ key[3];
+        s0 = t0;
+        s1 = t1;
+        s2 = t2;
+        s3 = t3;
+    }
+
+    let key = &keys[4 * rounds..];
+    [
+        final_round(&SBOX, [s0, s1, s2, s3]) ^ key[0],
+        final_round(&SBOX, [s1, s2, s3, s0]) ^ key[1],
+        final_round(&SBOX, [s2, s3, s0, s1]) ^ key[2],
+        final_round(&SBOX, [s3, s0, s1, s2]) ^ key[3],
+    ]
+}
+
+/// Decrypt the state with the round keys of the equivalent inverse cipher.
+pub(super) fn decrypt(state: [u32; 4], keys: &[u32]) -> [u32; 4] {
+    let [mut s0, mut s1, mut s2, mut s3] = state;
+    s0 ^= keys[0];
+    s1 ^= keys[1];
+    s2 ^= keys[2];
+    s3 ^= keys[3];
+
+    // InvShiftRows takes the row r of the column c from the column c - r
+    let rounds = keys.len() / 4 - 1;
+    for key in keys[4..4 * rounds].chunks(4) {
+        let t0 = round(&TD, [s0, s3, s2, s1]) ^ key[0];
+        let t1 = round(&TD, [s1, s0, s3, s2]) ^ key[1];
+        let t2 = round(&TD, [s2, s1, s0, s3]) ^ key[2];
+        let t3 = round(&TD, [s3, s2, s1, s0]) ^ key[3];
+        s0 = t0;
+        s1 = t1;
+        s2 = t2;
+        s3 = t3;
+    }
+
+    let key = &keys[4 * rounds..];
+    [
+        final_round(&INV_SBOX, [s0, s3, s2, s1]) ^ key[0],
+        final_round(&INV_SBOX, [s1, s0, s3, s2]) ^ key[1],
+        final_round(&INV_SBOX, [s2, s1, s0, s3]) ^ key[2],
+        final_round(&INV_SBOX, [s3, s2, s1, s0]) ^ key[3],
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sbox() {
+        // FIPS 197 Figure 7 and 14
+        assert_eq!(SBOX[0x00], 0x63);
+        assert_eq!(SBOX[0x53], 0xed);
+        assert_eq!(SBOX[0xff], 0x16);
+        assert_eq!(INV_SBOX[0x00], 0x52);
+        assert_eq!(INV_SBOX[0xed], 0x53);
+    }
+}
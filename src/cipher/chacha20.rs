@@ -0,0 +1,174 @@
+//! ChaCha20 stream cipher (RFC 8439) with the 96-bit nonce and the 32-bit
+//! block counter.
+
+use core::convert::TryInto;
+
+/// Key size in bytes.
+pub const KEY_SIZE: usize = 32;
+/// Nonce size in bytes.
+pub const NONCE_SIZE: usize = 12;
+/// Size in bytes of a block of the key stream.
+pub const BLOCK_SIZE: usize = 64;
+
+/// "expand 32-byte k" in little endian words.
+const CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+/// ChaCha20 which encrypts and decrypts data of any length with the key
+/// stream from the initial block counter.
+#[derive(Clone)]
+pub struct ChaCha20 {
+    /// Constants, key, block counter and nonce.
+    state: [u32; 16],
+    key_stream: [u8; BLOCK_SIZE],
+    /// Position of the unused key stream.
+    position: usize,
+    /// The block counter has wrapped around.
+    exhausted: bool,
+}
+
+impl ChaCha20 {
+    /// Create with the key, the nonce and the initial block counter, which
+    /// is 1 for AEAD encryption and 0 for the Poly1305 key.
+    pub fn new(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE], counter: u32) -> ChaCha20 {
+        let mut state = [0; 16];
+        state[..4].copy_from_slice(&CONSTANTS);
+        for (word, bytes) in state[4..12].iter_mut().zip(key.chunks(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+        state[12] = counter;
+        for (word, bytes) in state[13..].iter_mut().zip(nonce.chunks(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+        ChaCha20 { state, key_stream: [0; BLOCK_SIZE], position: BLOCK_SIZE, exhausted: false }
+    }
+
+    /// Encrypt or decrypt the data in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block counter overflows, i.e. after 256 GiB of the key
+    /// stream.
+    pub fn apply_key_stream(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            if self.position == BLOCK_SIZE {
+                assert!(!self.exhausted, "ChaCha20 block counter overflow");
+                self.key_stream = block(&self.state);
+                let (counter, overflow) = self.state[12].overflowing_add(1);
+                self.state[12] = counter;
+                self.exhausted = overflow;
+                self.position = 0;
+            }
+            *byte ^= self.key_stream[self.position];
+            self.position += 1;
+        }
+    }
+}
+
+/// Compute a block of the key stream.
+fn block(state: &[u32; 16]) -> [u8; BLOCK_SIZE] {
+    let mut working_state = *state;
+    for _ in 0..10 {
+        // column rounds
+        quarter_round(&mut working_state, 0, 4, 8, 12);
+        quarter_round(&mut working_state, 1, 5, 9, 13);
+        quarter_round(&mut working_state, 2, 6, 10, 14);
+        quarter_round(&mut working_state, 3, 7, 11, 15);
+        // diagonal rounds
+        quarter_round(&mut working_state, 0, 5, 10, 15);
+        quarter_round(&mut working_state, 1, 6, 11, 12);
+        quarter_round(&mut working_state, 2, 7, 8, 13);
+        quarter_round(&mut working_state, 3, 4, 9, 14);
+    }
+
+    let mut output = [0; BLOCK_SIZE];
+    for (i, bytes) in output.chunks_mut(4).enumerate() {
+        bytes.copy_from_slice(&working_state[i].wrapping_add(state[i]).to_le_bytes());
+    }
+    output
+}
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use crate::test_util::hex;
+
+    const KEY: [u8; KEY_SIZE] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+        0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+    ];
+    const SUNSCREEN: &[u8] = b"Ladies and Gentlemen of the class of '99: \
+        If I could offer you only one tip for the future, sunscreen would be it.";
+
+    #[test]
+    fn test_quarter_round() {
+        // RFC 8439 2.1.1
+        let mut state = [0; 16];
+        state[..4].copy_from_slice(&[0x1111_1111, 0x0102_0304, 0x9b8d_6f43, 0x0123_4567]);
+        quarter_round(&mut state, 0, 1, 2, 3);
+        assert_eq!(state[..4], [0xea2a_92f4, 0xcb1c_f8ce, 0x4581_472e, 0x5881_c4bb]);
+    }
+
+    #[test]
+    fn test_block() {
+        // RFC 8439 2.3.2
+        let nonce = [0, 0, 0, 0x09, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+        let mut data = [0; BLOCK_SIZE];
+        ChaCha20::new(&KEY, &nonce, 1).apply_key_stream(&mut data);
+        assert_eq!(data[..], hex(concat!(
+            "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e",
+            "d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e",
+        ))[..]);
+    }
+
+    #[test]
+    fn test_encrypt() {
+        // RFC 8439 2.4.2
+        let nonce = [0, 0, 0, 0, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+        let ciphertext = hex(concat!(
+            "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b",
+            "f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8",
+            "07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736",
+            "5af90bbf74a35be6b40b8eedf2785e42874d",
+        ));
+        let mut data = SUNSCREEN.to_vec();
+        ChaCha20::new(&KEY, &nonce, 1).apply_key_stream(&mut data);
+        assert_eq!(data, ciphertext);
+
+        // the key stream continues across calls
+        let mut chacha20 = ChaCha20::new(&KEY, &nonce, 1);
+        for chunk in data.chunks_mut(7) {
+            chacha20.apply_key_stream(chunk);
+        }
+        assert_eq!(data, SUNSCREEN);
+    }
+
+    #[test]
+    fn test_poly1305_key_generation() {
+        // RFC 8439 2.6.2
+        let key: Vec<u8> = (0x80..0xa0).collect();
+        let nonce = [0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7];
+        let mut data = [0; 32];
+        ChaCha20::new(key[..].try_into().unwrap(), &nonce, 0).apply_key_stream(&mut data);
+        assert_eq!(data[..], hex("8ad5a08b905f81cc815040274ab29471a833b637e3fd0da508dbb8e2fdd1a646")[..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "ChaCha20 block counter overflow")]
+    fn test_counter_overflow() {
+        let mut chacha20 = ChaCha20::new(&KEY, &[0; NONCE_SIZE], u32::MAX);
+        chacha20.apply_key_stream(&mut [0; BLOCK_SIZE]);
+        chacha20.apply_key_stream(&mut [0; 1]);
+    }
+}
@@ -0,0 +1,513 @@
+//! DES and Triple-DES (FIPS 46-3, SP 800-67) for legacy cipher suites such as
+//! `TLS_RSA_WITH_3DES_EDE_CBC_SHA`.
+//!
+//! The S-boxes are looked up by secret values, so this implementation is not
+//! constant-time. Don't use it unless the peer supports nothing else.
+
+use core::convert::TryInto;
+use alloc::vec::Vec;
+use super::{BlockCipher, KeyError};
+
+/// Block size of DES in bytes.
+pub const BLOCK_SIZE: usize = 8;
+
+/// Key size of DES in bytes including the parity bits.
+pub const KEY_SIZE: usize = 8;
+
+/// Initial permutation.
+const IP: [u8; 64] = [
+    58, 50, 42, 34, 26, 18, 10, 2,
+    60, 52, 44, 36, 28, 20, 12, 4,
+    62, 54, 46, 38, 30, 22, 14, 6,
+    64, 56, 48, 40, 32, 24, 16, 8,
+    57, 49, 41, 33, 25, 17,  9, 1,
+    59, 51, 43, 35, 27, 19, 11, 3,
+    61, 53, 45, 37, 29, 21, 13, 5,
+    63, 55, 47, 39, 31, 23, 15, 7,
+];
+
+/// Final permutation, the inverse of the initial permutation.
+const FP: [u8; 64] = [
+    40, 8, 48, 16, 56, 24, 64, 32,
+    39, 7, 47, 15, 55, 23, 63, 31,
+    38, 6, 46, 14, 54, 22, 62, 30,
+    37, 5, 45, 13, 53, 21, 61, 29,
+    36, 4, 44, 12, 52, 20, 60, 28,
+    35, 3, 43, 11, 51, 19, 59, 27,
+    34, 2, 42, 10, 50, 18, 58, 26,
+    33, 1, 41,  9, 49, 17, 57, 25,
+];
+
+/// Permutation of the output of the S-boxes.
+const P: [u8; 32] = [
+    16,  7, 20, 21, 29, 12, 28, 17,
+     1, 15, 23, 26,  5, 18, 31, 10,
+     2,  8, 24, 14, 32, 27,  3,  9,
+    19, 13, 30,  6, 22, 11,  4, 25,
+];
+
+/// Permuted choice 1, which selects 56 bits of the key without parity bits.
+const PC1: [u8; 56] = [
+    57, 49, 41, 33, 25, 17,  9,
+     1, 58, 50, 42, 34, 26, 18,
+    10,  2, 59, 51, 43, 35, 27,
+    19, 11,  3, 60, 52, 44, 36,
+    63, 55, 47, 39, 31, 23, 15,
+     7, 62, 54, 46, 38, 30, 22,
+    14,  6, 61, 53, 45, 37, 29,
+    21, 13,  5, 28, 20, 12,  4,
+];
+
+/// Permuted choice 2, which selects 48 bits of a subkey from 56 bits.
+const PC2: [u8; 48] = [
+    14, 17, 11, 24,  1,  5,
+     3, 28, 15,  6, 21, 10,
+    23, 19, 12,  4, 26,  8,
+    16,  7, 27, 20, 13,  2,
+    41, 52, 31, 37, 47, 55,
+    30, 40, 51, 45, 33, 48,
+    44, 49, 39, 56, 34, 53,
+    46, 42, 50, 36, 29, 32,
+];
+
+/// Left rotations of the key halves in each round.
+const ROTATIONS: [u32; 16] = [1, 1, 2, 2, 2, 2, 2, 2, 1, 2, 2, 2, 2, 2, 2, 1];
+
+/// S-boxes, each of 4 rows of 16 columns.
+const S_BOXES: [[u8; 64]; 8] = [
+    [
+        14,  4, 13,  1,  2, 15, 11,  8,  3, 10,  6, 12,  5,  9,  0,  7,
+         0, 15,  7,  4, 14,  2, 13,  1, 10,  6, 12, 11,  9,  5,  3,  8,
+         4,  1, 14,  8, 13,  6,  2, 11, 15, 12,  9,  7,  3, 10,  5,  0,
+        15, 12,  8,  2,  4,  9,  1,  7,  5, 11,  3, 14, 10,  0,  6, 13,
+    ],
+    [
+        15,  1,  8, 14,  6, 11,  3,  4,  9,  7,  2, 13, 12,  0,  5, 10,
+         3, 13,  4,  7, 15,  2,  8, 14, 12,  0,  1, 10,  6,  9, 11,  5,
+         0, 14,  7, 11, 10,  4, 13,  1,  5,  8, 12,  6,  9,  3,  2, 15,
+        13,  8, 10,  1,  3, 15,  4,  2, 11,  6,  7, 12,  0,  5, 14,  9,
+    ],
+    [
+        10,  0,  9, 14,  6,  3, 15,  5,  1, 13, 12,  7, 11,  4,  2,  8,
+        13,  7,  0,  9,  3,  4,  6, 10,  2,  8,  5, 14, 12, 11, 15,  1,
+        13,  6,  4,  9,  8, 15,  3,  0, 11,  1,  2, 12,  5, 10, 14,  7,
+         1, 10, 13,  0,  6,  9,  8,  7,  4, 15, 14,  3, 11,  5,  2, 12,
+    ],
+    [
+         7, 13, 14,  3,  0,  6,  9, 10,  1,  2,  8,  5, 11, 12,  4, 15,
+        13,  8, 11,  5,  6, 15,  0,  3,  4,  7,  2, 12,  1, 10, 14,  9,
+        10,  6,  9,  0, 12, 11,  7, 13, 15,  1,  3, 14,  5,  2,  8,  4,
+         3, 15,  0,  6, 10,  1, 13,  8,  9,  4,  5, 11, 12,  7,  2, 14,
+    ],
+    [
+         2, 12,  4,  1,  7, 10, 11,  6,  8,  5,  3, 15, 13,  0, 14,  9,
+        14, 11,  2, 12,  4,  7, 13,  1,  5,  0, 15, 10,  3,  9,  8,  6,
+         4,  2,  1, 11, 10, 13,  7,  8, 15,  9, 12,  5,  6,  3,  0, 14,
+        11,  8, 12,  7,  1, 14,  2, 13,  6, 15,  0,  9, 10,  4,  5,  3,
+    ],
+    [
+        12,  1, 10, 15,  9,  2,  6,  8,  0, 13,  3,  4, 14,  7,  5, 11,
+        10, 15,  4,  2,  7, 12,  9,  5,  6,  1, 13, 14,  0, 11,  3,  8,
+         9, 14, 15,  5,  2,  8, 12,  3,  7,  0,  4, 10,  1, 13, 11,  6,
+         4,  3,  2, 12,  9,  5, 15, 10, 11, 14,  1,  7,  6,  0,  8, 13,
+    ],
+    [
+         4, 11,  2, 14, 15,  0,  8, 13,  3, 12,  9,  7,  5, 10,  6,  1,
+        13,  0, 11,  7,  4,  9,  1, 10, 14,  3,  5, 12,  2, 15,  8,  6,
+         1,  4, 11, 13, 12,  3,  7, 14, 10, 15,  6,  8,  0,  5,  9,  2,
+         6, 11, 13,  8,  1,  4, 10,  7,  9,  5,  0, 15, 14,  2,  3, 12,
+    ],
+    [
+        13,  2,  8,  4,  6, 15, 11,  1, 10,  9,  3, 14,  5,  0, 12,  7,
+         1, 15, 13,  8, 10,  3,  7,  4, 12,  5,  6, 11,  0, 14,  9,  2,
+         7, 11,  4,  1,  9, 12, 14,  2,  0,  6, 10, 13, 15,  3,  5,  8,
+         2,  1, 14,  7,  4, 10,  8, 13, 15, 12,  9,  0,  3,  5,  6, 11,
+    ],
+];
+
+/// S-boxes combined with the permutation P, indexed by the 6-bit input.
+const SP_BOXES: [[u32; 64]; 8] = generate_sp_boxes();
+
+/// Weak keys, whose all subkeys are the same, without parity bits.
+const WEAK_KEYS: [u64; 4] = [
+    0x0101010101010101,
+    0xFEFEFEFEFEFEFEFE,
+    0xE0E0E0E0F1F1F1F1,
+    0x1F1F1F1F0E0E0E0E,
+];
+
+/// Semi-weak keys, where each pair of them decrypts what the other encrypts.
+const SEMI_WEAK_KEYS: [u64; 12] = [
+    0x01FE01FE01FE01FE, 0xFE01FE01FE01FE01,
+    0x1FE01FE00EF10EF1, 0xE01FE01FF10EF10E,
+    0x01E001E001F101F1, 0xE001E001F101F101,
+    0x1FFE1FFE0EFE0EFE, 0xFE1FFE1FFE0EFE0E,
+    0x011F011F010E010E, 0x1F011F010E010E01,
+    0xE0FEE0FEF1FEF1FE, 0xFEE0FEE0FEF1FEF1,
+];
+
+/// Mask to ignore the parity bit of each byte.
+const PARITY_MASK: u64 = 0xFEFEFEFEFEFEFEFE;
+
+/// Permute bits by the table of 1-based bit positions counted from the most
+/// significant bit of `input_bits` bits.
+const fn permute(input: u64, input_bits: u32, table: &[u8]) -> u64 {
+    let mut output = 0;
+    let mut index = 0;
+    while index < table.len() {
+        let bit = (input >> (input_bits - table[index] as u32)) & 1;
+        output = (output << 1) | bit;
+        index += 1;
+    }
+    output
+}
+
+/// Generate the tables mapping the 6-bit input of each S-box to its 4-bit
+/// output at its position in the 32-bit half block, permuted by P.
+const fn generate_sp_boxes() -> [[u32; 64]; 8] {
+    let mut sp_boxes = [[0; 64]; 8];
+    let mut i = 0;
+    while i < 8 {
+        let mut input = 0;
+        while input < 64 {
+            // the outer bits select the row and the inner bits the column
+            let row = ((input & 0x20) >> 4) | (input & 0x01);
+            let column = (input >> 1) & 0x0F;
+            let output = (S_BOXES[i][row * 16 + column] as u64) << (28 - 4 * i);
+            sp_boxes[i][input] = permute(output, 32, &P) as u32;
+            input += 1;
+        }
+        i += 1;
+    }
+    sp_boxes
+}
+
+/// Check if the key is one of the weak or semi-weak keys of DES, ignoring the
+/// parity bits.
+pub fn is_weak_key(key: &[u8; KEY_SIZE]) -> bool {
+    let key = u64::from_be_bytes(*key) & PARITY_MASK;
+    WEAK_KEYS.iter()
+        .chain(SEMI_WEAK_KEYS.iter())
+        .any(|weak_key| { weak_key & PARITY_MASK == key })
+}
+
+/// Check the key of DES or Triple-DES.
+/// A Triple-DES key is weak if any of its DES keys is weak, or if it degrades
+/// into a single DES, i.e. the first or the last DES key equals the second.
+pub fn check_key(key: &[u8]) -> Result<(), KeyError> {
+    if !matches!(key.len(), KEY_SIZE | TripleDes::KEY_SIZE_2KEY | TripleDes::KEY_SIZE_3KEY) {
+        return Err(KeyError::InvalidLength(key.len()));
+    }
+
+    let keys: Vec<u64> = key.chunks(KEY_SIZE)
+        .map(|chunk| { u64::from_be_bytes(chunk.try_into().unwrap()) & PARITY_MASK })
+        .collect();
+    if keys.iter().any(|key| { is_weak_key(&key.to_be_bytes()) }) {
+        return Err(KeyError::WeakKey);
+    }
+    if keys.len() > 1 && (keys[0] == keys[1] || keys.get(2) == Some(&keys[1])) {
+        return Err(KeyError::WeakKey);
+    }
+    Ok(())
+}
+
+/// DES block cipher.
+#[derive(Clone)]
+pub struct Des {
+    /// 48-bit subkeys of the rounds.
+    subkeys: [u64; 16],
+}
+
+impl Des {
+    /// Create with the key of 8 bytes. The parity bits are ignored.
+    pub fn new(key: &[u8]) -> Result<Des, KeyError> {
+        let key: [u8; KEY_SIZE] = key.try_into()
+            .map_err(|_| { KeyError::InvalidLength(key.len()) })?;
+        Ok(Des { subkeys: generate_subkeys(u64::from_be_bytes(key)) })
+    }
+
+    fn encrypt_u64(&self, block: u64) -> u64 {
+        crypt(block, self.subkeys.iter())
+    }
+
+    fn decrypt_u64(&self, block: u64) -> u64 {
+        crypt(block, self.subkeys.iter().rev())
+    }
+}
+
+impl BlockCipher for Des {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let output = self.encrypt_u64(read_block(block));
+        block.copy_from_slice(&output.to_be_bytes());
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let output = self.decrypt_u64(read_block(block));
+        block.copy_from_slice(&output.to_be_bytes());
+    }
+}
+
+/// Triple-DES block cipher in the encrypt-decrypt-encrypt (EDE) mode.
+#[derive(Clone)]
+pub struct TripleDes {
+    des1: Des,
+    des2: Des,
+    des3: Des,
+}
+
+impl TripleDes {
+    /// Key size of the keying option 2, where the third key is the first.
+    pub const KEY_SIZE_2KEY: usize = 2 * KEY_SIZE;
+    /// Key size of the keying option 1 with 3 independent keys.
+    pub const KEY_SIZE_3KEY: usize = 3 * KEY_SIZE;
+
+    /// Create with the key of 16 bytes for 2-key Triple-DES or 24 bytes for
+    /// 3-key Triple-DES. The parity bits are ignored.
+    pub fn new(key: &[u8]) -> Result<TripleDes, KeyError> {
+        let (key1, key2, key3) = match key.len() {
+            TripleDes::KEY_SIZE_2KEY => (&key[..8], &key[8..16], &key[..8]),
+            TripleDes::KEY_SIZE_3KEY => (&key[..8], &key[8..16], &key[16..24]),
+            len => return Err(KeyError::InvalidLength(len)),
+        };
+        Ok(TripleDes {
+            des1: Des::new(key1)?,
+            des2: Des::new(key2)?,
+            des3: Des::new(key3)?,
+        })
+    }
+}
+
+impl BlockCipher for TripleDes {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let output = self.des1.encrypt_u64(read_block(block));
+        let output = self.des2.decrypt_u64(output);
+        let output = self.des3.encrypt_u64(output);
+        block.copy_from_slice(&output.to_be_bytes());
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let output = self.des3.decrypt_u64(read_block(block));
+        let output = self.des2.encrypt_u64(output);
+        let output = self.des1.decrypt_u64(output);
+        block.copy_from_slice(&output.to_be_bytes());
+    }
+}
+
+fn read_block(block: &[u8]) -> u64 {
+    assert_eq!(block.len(), BLOCK_SIZE, "invalid block length");
+    u64::from_be_bytes(block.try_into().unwrap())
+}
+
+/// Generate the subkeys of 16 rounds.
+fn generate_subkeys(key: u64) -> [u64; 16] {
+    let key = permute(key, 64, &PC1);
+    let mut c = (key >> 28) as u32;
+    let mut d = (key & 0x0FFFFFFF) as u32;
+    let mut subkeys = [0; 16];
+    for (subkey, rotation) in subkeys.iter_mut().zip(ROTATIONS.iter()) {
+        c = ((c << rotation) | (c >> (28 - rotation))) & 0x0FFFFFFF;
+        d = ((d << rotation) | (d >> (28 - rotation))) & 0x0FFFFFFF;
+        *subkey = permute(((c as u64) << 28) | d as u64, 56, &PC2);
+    }
+    subkeys
+}
+
+/// Encrypt or decrypt a block by the Feistel network with the subkeys, which
+/// are in the reverse order to decrypt.
+fn crypt<'a>(block: u64, subkeys: impl Iterator<Item = &'a u64>) -> u64 {
+    let block = permute(block, 64, &IP);
+    let mut left = (block >> 32) as u32;
+    let mut right = block as u32;
+    for subkey in subkeys {
+        let next = left ^ feistel(right, *subkey);
+        left = right;
+        right = next;
+    }
+    // the halves are not swapped after the last round
+    permute(((right as u64) << 32) | left as u64, 64, &FP)
+}
+
+/// The Feistel function of the half block and the 48-bit subkey.
+fn feistel(half: u32, subkey: u64) -> u32 {
+    // the expansion E takes 6 bits starting from the bit before each 4 bits
+    let rotated = half.rotate_right(1);
+    let mut output = 0;
+    for (i, sp_box) in SP_BOXES.iter().enumerate() {
+        let expanded = rotated.rotate_left(4 * i as u32) >> 26;
+        let input = expanded as u64 ^ ((subkey >> (42 - 6 * i)) & 0x3F);
+        output |= sp_box[input as usize];
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Known-answer tests of NIST SP 800-17 for FIPS 46-3 as
+    /// (key, plaintext, ciphertext).
+    const KNOWN_ANSWER_VECTORS: [(u64, u64, u64); 10] = [
+        // variable plaintext
+        (0x0101010101010101, 0x8000000000000000, 0x95F8A5E5DD31D900),
+        (0x0101010101010101, 0x4000000000000000, 0xDD7F121CA5015619),
+        (0x0101010101010101, 0x0000000000000001, 0x166B40B44ABA4BD6),
+        // variable key
+        (0x8001010101010101, 0x0000000000000000, 0x95A8D72813DAA94D),
+        (0x4001010101010101, 0x0000000000000000, 0x0EEC1487DD8C26D5),
+        (0x0101010101010180, 0x0000000000000000, 0x9CC62DF43B6EED74),
+        // permutation operation
+        (0x1046913489980131, 0x0000000000000000, 0x88D55E54F54C97B4),
+        // substitution table
+        (0x7CA110454A1A6E57, 0x01A1D6D039776742, 0x690F5B0D9A26939B),
+        (0x0131D9619DC1376E, 0x5CD54CA83DEF57DA, 0x7A389D10354BD271),
+        // FIPS 46-3 worked example
+        (0x133457799BBCDFF1, 0x0123456789ABCDEF, 0x85E813540F0AB405),
+    ];
+
+    #[test]
+    fn test_known_answers() {
+        for (key, plaintext, ciphertext) in KNOWN_ANSWER_VECTORS {
+            let des = Des::new(&key.to_be_bytes()).unwrap();
+            let mut block = plaintext.to_be_bytes();
+            des.encrypt_block(&mut block);
+            assert_eq!(block, ciphertext.to_be_bytes(), "key {:016X}", key);
+            des.decrypt_block(&mut block);
+            assert_eq!(block, plaintext.to_be_bytes(), "key {:016X}", key);
+        }
+    }
+
+    #[test]
+    fn test_parity_bits_ignored() {
+        let des = Des::new(&0x0101010101010101u64.to_be_bytes()).unwrap();
+        let des_without_parity = Des::new(&[0; 8]).unwrap();
+        let mut block = [0x80, 0, 0, 0, 0, 0, 0, 0];
+        let mut block_without_parity = block;
+        des.encrypt_block(&mut block);
+        des_without_parity.encrypt_block(&mut block_without_parity);
+        assert_eq!(block, block_without_parity);
+    }
+
+    /// SP 800-67 example with 3 keys.
+    const TRIPLE_DES_3KEY: &[u8] = &[
+        0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF,
+        0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF, 0x01,
+        0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF, 0x01, 0x23,
+    ];
+    const TRIPLE_DES_PLAINTEXT: &[u8] = b"The qufck brown fox jump";
+    const TRIPLE_DES_3KEY_CIPHERTEXT: &[u8] = &[
+        0xA8, 0x26, 0xFD, 0x8C, 0xE5, 0x3B, 0x85, 0x5F,
+        0xCC, 0xE2, 0x1C, 0x81, 0x12, 0x25, 0x6F, 0xE6,
+        0x68, 0xD5, 0xC0, 0x5D, 0xD9, 0xB6, 0xB9, 0x00,
+    ];
+    /// The same example with 2 keys, generated by OpenSSL.
+    const TRIPLE_DES_2KEY_CIPHERTEXT: &[u8] = &[
+        0xC4, 0x48, 0x62, 0xF7, 0x0C, 0xF2, 0xFB, 0xDC,
+        0x90, 0x77, 0xD0, 0x90, 0x9F, 0xA9, 0x1B, 0x88,
+        0x4C, 0xAB, 0xD6, 0x1F, 0xC5, 0x8E, 0x0C, 0xBB,
+    ];
+
+    fn encrypt_blocks<C: BlockCipher>(cipher: &C, input: &[u8]) -> Vec<u8> {
+        let mut output = input.to_vec();
+        output.chunks_mut(C::BLOCK_SIZE).for_each(|block| { cipher.encrypt_block(block) });
+        output
+    }
+
+    fn decrypt_blocks<C: BlockCipher>(cipher: &C, input: &[u8]) -> Vec<u8> {
+        let mut output = input.to_vec();
+        output.chunks_mut(C::BLOCK_SIZE).for_each(|block| { cipher.decrypt_block(block) });
+        output
+    }
+
+    #[test]
+    fn test_triple_des_3key() {
+        let cipher = TripleDes::new(TRIPLE_DES_3KEY).unwrap();
+        assert_eq!(encrypt_blocks(&cipher, TRIPLE_DES_PLAINTEXT), TRIPLE_DES_3KEY_CIPHERTEXT);
+        assert_eq!(decrypt_blocks(&cipher, TRIPLE_DES_3KEY_CIPHERTEXT), TRIPLE_DES_PLAINTEXT);
+    }
+
+    #[test]
+    fn test_triple_des_2key() {
+        let cipher = TripleDes::new(&TRIPLE_DES_3KEY[..16]).unwrap();
+        assert_eq!(encrypt_blocks(&cipher, TRIPLE_DES_PLAINTEXT), TRIPLE_DES_2KEY_CIPHERTEXT);
+        assert_eq!(decrypt_blocks(&cipher, TRIPLE_DES_2KEY_CIPHERTEXT), TRIPLE_DES_PLAINTEXT);
+    }
+
+    #[test]
+    fn test_triple_des_same_keys_as_des() {
+        let key = 0x133457799BBCDFF1u64.to_be_bytes();
+        let cipher = TripleDes::new(&[key, key, key].concat()).unwrap();
+        let mut block = 0x0123456789ABCDEFu64.to_be_bytes();
+        cipher.encrypt_block(&mut block);
+        assert_eq!(block, 0x85E813540F0AB405u64.to_be_bytes());
+    }
+
+    #[test]
+    fn test_invalid_key_length() {
+        assert!(matches!(Des::new(&[0; 7]), Err(KeyError::InvalidLength(7))));
+        assert!(matches!(TripleDes::new(&[0; 8]), Err(KeyError::InvalidLength(8))));
+        assert_eq!(check_key(&[0; 20]), Err(KeyError::InvalidLength(20)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_block_length() {
+        Des::new(&[0; 8]).unwrap().encrypt_block(&mut [0; 7]);
+    }
+
+    #[test]
+    fn test_weak_keys() {
+        for key in WEAK_KEYS.iter().chain(SEMI_WEAK_KEYS.iter()) {
+            assert!(is_weak_key(&key.to_be_bytes()), "{:016X}", key);
+            // the parity bits don't matter
+            assert!(is_weak_key(&(key ^ 0x0101010101010101).to_be_bytes()), "{:016X}", key);
+        }
+        assert!(!is_weak_key(&0x133457799BBCDFF1u64.to_be_bytes()));
+    }
+
+    #[test]
+    fn test_weak_key_encrypts_twice_to_plaintext() {
+        for key in WEAK_KEYS {
+            let des = Des::new(&key.to_be_bytes()).unwrap();
+            let mut block = *b"weak key";
+            des.encrypt_block(&mut block);
+            des.encrypt_block(&mut block);
+            assert_eq!(&block, b"weak key");
+        }
+    }
+
+    #[test]
+    fn test_semi_weak_key_pairs() {
+        for pair in SEMI_WEAK_KEYS.chunks(2) {
+            let des1 = Des::new(&pair[0].to_be_bytes()).unwrap();
+            let des2 = Des::new(&pair[1].to_be_bytes()).unwrap();
+            let mut block = *b"semiweak";
+            des1.encrypt_block(&mut block);
+            des2.encrypt_block(&mut block);
+            assert_eq!(&block, b"semiweak");
+        }
+    }
+
+    #[test]
+    fn test_check_key() {
+        assert_eq!(check_key(&0x133457799BBCDFF1u64.to_be_bytes()), Ok(()));
+        assert_eq!(check_key(TRIPLE_DES_3KEY), Ok(()));
+        assert_eq!(check_key(&TRIPLE_DES_3KEY[..16]), Ok(()));
+        assert_eq!(check_key(&0xFEFEFEFEFEFEFEFEu64.to_be_bytes()), Err(KeyError::WeakKey));
+
+        // one of the keys is weak
+        let mut key = TRIPLE_DES_3KEY.to_vec();
+        key[16..].copy_from_slice(&0x1F1F1F1F0E0E0E0Eu64.to_be_bytes());
+        assert_eq!(check_key(&key), Err(KeyError::WeakKey));
+
+        // degrades into single DES
+        let key = [&TRIPLE_DES_3KEY[..8], &TRIPLE_DES_3KEY[..8], &TRIPLE_DES_3KEY[16..]].concat();
+        assert_eq!(check_key(&key), Err(KeyError::WeakKey));
+        let key = [&TRIPLE_DES_3KEY[..8], &TRIPLE_DES_3KEY[8..16], &TRIPLE_DES_3KEY[8..16]].concat();
+        assert_eq!(check_key(&key), Err(KeyError::WeakKey));
+        assert_eq!(check_key(&[&TRIPLE_DES_3KEY[..8], &TRIPLE_DES_3KEY[..8]].concat()), Err(KeyError::WeakKey));
+    }
+}
@@ -0,0 +1,392 @@
+//! Block cipher modes of operation (SP 800-38A), which encrypt and decrypt in
+//! place with any `BlockCipher`.
+
+use core::fmt;
+use alloc::vec;
+use alloc::vec::Vec;
+use super::BlockCipher;
+
+mod padding;
+
+pub use padding::{Padding, pad, check_padding, unpad};
+
+/// Electronic codebook mode, which encrypts each block independently.
+/// Don't use it for more than one block of secret data.
+pub struct Ecb<C: BlockCipher> {
+    cipher: C,
+}
+
+impl<C: BlockCipher> Ecb<C> {
+    pub fn new(cipher: C) -> Ecb<C> {
+        Ecb { cipher }
+    }
+
+    /// Encrypt the data of a multiple of the block size in place.
+    pub fn encrypt(&self, data: &mut [u8]) -> Result<(), ModeError> {
+        check_length::<C>(data)?;
+        data.chunks_mut(C::BLOCK_SIZE).for_each(|block| { self.cipher.encrypt_block(block) });
+        Ok(())
+    }
+
+    /// Decrypt the data of a multiple of the block size in place.
+    pub fn decrypt(&self, data: &mut [u8]) -> Result<(), ModeError> {
+        check_length::<C>(data)?;
+        data.chunks_mut(C::BLOCK_SIZE).for_each(|block| { self.cipher.decrypt_block(block) });
+        Ok(())
+    }
+
+    /// Pad and encrypt the data in place.
+    pub fn encrypt_padded(&self, data: &mut Vec<u8>, padding: Padding) {
+        pad(data, C::BLOCK_SIZE, padding);
+        data.chunks_mut(C::BLOCK_SIZE).for_each(|block| { self.cipher.encrypt_block(block) });
+    }
+
+    /// Decrypt the data in place and remove the padding checked in constant
+    /// time.
+    pub fn decrypt_padded(&self, data: &mut Vec<u8>, padding: Padding) -> Result<(), ModeError> {
+        self.decrypt(data)?;
+        let len = unpad(data, C::BLOCK_SIZE, padding)?;
+        data.truncate(len);
+        Ok(())
+    }
+}
+
+/// Cipher block chaining mode.
+///
+/// The IV is updated to the last ciphertext block after each call, so that
+/// consecutive calls continue the chain as TLS 1.0 does across records.
+pub struct Cbc<C: BlockCipher> {
+    cipher: C,
+    iv: Vec<u8>,
+}
+
+impl<C: BlockCipher> Cbc<C> {
+    /// Create with the IV of the block size.
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Cbc<C>, ModeError> {
+        let mut cbc = Cbc { cipher, iv: Vec::new() };
+        cbc.set_iv(iv)?;
+        Ok(cbc)
+    }
+
+    /// Get the IV of the next call.
+    pub fn iv(&self) -> &[u8] {
+        &self.iv
+    }
+
+    /// Set the IV, e.g. the explicit IV of a TLS 1.1 record.
+    pub fn set_iv(&mut self, iv: &[u8]) -> Result<(), ModeError> {
+        if iv.len() != C::BLOCK_SIZE {
+            return Err(ModeError::InvalidIvLength(iv.len()));
+        }
+        self.iv.clear();
+        self.iv.extend_from_slice(iv);
+        Ok(())
+    }
+
+    /// Encrypt the data of a multiple of the block size in place.
+    pub fn encrypt(&mut self, data: &mut [u8]) -> Result<(), ModeError> {
+        check_length::<C>(data)?;
+        for block in data.chunks_mut(C::BLOCK_SIZE) {
+            xor(block, &self.iv);
+            self.cipher.encrypt_block(block);
+            self.iv.copy_from_slice(block);
+        }
+        Ok(())
+    }
+
+    /// Decrypt the data of a multiple of the block size in place.
+    pub fn decrypt(&mut self, data: &mut [u8]) -> Result<(), ModeError> {
+        check_length::<C>(data)?;
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        // decrypt from the last block, so that the previous ciphertext block
+        // is still there
+        let next_iv = data[data.len() - C::BLOCK_SIZE..].to_vec();
+        for index in (0..data.len() / C::BLOCK_SIZE).rev() {
+            let (previous, rest) = data.split_at_mut(index * C::BLOCK_SIZE);
+            let block = &mut rest[..C::BLOCK_SIZE];
+            self.cipher.decrypt_block(block);
+            if index == 0 {
+                xor(block, &self.iv);
+            } else {
+                xor(block, &previous[previous.len() - C::BLOCK_SIZE..]);
+            }
+        }
+        self.iv = next_iv;
+        Ok(())
+    }
+
+    /// Pad and encrypt the data in place.
+    pub fn encrypt_padded(&mut self, data: &mut Vec<u8>, padding: Padding) {
+        pad(data, C::BLOCK_SIZE, padding);
+        self.encrypt(data).expect("padded data must be a multiple of the block size");
+    }
+
+    /// Decrypt the data in place and remove the padding checked in constant
+    /// time.
+    ///
+    /// The record layer should use `decrypt()` and `check_padding()` instead,
+    /// in order to check the MAC before reporting the padding error.
+    pub fn decrypt_padded(&mut self, data: &mut Vec<u8>, padding: Padding) -> Result<(), ModeError> {
+        self.decrypt(data)?;
+        let len = unpad(data, C::BLOCK_SIZE, padding)?;
+        data.truncate(len);
+        Ok(())
+    }
+}
+
+/// Counter mode, which XORs the data with the encrypted counter blocks.
+/// The whole counter block is incremented as a big-endian integer.
+///
+/// Encryption and decryption are the same operation, and the data can be of
+/// any length. Consecutive calls continue the key stream.
+pub struct Ctr<C: BlockCipher> {
+    cipher: C,
+    counter: Vec<u8>,
+    key_stream: Vec<u8>,
+    /// Position of the unused key stream.
+    position: usize,
+}
+
+impl<C: BlockCipher> Ctr<C> {
+    /// Create with the initial counter block of the block size.
+    pub fn new(cipher: C, counter: &[u8]) -> Result<Ctr<C>, ModeError> {
+        if counter.len() != C::BLOCK_SIZE {
+            return Err(ModeError::InvalidIvLength(counter.len()));
+        }
+        let key_stream = vec![0; C::BLOCK_SIZE];
+        Ok(Ctr { cipher, counter: counter.to_vec(), key_stream, position: C::BLOCK_SIZE })
+    }
+
+    /// Encrypt or decrypt the data in place.
+    pub fn apply_key_stream(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            if self.position == C::BLOCK_SIZE {
+                self.key_stream.copy_from_slice(&self.counter);
+                self.cipher.encrypt_block(&mut self.key_stream);
+                increment(&mut self.counter);
+                self.position = 0;
+            }
+            *byte ^= self.key_stream[self.position];
+            self.position += 1;
+        }
+    }
+}
+
+/// Errors of block cipher modes.
+#[derive(Debug, PartialEq)]
+pub enum ModeError {
+    /// The length of the data is not a multiple of the block size.
+    InvalidLength(usize),
+    /// The length of the IV or the counter block is not the block size.
+    InvalidIvLength(usize),
+    /// The padding of the decrypted data is malformed.
+    InvalidPadding,
+}
+
+impl fmt::Display for ModeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModeError::InvalidLength(len) => write!(f, "data length {} is not a multiple of the block size", len),
+            ModeError::InvalidIvLength(len) => write!(f, "invalid IV length {}", len),
+            ModeError::InvalidPadding => write!(f, "invalid padding"),
+        }
+    }
+}
+
+impl core::error::Error for ModeError {}
+
+fn check_length<C: BlockCipher>(data: &[u8]) -> Result<(), ModeError> {
+    if data.len().is_multiple_of(C::BLOCK_SIZE) {
+        Ok(())
+    } else {
+        Err(ModeError::InvalidLength(data.len()))
+    }
+}
+
+fn xor(block: &mut [u8], other: &[u8]) {
+    block.iter_mut().zip(other.iter()).for_each(|(byte, other_byte)| { *byte ^= other_byte });
+}
+
+/// Increment the big-endian integer, wrapping around to 0.
+fn increment(counter: &mut [u8]) {
+    for byte in counter.iter_mut().rev() {
+        *byte = byte.wrapping_add(1);
+        if *byte != 0 {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::hex;
+    use crate::cipher::{Aes, TripleDes};
+
+    const AES128_KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+    const AES256_KEY: &str = "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4";
+    const IV: &str = "000102030405060708090a0b0c0d0e0f";
+    const COUNTER: &str = "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+
+    /// Plaintext of SP 800-38A Appendix F.
+    const PLAINTEXT: &str = concat!(
+        "6bc1bee22e409f96e93d7e117393172a",
+        "ae2d8a571e03ac9c9eb76fac45af8e51",
+        "30c81c46a35ce411e5fbc1191a0a52ef",
+        "f69f2445df4f9b17ad2b417be66c3710",
+    );
+
+    /// SP 800-38A F.1.1 ECB-AES128.
+    const ECB_AES128_CIPHERTEXT: &str = concat!(
+        "3ad77bb40d7a3660a89ecaf32466ef97",
+        "f5d3d58503b9699de785895a96fdbaaf",
+        "43b1cd7f598ece23881b00e3ed030688",
+        "7b0c785e27e8ad3f8223207104725dd4",
+    );
+
+    /// SP 800-38A F.2.1 CBC-AES128.
+    const CBC_AES128_CIPHERTEXT: &str = concat!(
+        "7649abac8119b246cee98e9b12e9197d",
+        "5086cb9b507219ee95db113a917678b2",
+        "73bed6b8e3c1743b7116e69e22229516",
+        "3ff1caa1681fac09120eca307586e1a7",
+    );
+
+    /// SP 800-38A F.2.5 CBC-AES256.
+    const CBC_AES256_CIPHERTEXT: &str = concat!(
+        "f58c4c04d6e5f1ba779eabfb5f7bfbd6",
+        "9cfc4e967edb808d679f777bc6702c7d",
+        "39f23369a9d9bacfa530e26304231461",
+        "b2eb05e2c39be9fcda6c19078c6a9d1b",
+    );
+
+    /// SP 800-38A F.5.1 CTR-AES128.
+    const CTR_AES128_CIPHERTEXT: &str = concat!(
+        "874d6191b620e3261bef6864990db6ce",
+        "9806f66b7970fdff8617187bb9fffdff",
+        "5ae4df3edbd5d35e5b4f09020db03eab",
+        "1e031dda2fbe03d1792170a0f3009cee",
+    );
+
+    #[test]
+    fn test_ecb_aes128() {
+        let ecb = Ecb::new(Aes::new(&hex(AES128_KEY)).unwrap());
+        let mut data = hex(PLAINTEXT);
+        ecb.encrypt(&mut data).unwrap();
+        assert_eq!(data, hex(ECB_AES128_CIPHERTEXT));
+        ecb.decrypt(&mut data).unwrap();
+        assert_eq!(data, hex(PLAINTEXT));
+    }
+
+    #[test]
+    fn test_cbc_aes128() {
+        let aes = Aes::new(&hex(AES128_KEY)).unwrap();
+        let mut data = hex(PLAINTEXT);
+        Cbc::new(aes.clone(), &hex(IV)).unwrap().encrypt(&mut data).unwrap();
+        assert_eq!(data, hex(CBC_AES128_CIPHERTEXT));
+        Cbc::new(aes, &hex(IV)).unwrap().decrypt(&mut data).unwrap();
+        assert_eq!(data, hex(PLAINTEXT));
+    }
+
+    #[test]
+    fn test_cbc_aes256() {
+        let aes = Aes::new(&hex(AES256_KEY)).unwrap();
+        let mut data = hex(PLAINTEXT);
+        Cbc::new(aes.clone(), &hex(IV)).unwrap().encrypt(&mut data).unwrap();
+        assert_eq!(data, hex(CBC_AES256_CIPHERTEXT));
+        Cbc::new(aes, &hex(IV)).unwrap().decrypt(&mut data).unwrap();
+        assert_eq!(data, hex(PLAINTEXT));
+    }
+
+    #[test]
+    fn test_cbc_chaining_across_calls() {
+        let aes = Aes::new(&hex(AES128_KEY)).unwrap();
+        let mut data = hex(PLAINTEXT);
+        let mut cbc = Cbc::new(aes.clone(), &hex(IV)).unwrap();
+        cbc.encrypt(&mut data[..16]).unwrap();
+        cbc.encrypt(&mut data[16..]).unwrap();
+        assert_eq!(data, hex(CBC_AES128_CIPHERTEXT));
+        assert_eq!(cbc.iv(), &data[48..]);
+
+        let mut cbc = Cbc::new(aes, &hex(IV)).unwrap();
+        cbc.decrypt(&mut data[..32]).unwrap();
+        cbc.decrypt(&mut data[32..]).unwrap();
+        assert_eq!(data, hex(PLAINTEXT));
+    }
+
+    #[test]
+    fn test_cbc_padded() {
+        let cipher = TripleDes::new(b"0123456789abcdefFEDCBA98").unwrap();
+        for padding in [Padding::Pkcs7, Padding::Tls] {
+            let mut data = b"attack at dawn".to_vec();
+            Cbc::new(cipher.clone(), &[0; 8]).unwrap().encrypt_padded(&mut data, padding);
+            assert_eq!(data.len(), 16);
+            Cbc::new(cipher.clone(), &[0; 8]).unwrap().decrypt_padded(&mut data, padding).unwrap();
+            assert_eq!(data, b"attack at dawn");
+        }
+    }
+
+    #[test]
+    fn test_cbc_bad_padding() {
+        let aes = Aes::new(&hex(AES128_KEY)).unwrap();
+        let mut data = hex(CBC_AES128_CIPHERTEXT);
+        let result = Cbc::new(aes, &hex(IV)).unwrap().decrypt_padded(&mut data, Padding::Pkcs7);
+        assert_eq!(result, Err(ModeError::InvalidPadding));
+    }
+
+    #[test]
+    fn test_ecb_padded() {
+        let ecb = Ecb::new(Aes::new(&hex(AES128_KEY)).unwrap());
+        let mut data = hex(PLAINTEXT);
+        ecb.encrypt_padded(&mut data, Padding::Pkcs7);
+        assert_eq!(&data[..64], &hex(ECB_AES128_CIPHERTEXT)[..]);
+        assert_eq!(data.len(), 80);
+        ecb.decrypt_padded(&mut data, Padding::Pkcs7).unwrap();
+        assert_eq!(data, hex(PLAINTEXT));
+    }
+
+    #[test]
+    fn test_invalid_lengths() {
+        let aes = Aes::new(&hex(AES128_KEY)).unwrap();
+        assert_eq!(Ecb::new(aes.clone()).encrypt(&mut [0; 15]), Err(ModeError::InvalidLength(15)));
+        assert!(matches!(Cbc::new(aes.clone(), &[0; 8]), Err(ModeError::InvalidIvLength(8))));
+        assert!(matches!(Ctr::new(aes.clone(), &[0; 17]), Err(ModeError::InvalidIvLength(17))));
+        let mut cbc = Cbc::new(aes, &[0; 16]).unwrap();
+        assert_eq!(cbc.decrypt(&mut [0; 17]), Err(ModeError::InvalidLength(17)));
+        assert_eq!(cbc.decrypt(&mut []), Ok(()));
+    }
+
+    #[test]
+    fn test_ctr_aes128() {
+        let aes = Aes::new(&hex(AES128_KEY)).unwrap();
+        let mut data = hex(PLAINTEXT);
+        Ctr::new(aes.clone(), &hex(COUNTER)).unwrap().apply_key_stream(&mut data);
+        assert_eq!(data, hex(CTR_AES128_CIPHERTEXT));
+        Ctr::new(aes, &hex(COUNTER)).unwrap().apply_key_stream(&mut data);
+        assert_eq!(data, hex(PLAINTEXT));
+    }
+
+    #[test]
+    fn test_ctr_partial_blocks() {
+        let aes = Aes::new(&hex(AES128_KEY)).unwrap();
+        let mut data = hex(PLAINTEXT);
+        let mut ctr = Ctr::new(aes, &hex(COUNTER)).unwrap();
+        for chunk in data.chunks_mut(7) {
+            ctr.apply_key_stream(chunk);
+        }
+        assert_eq!(data, hex(CTR_AES128_CIPHERTEXT));
+    }
+
+    #[test]
+    fn test_ctr_counter_wraps_around() {
+        // the second block uses the counter 0, whose key stream is the
+        // encryption of the zero block
+        let aes = Aes::new(&[0; 16]).unwrap();
+        let mut data = [0; 32];
+        Ctr::new(aes, &[0xFF; 16]).unwrap().apply_key_stream(&mut data);
+        assert_eq!(data[16..], hex("66e94bd4ef8a2c3b884cfa59ca342b2e")[..]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Block padding, checked in constant time.
+//!
+//! A padding check which returns early or branches on the padding bytes tells
+//! an attacker whether a forged CBC ciphertext decrypts to valid padding, as
+//! in POODLE and Lucky13. `check_padding()` reads the same bytes and takes the
+//! same branches for any padding of the same data length.
+
+use alloc::vec::Vec;
+use crate::ct::{eq_mask, lt_mask};
+use super::ModeError;
+
+/// Maximum number of bytes of TLS padding including the length byte.
+const MAX_TLS_PADDING: usize = 256;
+
+/// Padding schemes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Padding {
+    /// PKCS #7 (RFC 5652), `n` bytes of the value `n` for `n` from 1 to the
+    /// block size.
+    Pkcs7,
+    /// TLS 1.0 to 1.2 (RFC 5246 6.2.3.2), `n + 1` bytes of the value `n` for
+    /// `n` up to 255, where the last byte is the padding length.
+    Tls,
+}
+
+/// Pad the data to a multiple of the block size with the minimum padding.
+pub fn pad(data: &mut Vec<u8>, block_size: usize, padding: Padding) {
+    let padding_len = block_size - data.len() % block_size;
+    let value = match padding {
+        Padding::Pkcs7 => padding_len,
+        Padding::Tls => padding_len - 1,
+    };
+    data.resize(data.len() + padding_len, value as u8);
+}
+
+/// Check the padding in constant time and get the length of the data without
+/// it and if the padding is valid.
+///
+/// The length is of the whole data if the padding is invalid, so that the
+/// record layer can compute the MAC as if there were no padding and report
+/// both errors in the same way.
+pub fn check_padding(data: &[u8], block_size: usize, padding: Padding) -> (usize, bool) {
+    let len = data.len();
+    if len == 0 {
+        return (0, false);
+    }
+
+    // masks are all ones for true and all zeros for false
+    let value = data[len - 1] as usize;
+    let (padding_len, max_padding_len, mut invalid) = match padding {
+        Padding::Pkcs7 => (value, block_size, eq_mask(value, 0) | lt_mask(block_size, value)),
+        Padding::Tls => (value + 1, MAX_TLS_PADDING, 0),
+    };
+    invalid |= lt_mask(len, padding_len);
+    for (distance, byte) in data.iter().rev().take(max_padding_len).enumerate() {
+        let is_padding = lt_mask(distance, padding_len);
+        invalid |= is_padding & !eq_mask(*byte as usize, value);
+    }
+
+    let unpadded_len = (len & invalid) | (len.wrapping_sub(padding_len) & !invalid);
+    (unpadded_len, invalid == 0)
+}
+
+/// Get the length of the data without the padding checked in constant time.
+pub fn unpad(data: &[u8], block_size: usize, padding: Padding) -> Result<usize, ModeError> {
+    match check_padding(data, block_size, padding) {
+        (len, true) => Ok(len),
+        (_, false) => Err(ModeError::InvalidPadding),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pad_pkcs7() {
+        let mut data = b"YELLOW SUBMARINE".to_vec();
+        pad(&mut data, 20, Padding::Pkcs7);
+        assert_eq!(data, b"YELLOW SUBMARINE\x04\x04\x04\x04");
+
+        let mut data = b"YELLOW SUBMARINE".to_vec();
+        pad(&mut data, 16, Padding::Pkcs7);
+        assert_eq!(data.len(), 32);
+        assert!(data[16..].iter().all(|byte| { *byte == 16 }));
+    }
+
+    #[test]
+    fn test_pad_tls() {
+        let mut data = b"YELLOW SUBMARINE".to_vec();
+        pad(&mut data, 20, Padding::Tls);
+        assert_eq!(data, b"YELLOW SUBMARINE\x03\x03\x03\x03");
+
+        let mut data = b"YELLOW SUBMARINE!!!".to_vec();
+        pad(&mut data, 20, Padding::Tls);
+        assert_eq!(data, b"YELLOW SUBMARINE!!!\x00");
+    }
+
+    #[test]
+    fn test_unpad_round_trip() {
+        for padding in [Padding::Pkcs7, Padding::Tls] {
+            for len in 0..40 {
+                let mut data = (0..len as u8).collect();
+                pad(&mut data, 16, padding);
+                assert!(data.len().is_multiple_of(16));
+                assert_eq!(unpad(&data, 16, padding), Ok(len), "{:?} {}", padding, len);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unpad_tls_longer_than_block() {
+        // TLS allows padding up to 255 bytes to hide the length
+        let mut data = b"0123456789abcdef".to_vec();
+        data.resize(16 + 48, 47);
+        assert_eq!(unpad(&data, 16, Padding::Tls), Ok(16));
+        // but not PKCS #7
+        assert_eq!(unpad(&data, 16, Padding::Pkcs7), Err(ModeError::InvalidPadding));
+
+        let mut data = b"0123456789abcdef".to_vec();
+        data.resize(16 + 256, 255);
+        assert_eq!(unpad(&data, 16, Padding::Tls), Ok(16));
+    }
+
+    #[test]
+    fn test_unpad_invalid_pkcs7() {
+        let invalid_data: [&[u8]; 5] = [
+            b"",
+            b"YELLOW SUBMARIN\x00",
+            b"YELLOW SUBMARIN\x11",
+            b"YELLOW SUBMARI\x01\x02",
+            b"YELLOW SUBMA\x04\x03\x04\x04",
+        ];
+        for data in invalid_data {
+            assert_eq!(unpad(data, 16, Padding::Pkcs7), Err(ModeError::InvalidPadding), "{:?}", data);
+        }
+    }
+
+    #[test]
+    fn test_unpad_invalid_tls() {
+        let invalid_data: [&[u8]; 4] = [
+            b"",
+            b"YELLOW SUBMARI\x00\x01",
+            b"YELLOW SUBMA\x02\x03\x03\x03",
+            b"\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10",
+        ];
+        for data in invalid_data {
+            assert_eq!(unpad(data, 16, Padding::Tls), Err(ModeError::InvalidPadding), "{:?}", data);
+        }
+    }
+
+    #[test]
+    fn test_check_padding_invalid_length() {
+        assert_eq!(check_padding(b"YELLOW SUBMARIN\x00", 16, Padding::Pkcs7), (16, false));
+        assert_eq!(check_padding(b"YELLOW SUBMARIN\x00", 16, Padding::Tls), (15, true));
+    }
+}
@@ -0,0 +1,133 @@
+//! RC4 stream cipher for `TLS_RSA_WITH_RC4_128_*` cipher suites.
+//!
+//! RC4 is broken and prohibited in TLS by RFC 7465, so this module is only
+//! built with the `legacy-insecure` feature for interoperability tests with
+//! old peers.
+
+use super::KeyError;
+
+/// Maximum key size in bytes.
+pub const MAX_KEY_SIZE: usize = 256;
+
+/// RC4 which encrypts and decrypts data of any length.
+#[derive(Clone)]
+pub struct Rc4 {
+    state: [u8; 256],
+    i: u8,
+    j: u8,
+}
+
+impl Rc4 {
+    /// Create with the key of 1 to 256 bytes, which is 16 bytes in TLS.
+    pub fn new(key: &[u8]) -> Result<Rc4, KeyError> {
+        if key.is_empty() || key.len() > MAX_KEY_SIZE {
+            return Err(KeyError::InvalidLength(key.len()));
+        }
+
+        let mut state = [0; 256];
+        for (i, byte) in state.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let mut j = 0u8;
+        for i in 0..256 {
+            j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+            state.swap(i, j as usize);
+        }
+        Ok(Rc4 { state, i: 0, j: 0 })
+    }
+
+    /// Encrypt or decrypt the data in place.
+    pub fn apply_key_stream(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            self.i = self.i.wrapping_add(1);
+            self.j = self.j.wrapping_add(self.state[self.i as usize]);
+            self.state.swap(self.i as usize, self.j as usize);
+            let index = self.state[self.i as usize].wrapping_add(self.state[self.j as usize]);
+            *byte ^= self.state[index as usize];
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use crate::test_util::hex;
+
+    /// Offsets of the key stream in the vectors.
+    const OFFSETS: [usize; 6] = [0, 16, 240, 256, 4080, 4096];
+
+    /// Key streams of RFC 6229 at the offsets as (key, key stream).
+    const VECTORS: [(&str, [&str; 6]); 8] = [
+        ("0102030405", [
+            "b2396305f03dc027ccc3524a0a1118a8", "6982944f18fc82d589c403a47a0d0919",
+            "28cb1132c96ce286421dcaadb8b69eae", "1cfcf62b03eddb641d77dfcf7f8d8c93",
+            "068326a2118416d21f9d04b2cd1ca050", "ff25b58995996707e51fbdf08b34d875",
+        ]),
+        ("01020304050607", [
+            "293f02d47f37c9b633f2af5285feb46b", "e620f1390d19bd84e2e0fd752031afc1",
+            "914f02531c9218810df60f67e338154c", "d0fdb583073ce85ab83917740ec011d5",
+            "f3172ceffc3b3d997c85ccd5af1a950c", "e74b0b9731227fd37c0ec08a47ddd8b8",
+        ]),
+        ("0102030405060708", [
+            "97ab8a1bf0afb96132f2f67258da15a8", "8263efdb45c4a18684ef87e6b19e5b09",
+            "9636ebc9841926f4f7d1f362bddf6e18", "d0a990ff2c05fef5b90373c9ff4b870a",
+            "d5fa5a3469d29aaaf83d23589db8c85b", "3fb46e2c8f0f068edce8cdcd7dfc5862",
+        ]),
+        ("0102030405060708090a", [
+            "ede3b04643e586cc907dc21851709902", "03516ba78f413beb223aa5d4d2df6711",
+            "3cfd6cb58ee0fdde640176ad0000044d", "48532b21fb6079c9114c0ffd9c04a1ad",
+            "1f135ed14ce60a91369d2322bef25e3c", "08b6be45124a43e2eb77953f84dc8553",
+        ]),
+        ("0102030405060708090a0b0c0d0e0f10", [
+            "9ac7cc9a609d1ef7b2932899cde41b97", "5248c4959014126a6e8a84f11d1a9e1c",
+            "065902e4b620f6cc36c8589f66432f2b", "d39d566bc6bce3010768151549f3873f",
+            "ff38265c1642c1abe8d3c2fe5e572bf8", "a36a4c301ae8ac13610ccbc12256cacc",
+        ]),
+        ("0102030405060708090a0b0c0d0e0f101112131415161718", [
+            "0595e57fe5f0bb3c706edac8a4b2db11", "dfde31344a1af769c74f070aee9e2326",
+            "b06b9b1e195d13d8f4a7995c4553ac05", "6bd2378ec341c9a42f37ba79f88a32ff",
+            "29a0b8aed54a132324c62e423f54b4c8", "3cb0f3b5020a98b82af9fe154484a168",
+        ]),
+        ("0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20", [
+            "eaa6bd25880bf93d3f5d1e4ca2611d91", "cfa45c9f7e714b54bdfa80027cb14380",
+            "114ae344ded71b35f2e60febad727fd8", "02e1e7056b0f623900496422943e97b6",
+            "a13a7c79c7e119b5ab0296ab28c300b9", "f3e4c0a2e02d1d01f7f0a74618af2b48",
+        ]),
+        ("ebb46227c6cc8b37641910833222772a", [
+            "720c94b63edf44e131d950ca211a5a30", "c366fdeacf9ca80436be7c358424d20b",
+            "b3394a40aabf75cba42282ef25a0059f", "4847d81da4942dbc249defc48c922b9f",
+            "a2221a11b833ccb498a59540f0545f4a", "5bbeb4787d59e5373fdbea6c6f75c29b",
+        ]),
+    ];
+
+    #[test]
+    fn test_rfc6229() {
+        for (key, key_streams) in VECTORS {
+            let mut data = vec![0; 4112];
+            Rc4::new(&hex(key)).unwrap().apply_key_stream(&mut data);
+            for (offset, key_stream) in OFFSETS.iter().zip(key_streams.iter()) {
+                assert_eq!(data[*offset..*offset + 16], hex(key_stream)[..], "key {} offset {}", key, offset);
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip_in_pieces() {
+        let key = b"Key";
+        let mut data = b"Plaintext".to_vec();
+        Rc4::new(key).unwrap().apply_key_stream(&mut data);
+        assert_eq!(data, hex("bbf316e8d940af0ad3"));
+
+        let mut rc4 = Rc4::new(key).unwrap();
+        data.chunks_mut(2).for_each(|chunk| { rc4.apply_key_stream(chunk) });
+        assert_eq!(data, b"Plaintext");
+    }
+
+    #[test]
+    fn test_invalid_key_length() {
+        assert_eq!(Rc4::new(&[]).err(), Some(KeyError::InvalidLength(0)));
+        assert_eq!(Rc4::new(&[0; 257]).err(), Some(KeyError::InvalidLength(257)));
+        assert!(Rc4::new(&[0; 256]).is_ok());
+    }
+}
@@ -0,0 +1,63 @@
+//! Constant-time operations on secret data.
+//!
+//! Comparing MACs or padding with early returns tells an attacker how many
+//! bytes matched through timing. These helpers read all the bytes and don't
+//! branch on them. Lengths are not treated as secret.
+
+/// Check if the bytes are equal in time depending only on the lengths.
+pub fn eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let difference = a.iter().zip(b.iter()).fold(0, |difference, (x, y)| { difference | (x ^ y) });
+    // keep the optimizer from turning the loop into a comparison which exits
+    // at the first difference
+    core::hint::black_box(difference) == 0
+}
+
+/// Get all 1 bits if they are equal, or 0 without branching.
+pub(crate) fn eq_mask(a: usize, b: usize) -> usize {
+    let x = a ^ b;
+    ((x | x.wrapping_neg()) >> (usize::BITS - 1)).wrapping_sub(1)
+}
+
+/// Get all 1 bits if they are equal, or 0 without branching, on the 64-bit
+/// limbs of the field elements.
+pub(crate) fn eq_mask_u64(a: u64, b: u64) -> u64 {
+    let x = a ^ b;
+    ((x | x.wrapping_neg()) >> 63).wrapping_sub(1)
+}
+
+/// Get all 1 bits if `a < b`, or 0 without branching.
+/// Both must be less than `2 ^ (usize::BITS - 1)`, which lengths always are.
+pub(crate) fn lt_mask(a: usize, b: usize) -> usize {
+    0usize.wrapping_sub(a.wrapping_sub(b) >> (usize::BITS - 1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_eq() {
+        assert!(eq(b"", b""));
+        assert!(eq(b"tag", b"tag"));
+        assert!(!eq(b"tag", b"tab"));
+        assert!(!eq(b"tag", b"Tag"));
+        assert!(!eq(b"tag", b"tags"));
+        assert!(!eq(b"tag", b""));
+    }
+
+    #[test]
+    fn test_masks() {
+        assert_eq!(eq_mask(3, 3), usize::MAX);
+        assert_eq!(eq_mask(3, 4), 0);
+        assert_eq!(eq_mask_u64(3, 3), u64::MAX);
+        assert_eq!(eq_mask_u64(3, 4), 0);
+        assert_eq!(eq_mask_u64(0, 1 << 63), 0);
+        assert_eq!(lt_mask(3, 4), usize::MAX);
+        assert_eq!(lt_mask(4, 4), 0);
+        assert_eq!(lt_mask(5, 4), 0);
+        assert_eq!(lt_mask(0, 256), usize::MAX);
+    }
+}
@@ -0,0 +1,273 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+use alloc::string::String;
+use crate::{aead, base16, base32, base64, cipher, kdf, pem, pk};
+
+/// Result type of this crate.
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Errors of this crate.
+#[derive(Debug)]
+pub enum Error {
+    /// I/O error of the underlying stream.
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    /// Malformed encoded input.
+    Decode(DecodeError),
+    /// The peer violated the protocol.
+    Protocol(String),
+    /// The peer sent a fatal alert with the description code.
+    Alert(u8),
+    /// The certificate is malformed or not trusted.
+    Certificate(String),
+    /// A cryptographic operation failed, e.g. a MAC or signature mismatch.
+    Crypto(CryptoError),
+    /// The configuration given by the user is invalid.
+    Config(String),
+}
+
+/// Decode errors of each encoding.
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    Base16(base16::DecodeError),
+    Base32(base32::DecodeError),
+    Base64(base64::DecodeError),
+    Pem(pem::DecodeError),
+}
+
+/// Errors of each cryptographic primitive.
+#[derive(Debug, PartialEq)]
+pub enum CryptoError {
+    Key(cipher::KeyError),
+    Mode(cipher::modes::ModeError),
+    Aead(aead::AeadError),
+    Kdf(kdf::KdfError),
+    Rsa(pk::rsa::RsaError),
+    Dh(pk::dh::DhError),
+    Ecc(pk::ecc::EccError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Decode(e) => write!(f, "decode error: {}", e),
+            Error::Protocol(message) => write!(f, "protocol error: {}", message),
+            Error::Alert(description) => write!(f, "received fatal alert: {}", description),
+            Error::Certificate(message) => write!(f, "certificate error: {}", message),
+            Error::Crypto(e) => write!(f, "crypto error: {}", e),
+            Error::Config(message) => write!(f, "invalid configuration: {}", message),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Error::Io(e) => Some(e),
+            Error::Decode(e) => Some(e),
+            Error::Crypto(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Base16(e) => write!(f, "{}", e),
+            DecodeError::Base32(e) => write!(f, "{}", e),
+            DecodeError::Base64(e) => write!(f, "{}", e),
+            DecodeError::Pem(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            DecodeError::Base16(e) => Some(e),
+            DecodeError::Base32(e) => Some(e),
+            DecodeError::Base64(e) => Some(e),
+            DecodeError::Pem(e) => Some(e),
+        }
+    }
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CryptoError::Key(e) => write!(f, "{}", e),
+            CryptoError::Mode(e) => write!(f, "{}", e),
+            CryptoError::Aead(e) => write!(f, "{}", e),
+            CryptoError::Kdf(e) => write!(f, "{}", e),
+            CryptoError::Rsa(e) => write!(f, "{}", e),
+            CryptoError::Dh(e) => write!(f, "{}", e),
+            CryptoError::Ecc(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl core::error::Error for CryptoError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            CryptoError::Key(e) => Some(e),
+            CryptoError::Mode(e) => Some(e),
+            CryptoError::Aead(e) => Some(e),
+            CryptoError::Kdf(e) => Some(e),
+            CryptoError::Rsa(e) => Some(e),
+            CryptoError::Dh(e) => Some(e),
+            CryptoError::Ecc(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(e: DecodeError) -> Error {
+        Error::Decode(e)
+    }
+}
+
+impl From<base16::DecodeError> for Error {
+    fn from(e: base16::DecodeError) -> Error {
+        Error::Decode(DecodeError::Base16(e))
+    }
+}
+
+impl From<base32::DecodeError> for Error {
+    fn from(e: base32::DecodeError) -> Error {
+        Error::Decode(DecodeError::Base32(e))
+    }
+}
+
+impl From<base64::DecodeError> for Error {
+    fn from(e: base64::DecodeError) -> Error {
+        Error::Decode(DecodeError::Base64(e))
+    }
+}
+
+impl From<pem::DecodeError> for Error {
+    fn from(e: pem::DecodeError) -> Error {
+        Error::Decode(DecodeError::Pem(e))
+    }
+}
+
+impl From<CryptoError> for Error {
+    fn from(e: CryptoError) -> Error {
+        Error::Crypto(e)
+    }
+}
+
+impl From<cipher::KeyError> for Error {
+    fn from(e: cipher::KeyError) -> Error {
+        Error::Crypto(CryptoError::Key(e))
+    }
+}
+
+impl From<cipher::modes::ModeError> for Error {
+    fn from(e: cipher::modes::ModeError) -> Error {
+        Error::Crypto(CryptoError::Mode(e))
+    }
+}
+
+impl From<aead::AeadError> for Error {
+    fn from(e: aead::AeadError) -> Error {
+        Error::Crypto(CryptoError::Aead(e))
+    }
+}
+
+impl From<kdf::KdfError> for Error {
+    fn from(e: kdf::KdfError) -> Error {
+        Error::Crypto(CryptoError::Kdf(e))
+    }
+}
+
+impl From<pk::dh::DhError> for Error {
+    fn from(e: pk::dh::DhError) -> Error {
+        Error::Crypto(CryptoError::Dh(e))
+    }
+}
+
+impl From<pk::ecc::EccError> for Error {
+    fn from(e: pk::ecc::EccError) -> Error {
+        Error::Crypto(CryptoError::Ecc(e))
+    }
+}
+
+impl From<pk::rsa::RsaError> for Error {
+    fn from(e: pk::rsa::RsaError) -> Error {
+        Error::Crypto(CryptoError::Rsa(e))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode_twice(input: &str) -> Result<std::vec::Vec<u8>> {
+        let once = base64::decode(input)?;
+        Ok(base64::decode(once)?)
+    }
+
+    #[test]
+    fn test_question_mark_conversion() {
+        let output = decode_twice("YSE=");  // "a!"
+        match output {
+            Err(Error::Decode(DecodeError::Base64(e))) => {
+                assert_eq!(e, base64::DecodeError::InvalidLength);
+            },
+            _ => panic!("unexpected result: {:?}", output),
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let error = Error::from(base64::DecodeError::InvalidByte(1, b'!'));
+        assert_eq!(error.to_string(), "decode error: invalid BASE64 symbol 0x21 at index 1");
+
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_display_io() {
+        let error = Error::from(io::Error::new(io::ErrorKind::NotFound, "no file"));
+        assert_eq!(error.to_string(), "I/O error: no file");
+    }
+
+    #[test]
+    fn test_source() {
+        use std::error::Error as _;
+        let error = Error::from(pem::DecodeError {
+            line: 2,
+            column: 3,
+            kind: pem::DecodeErrorKind::InvalidBase64(base64::DecodeError::InvalidLength),
+        });
+        assert_eq!(error.to_string(), "decode error: invalid BASE64 length at line 2 column 3");
+
+        let source = error.source().unwrap().source().unwrap().source().unwrap();
+        assert_eq!(source.to_string(), "invalid BASE64 length");
+    }
+
+    #[test]
+    fn test_crypto_error() {
+        use std::error::Error as _;
+        let error = Error::from(aead::AeadError::InvalidTag);
+        match &error {
+            Error::Crypto(CryptoError::Aead(aead::AeadError::InvalidTag)) => {},
+            _ => panic!("unexpected error: {:?}", error),
+        }
+        assert_eq!(error.to_string(), "crypto error: authentication tag mismatch");
+        let source = error.source().unwrap().source().unwrap();
+        assert_eq!(source.to_string(), "authentication tag mismatch");
+    }
+}
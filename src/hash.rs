@@ -0,0 +1,99 @@
+//! Hash functions for the PRF, handshake transcripts and certificates.
+
+use alloc::vec::Vec;
+
+pub mod md5;
+pub mod sha1;
+pub mod sha2;
+
+pub use md5::Md5;
+pub use sha1::Sha1;
+pub use sha2::{Sha224, Sha256, Sha384, Sha512, Sha512_224, Sha512_256};
+
+/// Hash function which processes the input incrementally.
+///
+/// Cloning the state forks the hash, e.g. to get the hash of the handshake
+/// transcript so far and continue it.
+pub trait Digest: Clone {
+    /// Output size in bytes.
+    const OUTPUT_SIZE: usize;
+    /// Block size in bytes.
+    const BLOCK_SIZE: usize;
+
+    /// Create the initial state.
+    fn new() -> Self;
+
+    /// Process the data.
+    fn update(&mut self, data: &[u8]);
+
+    /// Get the hash of all the data processed.
+    fn finalize(self) -> Vec<u8>;
+
+    /// Get the hash of the data at once.
+    fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hash = Self::new();
+        hash.update(data);
+        hash.finalize()
+    }
+}
+
+/// Buffer of a partial block of Merkle-Damgård hash functions, which keeps at
+/// most one block.
+#[derive(Clone)]
+struct BlockBuffer<const N: usize> {
+    buffer: [u8; N],
+    len: usize,
+    /// Length of all the data in bytes.
+    total_len: u128,
+}
+
+impl<const N: usize> BlockBuffer<N> {
+    fn new() -> BlockBuffer<N> {
+        BlockBuffer { buffer: [0; N], len: 0, total_len: 0 }
+    }
+
+    /// Process the full blocks of the buffered data followed by the data and
+    /// buffer the rest.
+    fn update(&mut self, mut data: &[u8], mut process: impl FnMut(&[u8; N])) {
+        self.total_len += data.len() as u128;
+        if self.len > 0 {
+            let len = data.len().min(N - self.len);
+            self.buffer[self.len..self.len + len].copy_from_slice(&data[..len]);
+            self.len += len;
+            data = &data[len..];
+            if self.len < N {
+                return;
+            }
+            process(&self.buffer);
+            self.len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(N);
+        for block in &mut blocks {
+            let mut full_block = [0; N];
+            full_block.copy_from_slice(block);
+            process(&full_block);
+        }
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.len = remainder.len();
+    }
+
+    /// Process the last blocks padded with a 1 bit, zeros and the encoded
+    /// length in bits at the end.
+    fn finalize(mut self, encoded_len: &[u8], mut process: impl FnMut(&[u8; N])) {
+        self.buffer[self.len] = 0x80;
+        self.buffer[self.len + 1..].fill(0);
+        if self.len + 1 > N - encoded_len.len() {
+            process(&self.buffer);
+            self.buffer.fill(0);
+        }
+        self.buffer[N - encoded_len.len()..].copy_from_slice(encoded_len);
+        process(&self.buffer);
+    }
+
+    /// Get the length of all the data in bits.
+    fn bit_len(&self) -> u128 {
+        self.total_len * 8
+    }
+}
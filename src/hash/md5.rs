@@ -0,0 +1,152 @@
+//! MD5 (RFC 1321) for the PRF and the finished hashes of TLS 1.0 and 1.1.
+//!
+//! MD5 is broken for collision resistance and must not be used for new
+//! signatures.
+
+use core::convert::TryInto;
+use alloc::vec::Vec;
+use super::{BlockBuffer, Digest};
+
+const BLOCK_SIZE: usize = 64;
+
+const INITIAL_STATE: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+
+/// `floor(abs(sin(i + 1)) * 2 ^ 32)`
+const K: [u32; 64] = [
+    0xd76a_a478, 0xe8c7_b756, 0x2420_70db, 0xc1bd_ceee, 0xf57c_0faf, 0x4787_c62a, 0xa830_4613, 0xfd46_9501,
+    0x6980_98d8, 0x8b44_f7af, 0xffff_5bb1, 0x895c_d7be, 0x6b90_1122, 0xfd98_7193, 0xa679_438e, 0x49b4_0821,
+    0xf61e_2562, 0xc040_b340, 0x265e_5a51, 0xe9b6_c7aa, 0xd62f_105d, 0x0244_1453, 0xd8a1_e681, 0xe7d3_fbc8,
+    0x21e1_cde6, 0xc337_07d6, 0xf4d5_0d87, 0x455a_14ed, 0xa9e3_e905, 0xfcef_a3f8, 0x676f_02d9, 0x8d2a_4c8a,
+    0xfffa_3942, 0x8771_f681, 0x6d9d_6122, 0xfde5_380c, 0xa4be_ea44, 0x4bde_cfa9, 0xf6bb_4b60, 0xbebf_bc70,
+    0x289b_7ec6, 0xeaa1_27fa, 0xd4ef_3085, 0x0488_1d05, 0xd9d4_d039, 0xe6db_99e5, 0x1fa2_7cf8, 0xc4ac_5665,
+    0xf429_2244, 0x432a_ff97, 0xab94_23a7, 0xfc93_a039, 0x655b_59c3, 0x8f0c_cc92, 0xffef_f47d, 0x8584_5dd1,
+    0x6fa8_7e4f, 0xfe2c_e6e0, 0xa301_4314, 0x4e08_11a1, 0xf753_7e82, 0xbd3a_f235, 0x2ad7_d2bb, 0xeb86_d391,
+];
+
+/// Left rotations of each round.
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22,
+    5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20,
+    4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
+    6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+/// MD5 hash.
+#[derive(Clone)]
+pub struct Md5 {
+    state: [u32; 4],
+    buffer: BlockBuffer<BLOCK_SIZE>,
+}
+
+impl Digest for Md5 {
+    const OUTPUT_SIZE: usize = 16;
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    fn new() -> Md5 {
+        Md5 { state: INITIAL_STATE, buffer: BlockBuffer::new() }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.buffer.update(data, |block| { compress(state, block) });
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        let encoded_len = (self.buffer.bit_len() as u64).to_le_bytes();
+        let state = &mut self.state;
+        self.buffer.finalize(&encoded_len, |block| { compress(state, block) });
+        self.state.iter().flat_map(|word| { word.to_le_bytes() }).collect()
+    }
+}
+
+fn compress(state: &mut [u32; 4], block: &[u8; BLOCK_SIZE]) {
+    let mut m = [0u32; 16];
+    for (word, bytes) in m.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    let [mut a, mut b, mut c, mut d] = *state;
+    for i in 0..64 {
+        let (f, g) = match i / 16 {
+            0 => ((b & c) | (!b & d), i),
+            1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+            2 => (b ^ c ^ d, (3 * i + 5) % 16),
+            _ => (c ^ (b | !d), (7 * i) % 16),
+        };
+        let f = f.wrapping_add(a).wrapping_add(K[i]).wrapping_add(m[g]);
+        a = d;
+        d = c;
+        c = b;
+        b = b.wrapping_add(f.rotate_left(SHIFTS[i]));
+    }
+
+    for (word, value) in state.iter_mut().zip([a, b, c, d]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use crate::test_util::hex;
+
+    /// Test suite of RFC 1321 A.5 as (message, hash).
+    const VECTORS: [(&str, &str); 7] = [
+        ("", "d41d8cd98f00b204e9800998ecf8427e"),
+        ("a", "0cc175b9c0f1b6a831c399e269772661"),
+        ("abc", "900150983cd24fb0d6963f7d28e17f72"),
+        ("message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+        ("abcdefghijklmnopqrstuvwxyz", "c3fcd3d76192e4007dfb496cca67e13b"),
+        ("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789", "d174ab98d277d9f5a5611c2c9f419d9f"),
+        (
+            "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+            "57edf4a22be3c955ac49da2e2107b67a",
+        ),
+    ];
+
+    #[test]
+    fn test_rfc1321() {
+        for (message, hash) in VECTORS {
+            assert_eq!(Md5::digest(message.as_bytes()), hex(hash), "{:?}", message);
+        }
+    }
+
+    #[test]
+    fn test_update_in_pieces() {
+        let (message, hash) = VECTORS[6];
+        for piece_len in 1..message.len() {
+            let mut md5 = Md5::new();
+            message.as_bytes().chunks(piece_len).for_each(|piece| { md5.update(piece) });
+            assert_eq!(md5.finalize(), hex(hash), "{}", piece_len);
+        }
+    }
+
+    #[test]
+    fn test_padding_boundaries() {
+        // the length fits in the last block up to 55 bytes
+        assert_eq!(Md5::digest(&[b'a'; 55]), hex("ef1772b6dff9a122358552954ad0df65"));
+        assert_eq!(Md5::digest(&[b'a'; 56]), hex("3b0c8ac703f828b04c6c197006d17218"));
+        assert_eq!(Md5::digest(&[b'a'; 64]), hex("014842d480b571495a4a0363793f7367"));
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut md5 = Md5::new();
+        md5.update(b"message ");
+        let fork = md5.clone();
+        md5.update(b"digest");
+        assert_eq!(md5.finalize(), hex("f96b697d7cb7938d525a2f31aaf161d0"));
+        assert_eq!(fork.finalize(), Md5::digest(b"message "));
+    }
+
+    #[test]
+    fn test_million_a() {
+        let mut md5 = Md5::new();
+        let data = vec![b'a'; 1000];
+        for _ in 0..1000 {
+            md5.update(&data);
+        }
+        assert_eq!(md5.finalize(), hex("7707d6ae4e027c70eea2a935c2296f21"));
+    }
+}
@@ -0,0 +1,140 @@
+//! SHA-1 (FIPS 180-4) for the PRF and the finished hashes of TLS 1.0 and
+//! 1.1, `TLS_*_SHA` record MACs and certificate fingerprints.
+//!
+//! SHA-1 is broken for collision resistance and must not be used for new
+//! signatures.
+
+use core::convert::TryInto;
+use alloc::vec::Vec;
+use super::{BlockBuffer, Digest};
+
+const BLOCK_SIZE: usize = 64;
+
+const INITIAL_STATE: [u32; 5] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476, 0xc3d2_e1f0];
+
+/// Constants of each 20 rounds.
+const K: [u32; 4] = [0x5a82_7999, 0x6ed9_eba1, 0x8f1b_bcdc, 0xca62_c1d6];
+
+/// SHA-1 hash.
+#[derive(Clone)]
+pub struct Sha1 {
+    state: [u32; 5],
+    buffer: BlockBuffer<BLOCK_SIZE>,
+}
+
+impl Digest for Sha1 {
+    const OUTPUT_SIZE: usize = 20;
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    fn new() -> Sha1 {
+        Sha1 { state: INITIAL_STATE, buffer: BlockBuffer::new() }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.buffer.update(data, |block| { compress(state, block) });
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        let encoded_len = (self.buffer.bit_len() as u64).to_be_bytes();
+        let state = &mut self.state;
+        self.buffer.finalize(&encoded_len, |block| { compress(state, block) });
+        self.state.iter().flat_map(|word| { word.to_be_bytes() }).collect()
+    }
+}
+
+fn compress(state: &mut [u32; 5], block: &[u8; BLOCK_SIZE]) {
+    // message schedule
+    let mut w = [0u32; 80];
+    for (word, bytes) in w.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+    for t in 16..80 {
+        w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+    for (t, w) in w.iter().enumerate() {
+        let f = match t / 20 {
+            0 => (b & c) | (!b & d),
+            2 => (b & c) | (b & d) | (c & d),
+            _ => b ^ c ^ d,
+        };
+        let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(K[t / 20]).wrapping_add(*w);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use crate::test_util::hex;
+
+    /// Examples of FIPS 180-4 and more as (message, hash).
+    const VECTORS: [(&str, &str); 5] = [
+        ("", "da39a3ee5e6b4b0d3255bfef95601890afd80709"),
+        ("abc", "a9993e364706816aba3e25717850c26c9cd0d89d"),
+        ("abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq", "84983e441c3bd26ebaae4aa1f95129e5e54670f1"),
+        (
+            concat!(
+                "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmno",
+                "ijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+            ),
+            "a49b2446a02c645bf419f995b67091253a04a259",
+        ),
+        ("The quick brown fox jumps over the lazy dog", "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"),
+    ];
+
+    #[test]
+    fn test_vectors() {
+        for (message, hash) in VECTORS {
+            assert_eq!(Sha1::digest(message.as_bytes()), hex(hash), "{:?}", message);
+        }
+    }
+
+    #[test]
+    fn test_update_in_pieces() {
+        let (message, hash) = VECTORS[3];
+        for piece_len in 1..message.len() {
+            let mut sha1 = Sha1::new();
+            message.as_bytes().chunks(piece_len).for_each(|piece| { sha1.update(piece) });
+            assert_eq!(sha1.finalize(), hex(hash), "{}", piece_len);
+        }
+    }
+
+    #[test]
+    fn test_padding_boundaries() {
+        assert_eq!(Sha1::digest(&[b'a'; 55]), hex("c1c8bbdc22796e28c0e15163d20899b65621d65a"));
+        assert_eq!(Sha1::digest(&[b'a'; 56]), hex("c2db330f6083854c99d4b5bfb6e8f29f201be699"));
+        assert_eq!(Sha1::digest(&[b'a'; 64]), hex("0098ba824b5c16427bd7a1122a5a442a25ec644d"));
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut sha1 = Sha1::new();
+        sha1.update(b"ab");
+        let fork = sha1.clone();
+        sha1.update(b"c");
+        assert_eq!(sha1.finalize(), hex("a9993e364706816aba3e25717850c26c9cd0d89d"));
+        assert_eq!(fork.finalize(), Sha1::digest(b"ab"));
+    }
+
+    #[test]
+    fn test_million_a() {
+        let mut sha1 = Sha1::new();
+        let data = vec![b'a'; 1000];
+        for _ in 0..1000 {
+            sha1.update(&data);
+        }
+        assert_eq!(sha1.finalize(), hex("34aa973cd4c4daa4f61eeb2bdbad27316534016f"));
+    }
+}
@@ -0,0 +1,189 @@
+//! SHA-2 hash functions (FIPS 180-4) for certificates, the TLS 1.2 PRF and
+//! the TLS 1.3 key schedule.
+//!
+//! The hashes process the data as it comes and buffer at most one block.
+
+use alloc::vec::Vec;
+use super::Digest;
+
+mod sha256;
+mod sha512;
+
+macro_rules! impl_digest {
+    ($name:ident, $doc:expr, $engine:ident, $initial_state:expr, $output_size:expr) => {
+        #[doc = $doc]
+        #[derive(Clone)]
+        pub struct $name($engine::Engine);
+
+        impl Digest for $name {
+            const OUTPUT_SIZE: usize = $output_size;
+            const BLOCK_SIZE: usize = $engine::Engine::BLOCK_SIZE;
+
+            fn new() -> $name {
+                $name($engine::Engine::new($initial_state))
+            }
+
+            fn update(&mut self, data: &[u8]) {
+                self.0.update(data);
+            }
+
+            fn finalize(self) -> Vec<u8> {
+                let mut output = self.0.finalize();
+                output.truncate($output_size);
+                output
+            }
+        }
+    };
+}
+
+impl_digest!(Sha224, "SHA-224 hash.", sha256, sha256::INITIAL_STATE_224, 28);
+impl_digest!(Sha256, "SHA-256 hash.", sha256, sha256::INITIAL_STATE_256, 32);
+impl_digest!(Sha384, "SHA-384 hash.", sha512, sha512::INITIAL_STATE_384, 48);
+impl_digest!(Sha512, "SHA-512 hash.", sha512, sha512::INITIAL_STATE_512, 64);
+impl_digest!(Sha512_224, "SHA-512/224 hash.", sha512, sha512::INITIAL_STATE_512_224, 28);
+impl_digest!(Sha512_256, "SHA-512/256 hash.", sha512, sha512::INITIAL_STATE_512_256, 32);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use crate::test_util::{hex, upstream_vectors};
+
+    /// Parse the (message, hash) pairs of a response file of the CAVP byte
+    /// oriented format, which the generated vectors also use.
+    fn parse_rsp(rsp: &str) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let mut vectors = Vec::new();
+        let mut len = 0;
+        let mut message = Vec::new();
+        for line in rsp.lines() {
+            if let Some(value) = line.strip_prefix("Len = ") {
+                len = value.parse::<usize>().unwrap() / 8;
+            } else if let Some(value) = line.strip_prefix("Msg = ") {
+                message = hex(value);
+                message.truncate(len);
+            } else if let Some(value) = line.strip_prefix("MD = ") {
+                vectors.push((message.clone(), hex(value)));
+            }
+        }
+        vectors
+    }
+
+    fn check_rsp<D: Digest>(rsp: &str) {
+        let vectors = parse_rsp(rsp);
+        assert!(!vectors.is_empty());
+        for (message, hash) in vectors {
+            assert_eq!(D::digest(&message), hash, "message of {} bytes", message.len());
+
+            // in 3 pieces across the block boundaries
+            let mut digest = D::new();
+            let (first, rest) = message.split_at(message.len() / 3);
+            let (second, third) = rest.split_at(rest.len() / 2);
+            digest.update(first);
+            digest.update(second);
+            digest.update(third);
+            assert_eq!(digest.finalize(), hash, "message of {} bytes in pieces", message.len());
+        }
+    }
+
+    macro_rules! generated_test {
+        ($test_name:ident, $digest:ty, $file:expr) => {
+            #[test]
+            fn $test_name() {
+                check_rsp::<$digest>(include_str!(concat!("../../res/generated/sha2/", $file)));
+            }
+        };
+    }
+
+    generated_test!(test_generated_sha224_short, Sha224, "sha224_short.rsp");
+    generated_test!(test_generated_sha224_long, Sha224, "sha224_long.rsp");
+    generated_test!(test_generated_sha256_short, Sha256, "sha256_short.rsp");
+    generated_test!(test_generated_sha256_long, Sha256, "sha256_long.rsp");
+    generated_test!(test_generated_sha384_short, Sha384, "sha384_short.rsp");
+    generated_test!(test_generated_sha384_long, Sha384, "sha384_long.rsp");
+    generated_test!(test_generated_sha512_short, Sha512, "sha512_short.rsp");
+    generated_test!(test_generated_sha512_long, Sha512, "sha512_long.rsp");
+    generated_test!(test_generated_sha512_224_short, Sha512_224, "sha512_224_short.rsp");
+    generated_test!(test_generated_sha512_224_long, Sha512_224, "sha512_224_long.rsp");
+    generated_test!(test_generated_sha512_256_short, Sha512_256, "sha512_256_short.rsp");
+    generated_test!(test_generated_sha512_256_long, Sha512_256, "sha512_256_long.rsp");
+
+    /// Test over the response files of NIST, which are read at run time as
+    /// they are not in the tree.
+    macro_rules! cavp_test {
+        ($test_name:ident, $digest:ty, $file:expr) => {
+            #[test]
+            #[ignore = "needs the NIST vectors of res/fetch-vectors.sh"]
+            fn $test_name() {
+                check_rsp::<$digest>(&upstream_vectors(concat!("cavp/sha2/", $file)));
+            }
+        };
+    }
+
+    cavp_test!(test_cavp_sha224_short, Sha224, "SHA224ShortMsg.rsp");
+    cavp_test!(test_cavp_sha224_long, Sha224, "SHA224LongMsg.rsp");
+    cavp_test!(test_cavp_sha256_short, Sha256, "SHA256ShortMsg.rsp");
+    cavp_test!(test_cavp_sha256_long, Sha256, "SHA256LongMsg.rsp");
+    cavp_test!(test_cavp_sha384_short, Sha384, "SHA384ShortMsg.rsp");
+    cavp_test!(test_cavp_sha384_long, Sha384, "SHA384LongMsg.rsp");
+    cavp_test!(test_cavp_sha512_short, Sha512, "SHA512ShortMsg.rsp");
+    cavp_test!(test_cavp_sha512_long, Sha512, "SHA512LongMsg.rsp");
+    cavp_test!(test_cavp_sha512_224_short, Sha512_224, "SHA512_224ShortMsg.rsp");
+    cavp_test!(test_cavp_sha512_224_long, Sha512_224, "SHA512_224LongMsg.rsp");
+    cavp_test!(test_cavp_sha512_256_short, Sha512_256, "SHA512_256ShortMsg.rsp");
+    cavp_test!(test_cavp_sha512_256_long, Sha512_256, "SHA512_256LongMsg.rsp");
+
+    #[test]
+    fn test_fips180_examples() {
+        // "abc" of the examples of FIPS 180-4
+        assert_eq!(Sha224::digest(b"abc"), hex("23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7"));
+        assert_eq!(
+            Sha256::digest(b"abc"),
+            hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+        );
+        assert_eq!(
+            Sha384::digest(b"abc"),
+            hex("cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7"),
+        );
+        assert_eq!(
+            Sha512::digest(b"abc"),
+            hex(concat!(
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a",
+                "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+            )),
+        );
+        assert_eq!(Sha512_224::digest(b"abc"), hex("4634270f707b6a54daae7530460842e20e37ed265ceee9a43e8924aa"));
+        assert_eq!(
+            Sha512_256::digest(b"abc"),
+            hex("53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23"),
+        );
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut sha256 = Sha256::new();
+        sha256.update(b"ab");
+        let fork = sha256.clone();
+        sha256.update(b"c");
+        assert_eq!(sha256.finalize(), Sha256::digest(b"abc"));
+        assert_eq!(fork.finalize(), Sha256::digest(b"ab"));
+    }
+
+    #[test]
+    fn test_million_a() {
+        let data = vec![b'a'; 1000];
+        let mut sha256 = Sha256::new();
+        let mut sha512 = Sha512::new();
+        for _ in 0..1000 {
+            sha256.update(&data);
+            sha512.update(&data);
+        }
+        assert_eq!(sha256.finalize(), hex("cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"));
+        assert_eq!(
+            sha512.finalize(),
+            hex(concat!(
+                "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973eb",
+                "de0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b",
+            )),
+        );
+    }
+}
@@ -0,0 +1,30 @@
+//! Key derivation functions which derive the keys of the record layer from
+//! the secrets of the handshake.
+
+use core::fmt;
+
+pub mod hkdf;
+pub mod prf;
+
+/// Errors of key derivation.
+#[derive(Debug, PartialEq)]
+pub enum KdfError {
+    /// The output is longer than the function can derive.
+    InvalidOutputLength(usize),
+    /// The label of `HkdfLabel` is longer than 255 bytes with the prefix.
+    InvalidLabelLength(usize),
+    /// The context of `HkdfLabel` is longer than 255 bytes.
+    InvalidContextLength(usize),
+}
+
+impl fmt::Display for KdfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KdfError::InvalidOutputLength(len) => write!(f, "invalid output length {}", len),
+            KdfError::InvalidLabelLength(len) => write!(f, "invalid label length {}", len),
+            KdfError::InvalidContextLength(len) => write!(f, "invalid context length {}", len),
+        }
+    }
+}
+
+impl core::error::Error for KdfError {}
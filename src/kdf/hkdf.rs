@@ -0,0 +1,202 @@
+//! HKDF (RFC 5869) and the helpers of the TLS 1.3 key schedule (RFC 8446
+//! 7.1).
+
+use alloc::vec::Vec;
+use crate::hash::Digest;
+use crate::mac::Hmac;
+use super::KdfError;
+
+/// Prefix of the labels of TLS 1.3.
+const LABEL_PREFIX: &[u8] = b"tls13 ";
+
+/// Extract the pseudorandom key from the input keying material.
+/// The empty salt is the same as the zeros of the hash length.
+pub fn extract<D: Digest>(salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+    Hmac::<D>::mac(salt, ikm)
+}
+
+/// Expand the pseudorandom key with the info into the output of the length up
+/// to 255 times the hash length.
+pub fn expand<D: Digest>(prk: &[u8], info: &[u8], len: usize) -> Result<Vec<u8>, KdfError> {
+    check_output_length::<D>(len)?;
+    let hmac = Hmac::<D>::new(prk);
+    let mut output = Vec::with_capacity(len + D::OUTPUT_SIZE);
+    // T(i) = HMAC-Hash(PRK, T(i-1) | info | i)
+    let mut t = Vec::new();
+    let mut counter = 1u8;
+    while output.len() < len {
+        let mut t_hmac = hmac.clone();
+        t_hmac.update(&t);
+        t_hmac.update(info);
+        t_hmac.update(&[counter]);
+        t = t_hmac.finalize();
+        output.extend_from_slice(&t);
+        counter = counter.wrapping_add(1);
+    }
+    output.truncate(len);
+    Ok(output)
+}
+
+/// `HKDF-Expand-Label` of TLS 1.3, where the label is without the prefix
+/// "tls13 ".
+pub fn expand_label<D: Digest>(secret: &[u8], label: &[u8], context: &[u8], len: usize) -> Result<Vec<u8>, KdfError> {
+    check_output_length::<D>(len)?;
+    let label_len = LABEL_PREFIX.len() + label.len();
+    if label_len > u8::MAX as usize {
+        return Err(KdfError::InvalidLabelLength(label.len()));
+    }
+    if context.len() > u8::MAX as usize {
+        return Err(KdfError::InvalidContextLength(context.len()));
+    }
+
+    // struct { uint16 length; opaque label<7..255>; opaque context<0..255>; } HkdfLabel;
+    let mut hkdf_label = Vec::with_capacity(2 + 1 + label_len + 1 + context.len());
+    hkdf_label.extend_from_slice(&(len as u16).to_be_bytes());
+    hkdf_label.push(label_len as u8);
+    hkdf_label.extend_from_slice(LABEL_PREFIX);
+    hkdf_label.extend_from_slice(label);
+    hkdf_label.push(context.len() as u8);
+    hkdf_label.extend_from_slice(context);
+    expand::<D>(secret, &hkdf_label, len)
+}
+
+/// `Derive-Secret` of TLS 1.3 with the hash of the transcript, which the
+/// caller gets from a fork of the running transcript hash.
+pub fn derive_secret<D: Digest>(secret: &[u8], label: &[u8], transcript_hash: &[u8]) -> Result<Vec<u8>, KdfError> {
+    expand_label::<D>(secret, label, transcript_hash, D::OUTPUT_SIZE)
+}
+
+fn check_output_length<D: Digest>(len: usize) -> Result<(), KdfError> {
+    if len > 255 * D::OUTPUT_SIZE {
+        return Err(KdfError::InvalidOutputLength(len));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use crate::test_util::hex;
+    use crate::hash::{Sha1, Sha256};
+
+    /// Test case as (ikm, salt, info, prk, okm).
+    type TestCase = (Vec<u8>, Vec<u8>, Vec<u8>, &'static str, &'static str);
+
+    /// Test cases of RFC 5869 A.1 to A.3 for SHA-256.
+    fn sha256_cases() -> [TestCase; 3] {
+        [
+            (
+                vec![0x0b; 22], (0x00..=0x0c).collect(), (0xf0..=0xf9).collect(),
+                "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+                "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865",
+            ),
+            (
+                (0x00..=0x4f).collect(), (0x60..=0xaf).collect(), (0xb0..=0xff).collect(),
+                "06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244",
+                concat!(
+                    "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c",
+                    "59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71",
+                    "cc30c58179ec3e87c14c01d5c1f3434f1d87",
+                ),
+            ),
+            (
+                vec![0x0b; 22], vec![], vec![],
+                "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04",
+                "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8",
+            ),
+        ]
+    }
+
+    /// Test cases of RFC 5869 A.4 to A.7 for SHA-1, where A.7 has no salt,
+    /// i.e. the zeros of the hash length.
+    fn sha1_cases() -> [TestCase; 4] {
+        [
+            (
+                vec![0x0b; 11], (0x00..=0x0c).collect(), (0xf0..=0xf9).collect(),
+                "9b6c18c432a7bf8f0e71c8eb88f4b30baa2ba243",
+                "085a01ea1b10f36933068b56efa5ad81a4f14b822f5b091568a9cdd4f155fda2c22e422478d305f3f896",
+            ),
+            (
+                (0x00..=0x4f).collect(), (0x60..=0xaf).collect(), (0xb0..=0xff).collect(),
+                "8adae09a2a307059478d309b26c4115a224cfaf6",
+                concat!(
+                    "0bd770a74d1160f7c9f12cd5912a06ebff6adcae899d92191fe4305673ba2ffe",
+                    "8fa3f1a4e5ad79f3f334b3b202b2173c486ea37ce3d397ed034c7f9dfeb15c5e",
+                    "927336d0441f4c4300e2cff0d0900b52d3b4",
+                ),
+            ),
+            (
+                vec![0x0b; 22], vec![], vec![],
+                "da8c8a73c7fa77288ec6f5e7c297786aa0d32d01",
+                "0ac1af7002b3d761d1e55298da9d0506b9ae52057220a306e07b6b87e8df21d0ea00033de03984d34918",
+            ),
+            (
+                vec![0x0c; 22], vec![0; 20], vec![],
+                "2adccada18779e7c2077ad2eb19d3f3e731385dd",
+                "2c91117204d745f3500d636a62f64f0ab3bae548aa53d423b0d1f27ebba6f5e5673a081d70cce7acfc48",
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_rfc5869_sha256() {
+        for (ikm, salt, info, prk, okm) in sha256_cases() {
+            let computed_prk = extract::<Sha256>(&salt, &ikm);
+            assert_eq!(computed_prk, hex(prk));
+            assert_eq!(expand::<Sha256>(&computed_prk, &info, okm.len() / 2).unwrap(), hex(okm));
+        }
+    }
+
+    #[test]
+    fn test_rfc5869_sha1() {
+        for (ikm, salt, info, prk, okm) in sha1_cases() {
+            let computed_prk = extract::<Sha1>(&salt, &ikm);
+            assert_eq!(computed_prk, hex(prk));
+            assert_eq!(expand::<Sha1>(&computed_prk, &info, okm.len() / 2).unwrap(), hex(okm));
+        }
+    }
+
+    #[test]
+    fn test_expand_max_length() {
+        let prk = [0; 32];
+        assert_eq!(expand::<Sha256>(&prk, b"", 255 * 32).unwrap().len(), 255 * 32);
+        assert_eq!(expand::<Sha256>(&prk, b"", 255 * 32 + 1), Err(KdfError::InvalidOutputLength(255 * 32 + 1)));
+    }
+
+    #[test]
+    fn test_rfc8448_key_schedule() {
+        // simple 1-RTT handshake of RFC 8448 3
+        let early_secret = extract::<Sha256>(&[], &[0; 32]);
+        assert_eq!(early_secret, hex("33ad0a1c607ec03b09e6cd9893680ce210adf300aa1f2660e1b22e10f170f92a"));
+
+        let empty_hash = Sha256::digest(b"");
+        let derived = derive_secret::<Sha256>(&early_secret, b"derived", &empty_hash).unwrap();
+        assert_eq!(derived, hex("6f2615a108c702c5678f54fc9dbab69716c076189c48250cebeac3576c3611ba"));
+
+        let shared_secret = hex("8bd4054fb55b9d63fdfbacf9f04b9f0d35e6d63f537563efd46272900f89492d");
+        let handshake_secret = extract::<Sha256>(&derived, &shared_secret);
+        assert_eq!(handshake_secret, hex("1dc826e93606aa6fdc0aadc12f741b01046aa6b99f691ed221a9f0ca043fbeac"));
+
+        // hash of ClientHello and ServerHello
+        let transcript_hash = hex("860c06edc07858ee8e78f0e7428c58edd6b43f2ca3e6e95f02ed063cf0e1cad8");
+        let client_secret = derive_secret::<Sha256>(&handshake_secret, b"c hs traffic", &transcript_hash).unwrap();
+        assert_eq!(client_secret, hex("b3eddb126e067f35a780b3abf45e2d8f3b1a950738f52e9600746a0e27a55a21"));
+        let server_secret = derive_secret::<Sha256>(&handshake_secret, b"s hs traffic", &transcript_hash).unwrap();
+        assert_eq!(server_secret, hex("b67b7d690cc16c4e75e54213cb2d37b4e9c912bcded9105d42befd59d391ad38"));
+
+        let key = expand_label::<Sha256>(&server_secret, b"key", b"", 16).unwrap();
+        assert_eq!(key, hex("3fce516009c21727d0f2e4e86ee403bc"));
+        let iv = expand_label::<Sha256>(&server_secret, b"iv", b"", 12).unwrap();
+        assert_eq!(iv, hex("5d313eb2671276ee13000b30"));
+    }
+
+    #[test]
+    fn test_expand_label_invalid_lengths() {
+        let secret = [0; 32];
+        assert_eq!(expand_label::<Sha256>(&secret, &[b'a'; 250], b"", 32), Err(KdfError::InvalidLabelLength(250)));
+        assert!(expand_label::<Sha256>(&secret, &[b'a'; 249], b"", 32).is_ok());
+        assert_eq!(expand_label::<Sha256>(&secret, b"key", &[0; 256], 32), Err(KdfError::InvalidContextLength(256)));
+        assert_eq!(expand_label::<Sha256>(&secret, b"key", b"", 70000), Err(KdfError::InvalidOutputLength(70000)));
+    }
+}
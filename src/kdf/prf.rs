@@ -0,0 +1,116 @@
+//! Pseudo-random functions of TLS 1.0 to 1.2 which expand the secrets into
+//! the master secret, the key block and the finished verify data.
+
+use alloc::vec::Vec;
+use crate::hash::{Digest, Md5, Sha1};
+use crate::mac::Hmac;
+
+/// Expand the secret with the seed into the output of the length by the data
+/// expansion function `P_hash` of RFC 5246 5.
+pub fn p_hash<D: Digest>(secret: &[u8], seed: &[u8], len: usize) -> Vec<u8> {
+    let hmac = Hmac::<D>::new(secret);
+    let mut output = Vec::with_capacity(len + D::OUTPUT_SIZE);
+    // A(0) = seed, A(i) = HMAC_hash(secret, A(i-1))
+    let mut a = seed.to_vec();
+    while output.len() < len {
+        let mut a_hmac = hmac.clone();
+        a_hmac.update(&a);
+        a = a_hmac.finalize();
+
+        let mut output_hmac = hmac.clone();
+        output_hmac.update(&a);
+        output_hmac.update(seed);
+        output.extend_from_slice(&output_hmac.finalize());
+    }
+    output.truncate(len);
+    output
+}
+
+/// PRF of TLS 1.0 (RFC 2246 5) and TLS 1.1, which XORs `P_MD5` and `P_SHA-1`
+/// of the two halves of the secret.
+pub fn tls10_prf(secret: &[u8], label: &[u8], seed: &[u8], len: usize) -> Vec<u8> {
+    // the halves share the middle byte if the length is odd
+    let half_len = secret.len().div_ceil(2);
+    let label_and_seed = [label, seed].concat();
+    let md5_output = p_hash::<Md5>(&secret[..half_len], &label_and_seed, len);
+    let sha1_output = p_hash::<Sha1>(&secret[secret.len() - half_len..], &label_and_seed, len);
+    md5_output.iter().zip(sha1_output.iter()).map(|(a, b)| { a ^ b }).collect()
+}
+
+/// PRF of TLS 1.2 (RFC 5246 5), which is `P_SHA256` or the hash of the cipher
+/// suite such as `P_SHA384`.
+pub fn tls12_prf<D: Digest>(secret: &[u8], label: &[u8], seed: &[u8], len: usize) -> Vec<u8> {
+    p_hash::<D>(secret, &[label, seed].concat(), len)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::hex;
+    use crate::hash::{Sha256, Sha384};
+
+    #[test]
+    fn test_tls10_prf() {
+        // test vector posted to the IETF TLS working group list
+        let output = tls10_prf(&[0xab; 48], b"PRF Testvector", &[0xcd; 64], 104);
+        assert_eq!(output, hex(concat!(
+            "d3d4d1e349b5d515044666d51de32bab258cb521b6b053463e354832fd976754",
+            "443bcf9a296519bc289abcbc1187e4ebd31e602353776c408aafb74cbc85eff6",
+            "9255f9788faa184cbb957a9819d84a5d7eb006eb459d3ae8de9810454b8b2d8f",
+            "1afbc655a8c9a013",
+        )));
+    }
+
+    #[test]
+    fn test_tls10_prf_odd_secret_length() {
+        // the middle byte belongs to both halves
+        let secret = b"odd";
+        let output = tls10_prf(secret, b"label", b"seed", 20);
+        let md5_output = p_hash::<Md5>(b"od", b"labelseed", 20);
+        let sha1_output = p_hash::<Sha1>(b"dd", b"labelseed", 20);
+        let expected: Vec<u8> = md5_output.iter().zip(sha1_output.iter()).map(|(a, b)| { a ^ b }).collect();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_tls12_prf_sha256() {
+        let output = tls12_prf::<Sha256>(
+            &hex("9bbe436ba940f017b17652849a71db35"),
+            b"test label",
+            &hex("a0ba9f936cda311827a6f796ffd5198c"),
+            100,
+        );
+        assert_eq!(output, hex(concat!(
+            "e3f229ba727be17b8d122620557cd453c2aab21d07c3d495329b52d4e61edb5a",
+            "6b301791e90d35c9c9a46b4e14baf9af0fa022f7077def17abfd3797c0564bab",
+            "4fbc91666e9def9b97fce34f796789baa48082d122ee42c5a72e5a5110fff701",
+            "87347b66",
+        )));
+    }
+
+    #[test]
+    fn test_tls12_prf_sha384() {
+        let output = tls12_prf::<Sha384>(
+            &hex("b80b733d6ceefcdc71566ea48e5567df"),
+            b"test label",
+            &hex("cd665cf6a8447dd6ff8b27555edb7465"),
+            148,
+        );
+        assert_eq!(output, hex(concat!(
+            "7b0c18e9ced410ed1804f2cfa34a336a1c14dffb4900bb5fd7942107e81c83cd",
+            "e9ca0faa60be9fe34f82b1233c9146a0e534cb400fed2700884f9dc236f80edd",
+            "8bfa961144c9e8d792eca722a7b32fc3d416d473ebc2c5fd4abfdad05d918425",
+            "9b5bf8cd4d90fa0d31e2dec479e4f1a26066f2eea9a69236a3e52655c9e9aee6",
+            "91c8f3a26854308d5eaa3be85e0990703d73e56f",
+        )));
+    }
+
+    #[test]
+    fn test_p_hash_prefix() {
+        // shorter output is the prefix of longer output
+        let long = p_hash::<Sha256>(b"secret", b"seed", 100);
+        for len in [0, 1, 31, 32, 33, 64] {
+            assert_eq!(p_hash::<Sha256>(b"secret", b"seed", len), long[..len]);
+        }
+    }
+}
@@ -1 +1,24 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod aead;
+pub mod base16;
+pub mod base32;
 pub mod base64;
+pub mod bigint;
+pub mod cipher;
+pub mod ct;
+pub mod error;
+pub mod hash;
+pub mod kdf;
+pub mod mac;
+pub mod pem;
+pub mod pk;
+pub mod rng;
+
+mod table;
+#[cfg(test)]
+mod test_util;
+
+pub use error::{Error, Result};
@@ -0,0 +1,7 @@
+//! Message authentication codes.
+
+mod hmac;
+mod poly1305;
+
+pub use hmac::Hmac;
+pub use poly1305::Poly1305;
@@ -0,0 +1,212 @@
+//! HMAC (RFC 2104) for `TLS_*_CBC_SHA*` record MACs and the PRF.
+
+use alloc::vec::Vec;
+use crate::ct;
+use crate::hash::Digest;
+
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+/// HMAC over the hash function `D`.
+///
+/// Cloning the state after `new()` saves hashing the padded key again for
+/// each message with the same key.
+#[derive(Clone)]
+pub struct Hmac<D: Digest> {
+    inner: D,
+    outer: D,
+}
+
+impl<D: Digest> Hmac<D> {
+    /// Create with the key of any length.
+    pub fn new(key: &[u8]) -> Hmac<D> {
+        // keys longer than the block are hashed first
+        let mut padded_key = if key.len() > D::BLOCK_SIZE { D::digest(key) } else { key.to_vec() };
+        padded_key.resize(D::BLOCK_SIZE, 0);
+
+        let mut inner = D::new();
+        let mut outer = D::new();
+        inner.update(&padded_key.iter().map(|byte| { byte ^ IPAD }).collect::<Vec<u8>>());
+        outer.update(&padded_key.iter().map(|byte| { byte ^ OPAD }).collect::<Vec<u8>>());
+        Hmac { inner, outer }
+    }
+
+    /// Get the MAC of the data at once.
+    pub fn mac(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut hmac = Hmac::<D>::new(key);
+        hmac.update(data);
+        hmac.finalize()
+    }
+
+    /// Process the data.
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Get the MAC of all the data processed, of `D::OUTPUT_SIZE` bytes.
+    pub fn finalize(self) -> Vec<u8> {
+        let mut outer = self.outer;
+        outer.update(&self.inner.finalize());
+        outer.finalize()
+    }
+
+    /// Check if the tag is the MAC of all the data processed in constant
+    /// time.
+    #[must_use]
+    pub fn verify(self, tag: &[u8]) -> bool {
+        ct::eq(&self.finalize(), tag)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use crate::test_util::hex;
+    use crate::hash::{Md5, Sha1, Sha224, Sha256, Sha384, Sha512};
+
+    /// Keys and data of the test cases of RFC 2202 and RFC 4231, which differ
+    /// in the key lengths and the last data.
+    fn test_cases(key_len: usize, long_key_len: usize, last_data: &[u8]) -> [(Vec<u8>, Vec<u8>); 7] {
+        [
+            (vec![0x0b; key_len], b"Hi There".to_vec()),
+            (b"Jefe".to_vec(), b"what do ya want for nothing?".to_vec()),
+            (vec![0xaa; key_len], vec![0xdd; 50]),
+            ((0x01..=0x19).collect(), vec![0xcd; 50]),
+            (vec![0x0c; key_len], b"Test With Truncation".to_vec()),
+            (vec![0xaa; long_key_len], b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec()),
+            (vec![0xaa; long_key_len], last_data.to_vec()),
+        ]
+    }
+
+    const RFC2202_LAST_DATA: &[u8] = b"Test Using Larger Than Block-Size Key and Larger Than One Block-Size Data";
+    const RFC4231_LAST_DATA: &[u8] = b"This is a test using a larger than block-size key and a larger than block-size data. \
+        The key needs to be hashed before being used by the HMAC algorithm.";
+
+    /// Check the MACs, which may be truncated as in the RFC.
+    fn check<D: Digest>(cases: [(Vec<u8>, Vec<u8>); 7], macs: [&str; 7]) {
+        for (i, ((key, data), mac)) in cases.iter().zip(macs.iter()).enumerate() {
+            let output = Hmac::<D>::mac(key, data);
+            assert_eq!(output.len(), D::OUTPUT_SIZE);
+            assert_eq!(output[..mac.len() / 2], hex(mac)[..], "test case {}", i + 1);
+        }
+    }
+
+    #[test]
+    fn test_rfc2202_md5() {
+        check::<Md5>(test_cases(16, 80, RFC2202_LAST_DATA), [
+            "9294727a3638bb1c13f48ef8158bfc9d",
+            "750c783e6ab0b503eaa86e310a5db738",
+            "56be34521d144c88dbb8c733f0e8b3f6",
+            "697eaf0aca3a3aea3a75164746ffaa79",
+            "56461ef2342edc00f9bab995",
+            "6b1ab7fe4bd7bf8f0b62e6ce61b9d0cd",
+            "6f630fad67cda0ee1fb1f562db3aa53e",
+        ]);
+    }
+
+    #[test]
+    fn test_rfc2202_sha1() {
+        check::<Sha1>(test_cases(20, 80, RFC2202_LAST_DATA), [
+            "b617318655057264e28bc0b6fb378c8ef146be00",
+            "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79",
+            "125d7342b9ac11cd91a39af48aa17b4f63f175d3",
+            "4c9007f4026250c6bc8414f9bf50c86c2d7235da",
+            "4c1a03424b55e07fe7f27be1",
+            "aa4ae5e15272d00e95705637ce8a3b55ed402112",
+            "e8e99d0f45237d786d6bbaa7965c7808bbff1a91",
+        ]);
+    }
+
+    #[test]
+    fn test_rfc4231_sha224() {
+        check::<Sha224>(test_cases(20, 131, RFC4231_LAST_DATA), [
+            "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22",
+            "a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44",
+            "7fb3cb3588c6c1f6ffa9694d7d6ad2649365b0c1f65d69d1ec8333ea",
+            "6c11506874013cac6a2abc1bb382627cec6a90d86efc012de7afec5a",
+            "0e2aea68a90c8d37c988bcdb9fca6fa8",
+            "95e9a0db962095adaebe9b2d6f0dbce2d499f112f2d2b7273fa6870e",
+            "3a854166ac5d9f023f54d517d0b39dbd946770db9c2b95c9f6f565d1",
+        ]);
+    }
+
+    #[test]
+    fn test_rfc4231_sha256() {
+        check::<Sha256>(test_cases(20, 131, RFC4231_LAST_DATA), [
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+            "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+            "a3b6167473100ee06e0c796c2955552b",
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+        ]);
+    }
+
+    #[test]
+    fn test_rfc4231_sha384() {
+        check::<Sha384>(test_cases(20, 131, RFC4231_LAST_DATA), [
+            "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59cfaea9ea9076ede7f4af152e8b2fa9cb6",
+            "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649",
+            "88062608d3e6ad8a0aa2ace014c8a86f0aa635d947ac9febe83ef4e55966144b2a5ab39dc13814b94e3ab6e101a34f27",
+            "3e8a69b7783c25851933ab6290af6ca77a9981480850009cc5577c6e1f573b4e6801dd23c4a7d679ccf8a386c674cffb",
+            "3abf34c3503b2a23a46efc619baef897",
+            "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c60c2ef6ab4030fe8296248df163f44952",
+            "6617178e941f020d351e2f254e8fd32c602420feb0b8fb9adccebb82461e99c5a678cc31e799176d3860e6110c46523e",
+        ]);
+    }
+
+    #[test]
+    fn test_rfc4231_sha512() {
+        check::<Sha512>(test_cases(20, 131, RFC4231_LAST_DATA), [
+            concat!(
+                "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde",
+                "daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+            ),
+            concat!(
+                "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554",
+                "9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+            ),
+            concat!(
+                "fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39",
+                "bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb",
+            ),
+            concat!(
+                "b0ba465637458c6990e5a8c5f61d4af7e576d97ff94b872de76f8050361ee3db",
+                "a91ca5c11aa25eb4d679275cc5788063a5f19741120c4f2de2adebeb10a298dd",
+            ),
+            "415fad6271580a531d4179bc891d87a6",
+            concat!(
+                "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352",
+                "6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+            ),
+            concat!(
+                "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944",
+                "b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_update_in_pieces() {
+        let mut hmac = Hmac::<Sha256>::new(b"Jefe");
+        for piece in [&b"what do ya"[..], b" want ", b"for nothing?"] {
+            hmac.update(piece);
+        }
+        assert_eq!(hmac.finalize(), hex("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"));
+    }
+
+    #[test]
+    fn test_verify() {
+        let tag = hex("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+        let mut hmac = Hmac::<Sha256>::new(b"Jefe");
+        hmac.update(b"what do ya want for nothing?");
+        assert!(hmac.clone().verify(&tag));
+
+        let mut forged_tag = tag.clone();
+        forged_tag[31] ^= 1;
+        assert!(!hmac.clone().verify(&forged_tag));
+        assert!(!hmac.verify(&tag[..16]));
+    }
+}
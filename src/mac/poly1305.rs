@@ -0,0 +1,230 @@
+//! Poly1305 one-time authenticator (RFC 8439).
+//!
+//! The accumulator is kept in five 26-bit limbs so that the products fit in
+//! `u64`, and the final reduction selects with masks, so it runs in constant
+//! time.
+
+use core::convert::TryInto;
+
+/// Key size in bytes, `r` followed by `s`.
+pub const KEY_SIZE: usize = 32;
+/// Tag size in bytes.
+pub const TAG_SIZE: usize = 16;
+
+const BLOCK_SIZE: usize = 16;
+const LIMB_MASK: u32 = 0x3ff_ffff;
+
+/// Poly1305 which must not be used with the same key for two messages.
+#[derive(Clone)]
+pub struct Poly1305 {
+    /// Clamped `r` in 26-bit limbs.
+    r: [u32; 5],
+    s: [u32; 4],
+    /// Accumulator in 26-bit limbs, partially reduced.
+    h: [u32; 5],
+    buffer: [u8; BLOCK_SIZE],
+    buffer_len: usize,
+}
+
+impl Poly1305 {
+    pub fn new(key: &[u8; KEY_SIZE]) -> Poly1305 {
+        let word = |offset: usize| { u32::from_le_bytes(key[offset..offset + 4].try_into().unwrap()) };
+        // clamp r with 0x0ffffffc0ffffffc0ffffffc0fffffff
+        let r = [
+            word(0) & 0x3ff_ffff,
+            (word(3) >> 2) & 0x3ff_ff03,
+            (word(6) >> 4) & 0x3ff_c0ff,
+            (word(9) >> 6) & 0x3f0_3fff,
+            (word(12) >> 8) & 0x00f_ffff,
+        ];
+        let s = [word(16), word(20), word(24), word(28)];
+        Poly1305 { r, s, h: [0; 5], buffer: [0; BLOCK_SIZE], buffer_len: 0 }
+    }
+
+    /// Process the data.
+    pub fn update(&mut self, mut data: &[u8]) {
+        if self.buffer_len > 0 {
+            let len = data.len().min(BLOCK_SIZE - self.buffer_len);
+            self.buffer[self.buffer_len..self.buffer_len + len].copy_from_slice(&data[..len]);
+            self.buffer_len += len;
+            data = &data[len..];
+            if self.buffer_len < BLOCK_SIZE {
+                return;
+            }
+            let block = self.buffer;
+            self.process_block(&block, 1 << 24);
+            self.buffer_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(BLOCK_SIZE);
+        for block in &mut blocks {
+            self.process_block(block.try_into().unwrap(), 1 << 24);
+        }
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_len = remainder.len();
+    }
+
+    /// Get the tag.
+    pub fn finalize(mut self) -> [u8; TAG_SIZE] {
+        if self.buffer_len > 0 {
+            // the last partial block is followed by a 1 byte and zeros
+            let mut block = [0; BLOCK_SIZE];
+            block[..self.buffer_len].copy_from_slice(&self.buffer[..self.buffer_len]);
+            block[self.buffer_len] = 1;
+            self.process_block(&block, 0);
+        }
+
+        // fully carry h
+        let mut h = self.h;
+        let mut carry;
+        for i in 1..5 {
+            carry = h[i - 1] >> 26;
+            h[i - 1] &= LIMB_MASK;
+            h[i] += carry;
+        }
+        carry = h[4] >> 26;
+        h[4] &= LIMB_MASK;
+        h[0] += carry * 5;
+        carry = h[0] >> 26;
+        h[0] &= LIMB_MASK;
+        h[1] += carry;
+
+        // g = h + 5 - 2^130, which is h mod p if it doesn't underflow
+        let mut g = [0; 5];
+        carry = 5;
+        for i in 0..4 {
+            g[i] = h[i] + carry;
+            carry = g[i] >> 26;
+            g[i] &= LIMB_MASK;
+        }
+        g[4] = h[4].wrapping_add(carry).wrapping_sub(1 << 26);
+
+        // select h if g underflowed or g otherwise
+        let mask = (g[4] >> 31).wrapping_sub(1);
+        for i in 0..5 {
+            h[i] = (h[i] & !mask) | (g[i] & mask);
+        }
+
+        // h + s mod 2^128
+        let words = [
+            h[0] | (h[1] << 26),
+            (h[1] >> 6) | (h[2] << 20),
+            (h[2] >> 12) | (h[3] << 14),
+            (h[3] >> 18) | (h[4] << 8),
+        ];
+        let mut tag = [0; TAG_SIZE];
+        let mut sum = 0u64;
+        for i in 0..4 {
+            sum = words[i] as u64 + self.s[i] as u64 + (sum >> 32);
+            tag[i * 4..i * 4 + 4].copy_from_slice(&(sum as u32).to_le_bytes());
+        }
+        tag
+    }
+
+    /// h = (h + block) * r mod 2^130 - 5, where `high_bit` is the bit 128 of
+    /// the block in the top limb.
+    fn process_block(&mut self, block: &[u8; BLOCK_SIZE], high_bit: u32) {
+        let word = |offset: usize| { u32::from_le_bytes(block[offset..offset + 4].try_into().unwrap()) };
+        let h = [
+            self.h[0] + (word(0) & LIMB_MASK),
+            self.h[1] + ((word(3) >> 2) & LIMB_MASK),
+            self.h[2] + ((word(6) >> 4) & LIMB_MASK),
+            self.h[3] + ((word(9) >> 6) & LIMB_MASK),
+            self.h[4] + ((word(12) >> 8) | high_bit),
+        ].map(u64::from);
+
+        // 2^130 = 5 mod p, so the limbs above 2^130 wrap around times 5
+        let r = self.r.map(u64::from);
+        let s = [0, r[1] * 5, r[2] * 5, r[3] * 5, r[4] * 5];
+        let mut d = [0u64; 5];
+        for (i, d) in d.iter_mut().enumerate() {
+            for (j, h) in h.iter().enumerate() {
+                let r = if j <= i { r[i - j] } else { s[5 + i - j] };
+                *d += h * r;
+            }
+        }
+
+        let mut carry = 0;
+        for (h, d) in self.h.iter_mut().zip(d.iter()) {
+            let d = d + carry;
+            carry = d >> 26;
+            *h = d as u32 & LIMB_MASK;
+        }
+        let h0 = self.h[0] as u64 + carry * 5;
+        self.h[0] = h0 as u32 & LIMB_MASK;
+        self.h[1] += (h0 >> 26) as u32;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use crate::test_util::hex;
+
+    fn tag(key: &str, message: &[u8]) -> Vec<u8> {
+        let mut poly1305 = Poly1305::new(hex(key)[..].try_into().unwrap());
+        poly1305.update(message);
+        poly1305.finalize().to_vec()
+    }
+
+    #[test]
+    fn test_rfc8439() {
+        // 2.5.2
+        let key = "85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b";
+        assert_eq!(tag(key, b"Cryptographic Forum Research Group"), hex("a8061dc1305136c6c22b8baf0c0127a9"));
+    }
+
+    #[test]
+    fn test_update_in_pieces() {
+        let key = "85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b";
+        let message = b"Cryptographic Forum Research Group";
+        for piece_len in 1..message.len() {
+            let mut poly1305 = Poly1305::new(hex(key)[..].try_into().unwrap());
+            message.chunks(piece_len).for_each(|piece| { poly1305.update(piece) });
+            assert_eq!(poly1305.finalize()[..], hex("a8061dc1305136c6c22b8baf0c0127a9")[..], "{}", piece_len);
+        }
+    }
+
+    /// Edge cases of the reduction in RFC 8439 A.3 as (key, message, tag).
+    const EDGE_CASES: [(&str, &str, &str); 6] = [
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            "00000000000000000000000000000000",
+        ),
+        (
+            "0200000000000000000000000000000000000000000000000000000000000000",
+            "ffffffffffffffffffffffffffffffff",
+            "03000000000000000000000000000000",
+        ),
+        (
+            "02000000000000000000000000000000ffffffffffffffffffffffffffffffff",
+            "02000000000000000000000000000000",
+            "03000000000000000000000000000000",
+        ),
+        (
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            "fffffffffffffffffffffffffffffffff0ffffffffffffffffffffffffffffff11000000000000000000000000000000",
+            "05000000000000000000000000000000",
+        ),
+        (
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            "fffffffffffffffffffffffffffffffffbfefefefefefefefefefefefefefefe01010101010101010101010101010101",
+            "00000000000000000000000000000000",
+        ),
+        (
+            "0200000000000000000000000000000000000000000000000000000000000000",
+            "fdffffffffffffffffffffffffffffff",
+            "faffffffffffffffffffffffffffffff",
+        ),
+    ];
+
+    #[test]
+    fn test_edge_cases() {
+        for (key, message, expected) in EDGE_CASES {
+            assert_eq!(tag(key, &hex(message)), hex(expected), "{} {}", key, message);
+        }
+    }
+}
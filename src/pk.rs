@@ -0,0 +1,6 @@
+//! Public-key cryptography of the key exchange and the signatures of the
+//! handshake.
+
+pub mod dh;
+pub mod ecc;
+pub mod rsa;
@@ -0,0 +1,407 @@
+//! Finite field Diffie-Hellman of the `DHE_*` key exchange of TLS 1.2 and
+//! the `ffdhe*` groups of TLS 1.3.
+//!
+//! The peer's public value is validated before use, which keeps a
+//! malicious peer from confining the shared secret to a small subgroup.
+
+use alloc::vec::Vec;
+use core::fmt;
+use crate::bigint::BigUint;
+use crate::rng::Rng;
+
+mod ffdhe;
+
+/// Minimum size of the prime in bits, below which the discrete logarithm
+/// is within reach (Logjam).
+pub const MIN_PRIME_BITS: usize = 1024;
+/// Maximum size of the prime in bits, which bounds the time spent on the
+/// parameters sent by the server.
+pub const MAX_PRIME_BITS: usize = 8192;
+
+/// Errors of Diffie-Hellman.
+#[derive(Debug, PartialEq)]
+pub enum DhError {
+    /// The prime or the generator is invalid or of an unsupported size.
+    InvalidGroup,
+    /// The public value of the peer is out of range or not in the subgroup.
+    InvalidPublicKey,
+    /// The shared secret is degenerate.
+    InvalidSharedSecret,
+}
+
+impl fmt::Display for DhError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DhError::InvalidGroup => write!(f, "invalid DH group"),
+            DhError::InvalidPublicKey => write!(f, "invalid DH public key"),
+            DhError::InvalidSharedSecret => write!(f, "invalid DH shared secret"),
+        }
+    }
+}
+
+impl core::error::Error for DhError {}
+
+/// Group of the prime `p` and the generator `g`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DhGroup {
+    p: BigUint,
+    g: BigUint,
+    /// Order of the subgroup which `g` generates, `(p - 1) / 2` of the safe
+    /// primes, if it is known.
+    q: Option<BigUint>,
+}
+
+impl DhGroup {
+    /// Create with the parameters sent by the server, e.g. in
+    /// `ServerKeyExchange` of TLS 1.2.
+    ///
+    /// The primality of `p` is not tested, which is too slow for each
+    /// handshake, so the subgroup is known only if `p` and `g` exactly match
+    /// a named group. The public values of other groups are checked only for
+    /// the range; use `with_subgroup` if their order is known.
+    pub fn new(p: BigUint, g: BigUint) -> Result<DhGroup, DhError> {
+        DhGroup::check_parameters(&p, &g)?;
+
+        // build only the named group of the same size, as this runs on each
+        // ServerKeyExchange
+        let named: Option<fn() -> DhGroup> = match p.bits() {
+            2048 => Some(DhGroup::ffdhe2048),
+            3072 => Some(DhGroup::ffdhe3072),
+            4096 => Some(DhGroup::ffdhe4096),
+            6144 => Some(DhGroup::ffdhe6144),
+            8192 => Some(DhGroup::ffdhe8192),
+            _ => None,
+        };
+        let named = named
+            .map(|group| { group() })
+            .filter(|group| { group.p == p && group.g == g });
+        Ok(named.unwrap_or(DhGroup { p, g, q: None }))
+    }
+
+    /// Create with the order `q` of the subgroup which `g` generates, e.g. of
+    /// the groups of RFC 5114, so that the public values get the subgroup
+    /// check.
+    ///
+    /// `g ^ q = 1 mod p` is checked, but the primality of `p` and `q` is not
+    /// tested, so `q` must come from a trusted source.
+    pub fn with_subgroup(p: BigUint, g: BigUint, q: BigUint) -> Result<DhGroup, DhError> {
+        DhGroup::check_parameters(&p, &g)?;
+        if q <= BigUint::one() || q >= p || !g.modpow(&q, &p).is_one() {
+            return Err(DhError::InvalidGroup);
+        }
+        Ok(DhGroup { p, g, q: Some(q) })
+    }
+
+    /// Check the size of `p` and that `g` is in `(1, p - 1)`.
+    fn check_parameters(p: &BigUint, g: &BigUint) -> Result<(), DhError> {
+        if p.is_even() || p.bits() < MIN_PRIME_BITS || p.bits() > MAX_PRIME_BITS {
+            return Err(DhError::InvalidGroup);
+        }
+        let one = BigUint::one();
+        if g <= &one || g >= &(p - &one) {
+            return Err(DhError::InvalidGroup);
+        }
+        Ok(())
+    }
+
+    /// Group of the safe prime with the generator 2 of the order `q`.
+    fn safe_prime(p: &str) -> DhGroup {
+        let p = BigUint::from_hex(p).unwrap();
+        let q = &p >> 1;
+        DhGroup { p, g: BigUint::from(2), q: Some(q) }
+    }
+
+    pub fn ffdhe2048() -> DhGroup {
+        DhGroup::safe_prime(ffdhe::FFDHE2048_P)
+    }
+
+    pub fn ffdhe3072() -> DhGroup {
+        DhGroup::safe_prime(ffdhe::FFDHE3072_P)
+    }
+
+    pub fn ffdhe4096() -> DhGroup {
+        DhGroup::safe_prime(ffdhe::FFDHE4096_P)
+    }
+
+    pub fn ffdhe6144() -> DhGroup {
+        DhGroup::safe_prime(ffdhe::FFDHE6144_P)
+    }
+
+    pub fn ffdhe8192() -> DhGroup {
+        DhGroup::safe_prime(ffdhe::FFDHE8192_P)
+    }
+
+    /// Get the prime.
+    pub fn p(&self) -> &BigUint {
+        &self.p
+    }
+
+    /// Get the generator.
+    pub fn g(&self) -> &BigUint {
+        &self.g
+    }
+
+    /// Get the length of the prime in bytes, which the public values and the
+    /// shared secret of TLS 1.3 are padded to.
+    pub fn size(&self) -> usize {
+        self.p.bits().div_ceil(8)
+    }
+
+    /// Check that the public value is in `(1, p - 1)` and, for the known
+    /// subgroup, that `y ^ q = 1 mod p` (RFC 7919 5.1, SP 800-56A 5.6.2.3).
+    ///
+    /// The subgroup is known only for the named groups, the groups of `new`
+    /// which exactly match one, and the groups of `with_subgroup`. Other
+    /// groups get only the range check.
+    pub fn validate_public_key(&self, y: &BigUint) -> Result<(), DhError> {
+        let one = BigUint::one();
+        if y <= &one || y >= &(&self.p - &one) {
+            return Err(DhError::InvalidPublicKey);
+        }
+        if let Some(q) = &self.q {
+            if !y.modpow(q, &self.p).is_one() {
+                return Err(DhError::InvalidPublicKey);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Ephemeral private key of Diffie-Hellman.
+#[derive(Clone)]
+pub struct DhPrivateKey {
+    group: DhGroup,
+    x: BigUint,
+    y: BigUint,
+}
+
+impl DhPrivateKey {
+    /// Generate the private exponent in `[2, q - 1]` of the known subgroup,
+    /// or in `[2, p - 2]` otherwise.
+    pub fn generate<R: Rng + ?Sized>(group: &DhGroup, rng: &mut R) -> DhPrivateKey {
+        let two = BigUint::from(2);
+        let upper = match &group.q {
+            Some(q) => q - &BigUint::one(),
+            None => &group.p - &two,
+        };
+        let x = &BigUint::random_below(&(&upper - &BigUint::one()), rng) + &two;
+        DhPrivateKey::from_exponent(group, x)
+    }
+
+    fn from_exponent(group: &DhGroup, x: BigUint) -> DhPrivateKey {
+        let y = group.g.modpow(&x, &group.p);
+        DhPrivateKey { group: group.clone(), x, y }
+    }
+
+    pub fn group(&self) -> &DhGroup {
+        &self.group
+    }
+
+    /// Get the public value `g ^ x mod p`.
+    pub fn public_key(&self) -> &BigUint {
+        &self.y
+    }
+
+    /// Validate the peer's public value and get the shared secret
+    /// `y ^ x mod p`, padded with zeros to the length of the prime as
+    /// TLS 1.3 uses it.
+    /// The premaster secret of TLS 1.2 is without the leading zeros
+    /// (RFC 5246 8.1.2).
+    pub fn compute_shared_secret(&self, peer_public_key: &BigUint) -> Result<Vec<u8>, DhError> {
+        self.group.validate_public_key(peer_public_key)?;
+        let z = peer_public_key.modpow(&self.x, &self.group.p);
+        if z.is_one() {
+            return Err(DhError::InvalidSharedSecret);
+        }
+        Ok(z.to_bytes_be_padded(self.group.size()).unwrap())
+    }
+}
+
+impl fmt::Debug for DhPrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DhPrivateKey")
+            .field("group", &self.group)
+            .field("y", &self.y)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::ChaChaRng;
+    use crate::test_util::{biguint, hex};
+
+    /// Key exchange in ffdhe2048 computed by OpenSSL.
+    mod ffdhe2048 {
+        pub const A: &str = concat!(
+            "1b5b776cecb4b2740883ad16e4c8ea32a9275e4e5df38a37a623b9188ac6285a",
+            "13122e614e2bf47af5d1bfe353adcaf5ae635d5f285f0fca9c82b800d7df8b33",
+            "410027c7c2b3cb62afee4ee315ca51affec0ca1df3f9daa17ff032fa4dfa5465",
+            "ae8de42971b791cdd860055bbd38e7e27dc67e9ef54a07562b2cbd4c84533247",
+            "07362bea1d978d8ca29af482fce799cdb895579cdda3426b77bf23b970fe21e4",
+            "0341123cc414d39dec13f9abb97582c6488b09acb4e16c74ce6f291a26bb9d18",
+            "ffada062c1fb0cf7b4b4e566177f53c2ae80b07aabbf3b842b5c138b31b03dd5",
+            "2ad61d54ff8f735c37e06c7b2ebe57949530fcd9d6fd1d9b62032801b65c1c2a",
+        );
+        pub const GA: &str = concat!(
+            "7c043404d99b20965897d7bec6e799350321ffa186e324cf2b3c4463a4447ba4",
+            "a2258fbcc0a651e786c98ce1cc37b93c29b81a07c2478806078d28df7921d17f",
+            "10f07fda250439ccd02cffd0a226f3d30d0e45fe3d16e57f7056fbc43432b313",
+            "e6ab4da24a81c3ea6bac9aac27751e033e83d6eb9b3652c36c73858eee8c12f6",
+            "ed07ed045ffc9277600ae4a68961325d2b7151bfadd91a8bd1521c97fdc2048a",
+            "e5c9a06fa5b1cdc1c7955dca4910f3d079601586949cb40f0d19bf6917c36317",
+            "82c3e473e80e8d43a84a4e86ecec5b306de1d7b10200da8ab264e029a1aefe0c",
+            "73d3db1f1d75463c7242b5933a6c55651d471af7fa0a780ec0f1904675c2eba1",
+        );
+        pub const B: &str = concat!(
+            "88430ac674dfd554e55b2becb762dbf34e07c13642a0ead3afb6166a610b6be8",
+            "ac89a22a4d8173a503a6da642ce340c22f9cab4a187117715a3b9c13e1b76aac",
+            "bdbaa0e8837825539c96231c3d778c582435919ff535527426666778164dc3c7",
+            "9d0324121a015d3c891d109f948287f9ab52a86ab9e194232ab412d7ac3e4635",
+            "30293fb24a23aa3a71fa220277ebc9ec8e081497e92c07c938d017a17f5d3a63",
+            "2b51ef9ecb61cb7461c7d08be2729948ff03dcd4433962448bd7826dc170d4a7",
+            "d52a9c1fdf24503d90353c6d1b3d79c272689a5cc8fec8e98b20ad3aa45fad93",
+            "ef8884018cec47b4f8f2d8811d1fd36be35f399e5104b7856c419a250f068c9",
+        );
+        pub const GB: &str = concat!(
+            "ab99ed5385be0c3085f41a3990d624512d4a3b70fcf3e3829d7c1e8444a5a7bd",
+            "65b6a8829b4a8a73f649abc360179a19ba08e03cc9000fa1aeb3b7b66d3137aa",
+            "4330d8aca0d4df0eb6605ccda61b63c523d5725ed4ff0295dc497a38d1e22e4b",
+            "5b0125162102d1a00caad2692d57e11d0d15b3e30cfd0f6a0df37d070324c82e",
+            "afeebb842e70c013f446ea4086604fdb4260242fd1aa97447aeff5d9331c8484",
+            "4eefe4dd1af8a219648ddbc174e40b34c72971c0030dd95a3bb8b888f51fbefc",
+            "811ab6947842d8b99882973a3504d3898b9efcbf0bdb9e795eff831ab01881e5",
+            "f6adffc021732b4e9569863d6a6f9b293112d523ec8b3c373393a844cc88ea55",
+        );
+        pub const GAB: &str = concat!(
+            "34babcc0a09f6d3b00d190ff42bf2efe3c428e4185417f67afa9f21f6fb8ae97",
+            "c669872ad68679fc0cd2d81d6218e7cdd96df384d1cef647468a5d407cca9fb2",
+            "e03991a1a48a9ca87dd4a7ed478781c7328df4a3f30b4c9d37711abd73eb9218",
+            "0c320903f2489169db4b83b1902c9643690cf0a898d3fdc3f438ae03caeb5777",
+            "59be79571c728dc85ed92965bfd9a086b66f4e1c7e48d3aa63bf328ccc16f6bc",
+            "8150c92e0ce27f31c39d82c9bc0a57a7f1653b2bd9560c2f673880d632a4d3cf",
+            "7b80f27ec7e662a4f9216fec54ddf386e31608ace549eb1a495597e17da4e071",
+            "f93a99000bf8d68465d23192bcf8171279857d772819658a6315c46881eaa5c6",
+        );
+    }
+
+    #[test]
+    fn test_named_groups() {
+        let groups = [
+            (DhGroup::ffdhe2048(), 2048),
+            (DhGroup::ffdhe3072(), 3072),
+            (DhGroup::ffdhe4096(), 4096),
+            (DhGroup::ffdhe6144(), 6144),
+            (DhGroup::ffdhe8192(), 8192),
+        ];
+        for (group, bits) in groups {
+            // p = 2 ^ b - 2 ^ (b - 64) + {[2 ^ (b - 130) e] + X} * 2 ^ 64 - 1
+            assert_eq!(group.p().bits(), bits);
+            assert_eq!(group.size() * 8, bits);
+            assert_eq!(group.p().limbs()[0], u64::MAX);
+            assert_eq!((group.p() >> (bits - 64)).to_u64(), Some(u64::MAX));
+            assert_eq!(group.g(), &BigUint::from(2));
+            assert_eq!(&(group.q.as_ref().unwrap() << 1) + &BigUint::one(), *group.p());
+        }
+    }
+
+    #[test]
+    fn test_ffdhe2048_key_exchange() {
+        let group = DhGroup::ffdhe2048();
+        let a = DhPrivateKey::from_exponent(&group, biguint(ffdhe2048::A));
+        let b = DhPrivateKey::from_exponent(&group, biguint(ffdhe2048::B));
+        assert_eq!(a.public_key(), &biguint(ffdhe2048::GA));
+        assert_eq!(b.public_key(), &biguint(ffdhe2048::GB));
+
+        let secret = hex(ffdhe2048::GAB);
+        assert_eq!(a.compute_shared_secret(b.public_key()).unwrap(), secret);
+        assert_eq!(b.compute_shared_secret(a.public_key()).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut rng = ChaChaRng::from_seed(&[9; 32]);
+        let group = DhGroup::ffdhe2048();
+        let a = DhPrivateKey::generate(&group, &mut rng);
+        let b = DhPrivateKey::generate(&group, &mut rng);
+        assert_ne!(a.public_key(), b.public_key());
+        assert!(group.validate_public_key(a.public_key()).is_ok());
+
+        let secret = a.compute_shared_secret(b.public_key()).unwrap();
+        assert_eq!(secret.len(), 256);
+        assert_eq!(b.compute_shared_secret(a.public_key()).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_public_key_validation() {
+        let group = DhGroup::ffdhe2048();
+        let p1 = group.p() - &BigUint::one();
+        for y in [BigUint::zero(), BigUint::one(), p1.clone(), group.p().clone(), &p1 + &BigUint::from(2)] {
+            assert_eq!(group.validate_public_key(&y), Err(DhError::InvalidPublicKey));
+        }
+        // p - 2 has the order p - 1 as -2 is a non-residue
+        assert_eq!(group.validate_public_key(&(group.p() - &BigUint::from(2))), Err(DhError::InvalidPublicKey));
+        assert!(group.validate_public_key(&BigUint::from(4)).is_ok());
+
+        let key = DhPrivateKey::generate(&group, &mut ChaChaRng::from_seed(&[9; 32]));
+        assert_eq!(key.compute_shared_secret(&p1), Err(DhError::InvalidPublicKey));
+    }
+
+    #[test]
+    fn test_server_parameters() {
+        // the parameters of a named group get the subgroup check
+        let named = DhGroup::ffdhe3072();
+        let group = DhGroup::new(named.p().clone(), BigUint::from(2)).unwrap();
+        assert_eq!(group, named);
+        // other primes of the same size are not mistaken for the named one
+        let group = DhGroup::new(named.p() - &BigUint::from(2), BigUint::from(2)).unwrap();
+        assert_eq!(group.q, None);
+
+        // 1024-bit MODP group of RFC 2409 without the known subgroup
+        let p = biguint(concat!(
+            "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74",
+            "020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f1437",
+            "4fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7ed",
+            "ee386bfb5a899fa5ae9f24117c4b1fe649286651ece65381ffffffffffffffff",
+        ));
+        let group = DhGroup::new(p.clone(), BigUint::from(2)).unwrap();
+        assert_eq!(group.q, None);
+        let mut rng = ChaChaRng::from_seed(&[10; 32]);
+        let a = DhPrivateKey::generate(&group, &mut rng);
+        let b = DhPrivateKey::generate(&group, &mut rng);
+        assert_eq!(a.compute_shared_secret(b.public_key()).unwrap(), b.compute_shared_secret(a.public_key()).unwrap());
+        assert!(group.validate_public_key(&(&p - &BigUint::from(2))).is_ok());
+
+        assert_eq!(DhGroup::new(&p + &BigUint::one(), BigUint::from(2)), Err(DhError::InvalidGroup));
+        assert_eq!(DhGroup::new(&p >> 512, BigUint::from(2)), Err(DhError::InvalidGroup));
+        assert_eq!(DhGroup::new(p.clone(), BigUint::one()), Err(DhError::InvalidGroup));
+        assert_eq!(DhGroup::new(p.clone(), &p - &BigUint::one()), Err(DhError::InvalidGroup));
+    }
+
+    #[test]
+    fn test_with_subgroup() {
+        let named = DhGroup::ffdhe2048();
+        let q = named.q.clone().unwrap();
+        let group = DhGroup::with_subgroup(named.p().clone(), BigUint::from(2), q.clone()).unwrap();
+        assert_eq!(group, named);
+
+        // the quadratic residue 4 also generates the subgroup of the order q
+        let group = DhGroup::with_subgroup(named.p().clone(), BigUint::from(4), q.clone()).unwrap();
+        assert_eq!(group.q.as_ref(), Some(&q));
+        let p2 = named.p() - &BigUint::from(2);
+        assert_eq!(group.validate_public_key(&p2), Err(DhError::InvalidPublicKey));
+        let mut rng = ChaChaRng::from_seed(&[11; 32]);
+        let a = DhPrivateKey::generate(&group, &mut rng);
+        let b = DhPrivateKey::generate(&group, &mut rng);
+        assert_eq!(a.compute_shared_secret(b.public_key()).unwrap(), b.compute_shared_secret(a.public_key()).unwrap());
+
+        // -2 is a non-residue out of the subgroup
+        assert_eq!(DhGroup::with_subgroup(named.p().clone(), p2, q.clone()), Err(DhError::InvalidGroup));
+        let p = named.p().clone();
+        assert_eq!(DhGroup::with_subgroup(p.clone(), BigUint::from(2), &q + &BigUint::one()), Err(DhError::InvalidGroup));
+        assert_eq!(DhGroup::with_subgroup(p.clone(), BigUint::from(2), BigUint::one()), Err(DhError::InvalidGroup));
+        assert_eq!(DhGroup::with_subgroup(p.clone(), BigUint::from(2), p.clone()), Err(DhError::InvalidGroup));
+        assert_eq!(DhGroup::with_subgroup(&p + &BigUint::one(), BigUint::from(2), q), Err(DhError::InvalidGroup));
+    }
+}
@@ -0,0 +1,454 @@
+//! Elliptic curve Diffie-Hellman over the NIST curves P-256 and P-384 of the
+//! `ECDHE_*` key exchange of TLS 1.2 and the `secp256r1` and `secp384r1`
+//! groups of TLS 1.3.
+//!
+//! The points of the peer are validated to be on the curve, which keeps a
+//! malicious peer from getting the shared secret on a weak curve (invalid
+//! curve attack). The curves have the cofactor 1, so every such point other
+//! than the point at infinity is of the prime order.
+
+use alloc::vec::Vec;
+use core::fmt;
+use crate::base16;
+use crate::bigint::BigUint;
+use crate::rng::Rng;
+
+use self::field::{decode_limbs, encode_limbs, sub_limbs, Field, Limbs};
+
+mod curves;
+mod field;
+mod point;
+
+/// Errors of the elliptic curves.
+#[derive(Debug, PartialEq)]
+pub enum EccError {
+    /// The encoding of the point is malformed, or the point is not on the
+    /// curve.
+    InvalidPoint,
+    /// The private key is not in `[1, n - 1]`.
+    InvalidPrivateKey,
+    /// The keys are of different curves.
+    CurveMismatch,
+    /// The shared secret is the point at infinity.
+    InvalidSharedSecret,
+}
+
+impl fmt::Display for EccError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EccError::InvalidPoint => write!(f, "invalid EC point"),
+            EccError::InvalidPrivateKey => write!(f, "invalid EC private key"),
+            EccError::CurveMismatch => write!(f, "mismatched EC curves"),
+            EccError::InvalidSharedSecret => write!(f, "invalid ECDH shared secret"),
+        }
+    }
+}
+
+impl core::error::Error for EccError {}
+
+/// Short Weierstrass curve `y ^ 2 = x ^ 3 - 3 * x + b` of the prime order
+/// `n`.
+#[derive(PartialEq)]
+pub struct Curve {
+    name: &'static str,
+    field: Field,
+    /// Order of the base point.
+    order: Limbs,
+    b: Limbs,
+    gx: Limbs,
+    gy: Limbs,
+}
+
+/// NIST P-256, `secp256r1`.
+pub static P256: Curve = curves::P256;
+/// NIST P-384, `secp384r1`.
+pub static P384: Curve = curves::P384;
+
+impl Curve {
+    /// Get the name of FIPS 186-4, e.g. "P-256".
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the length of the coordinates and the private keys in bytes,
+    /// which is also of the shared secret.
+    pub fn size(&self) -> usize {
+        self.field.size()
+    }
+}
+
+impl fmt::Debug for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Curve").field(&self.name).finish()
+    }
+}
+
+/// Point of the curve other than the point at infinity, e.g. the public key
+/// of ECDH.
+#[derive(Clone, PartialEq)]
+pub struct PublicKey {
+    curve: &'static Curve,
+    /// Affine coordinates in Montgomery form.
+    x: Limbs,
+    y: Limbs,
+}
+
+impl PublicKey {
+    /// Decode the uncompressed or the compressed point of SEC 1 2.3.4, and
+    /// check that it is on the curve (SP 800-56A 5.6.2.3.3).
+    ///
+    /// The point at infinity and the hybrid form are rejected.
+    pub fn from_sec1(curve: &'static Curve, bytes: &[u8]) -> Result<PublicKey, EccError> {
+        let field = &curve.field;
+        let size = field.size();
+        let (x, y) = match bytes.split_first() {
+            Some((0x04, coordinates)) if coordinates.len() == 2 * size => {
+                let (x, y) = coordinates.split_at(size);
+                let x = field.decode(x).ok_or(EccError::InvalidPoint)?;
+                let y = field.decode(y).ok_or(EccError::InvalidPoint)?;
+                if !curve.is_on_curve(&x, &y) {
+                    return Err(EccError::InvalidPoint);
+                }
+                (x, y)
+            }
+            Some((tag @ (0x02 | 0x03), x)) if x.len() == size => {
+                let x = field.decode(x).ok_or(EccError::InvalidPoint)?;
+                let y = field.sqrt(&curve.curve_rhs(&x)).ok_or(EccError::InvalidPoint)?;
+                // y is never 0 on the curves of the odd order
+                if field.is_odd(&y) == (*tag == 0x03) {
+                    (x, y)
+                } else {
+                    (x, field.neg(&y))
+                }
+            }
+            _ => return Err(EccError::InvalidPoint),
+        };
+        Ok(PublicKey { curve, x, y })
+    }
+
+    pub fn curve(&self) -> &'static Curve {
+        self.curve
+    }
+
+    /// Encode as the uncompressed point `04 || x || y`, which TLS requires
+    /// (RFC 8422 5.1.2, RFC 8446 4.2.8.2).
+    pub fn to_sec1_uncompressed(&self) -> Vec<u8> {
+        let field = &self.curve.field;
+        let mut encoded = Vec::with_capacity(1 + 2 * field.size());
+        encoded.push(0x04);
+        encoded.extend_from_slice(&field.encode(&self.x));
+        encoded.extend_from_slice(&field.encode(&self.y));
+        encoded
+    }
+
+    /// Encode as the compressed point `02 || x` of even `y`, or `03 || x`
+    /// of odd `y`.
+    pub fn to_sec1_compressed(&self) -> Vec<u8> {
+        let field = &self.curve.field;
+        let mut encoded = Vec::with_capacity(1 + field.size());
+        encoded.push(0x02 | field.is_odd(&self.y) as u8);
+        encoded.extend_from_slice(&field.encode(&self.x));
+        encoded
+    }
+}
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PublicKey")
+            .field("curve", &self.curve)
+            .field("point", &base16::encode_lower(self.to_sec1_uncompressed()))
+            .finish()
+    }
+}
+
+/// Private key of ECDH.
+#[derive(Clone)]
+pub struct EcdhPrivateKey {
+    public_key: PublicKey,
+    d: Limbs,
+}
+
+impl EcdhPrivateKey {
+    /// Generate the private key in `[1, n - 1]` (FIPS 186-4 B.4.2).
+    pub fn generate<R: Rng + ?Sized>(curve: &'static Curve, rng: &mut R) -> EcdhPrivateKey {
+        let size = curve.size();
+        let order = BigUint::from_bytes_be(&encode_limbs(&curve.order, curve.field.limbs));
+        let d = &BigUint::random_below(&(&order - &BigUint::one()), rng) + &BigUint::one();
+        EcdhPrivateKey::from_bytes(curve, &d.to_bytes_be_padded(size).unwrap()).unwrap()
+    }
+
+    /// Create with the big-endian private key of the length of the curve.
+    pub fn from_bytes(curve: &'static Curve, bytes: &[u8]) -> Result<EcdhPrivateKey, EccError> {
+        if bytes.len() != curve.size() {
+            return Err(EccError::InvalidPrivateKey);
+        }
+        let d = decode_limbs(bytes);
+        // 0 < d < n without branching on the key but for the result
+        let (_, borrow) = sub_limbs(&d, &curve.order, curve.field.limbs);
+        let nonzero = d.iter().fold(0, |acc, limb| { acc | limb }) != 0;
+        if borrow == 0 || !nonzero {
+            return Err(EccError::InvalidPrivateKey);
+        }
+
+        let (x, y) = curve.to_affine(&curve.mul(&curve.generator(), &d)).unwrap();
+        Ok(EcdhPrivateKey { public_key: PublicKey { curve, x, y }, d })
+    }
+
+    pub fn curve(&self) -> &'static Curve {
+        self.public_key.curve
+    }
+
+    /// Get the public key `d * G`.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Get the shared secret, which is the x-coordinate of `d * Q` of the
+    /// length of the curve (SP 800-56A 5.7.1.2).
+    pub fn compute_shared_secret(&self, peer_public_key: &PublicKey) -> Result<Vec<u8>, EccError> {
+        let curve = self.curve();
+        if peer_public_key.curve != curve {
+            return Err(EccError::CurveMismatch);
+        }
+        let q = curve.to_projective(&peer_public_key.x, &peer_public_key.y);
+        let (x, _) = curve.to_affine(&curve.mul(&q, &self.d)).ok_or(EccError::InvalidSharedSecret)?;
+        Ok(curve.field.encode(&x))
+    }
+}
+
+impl fmt::Debug for EcdhPrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EcdhPrivateKey")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::ChaChaRng;
+    use crate::test_util::{hex, upstream_vectors};
+
+    /// Get the curve of the section, or `None` for the other curves of the
+    /// NIST files.
+    fn curve_of(section: &str) -> Option<&'static Curve> {
+        match section {
+            "[P-256]" => Some(&P256),
+            "[P-384]" => Some(&P384),
+            _ => None,
+        }
+    }
+
+    /// Decode the integer of the response file in the size of the curve.
+    /// NIST omits the leading zeros, and the out of range values are longer.
+    fn integer(curve: &Curve, value: &str) -> Vec<u8> {
+        let digits = (2 * curve.size()).max(value.len() + value.len() % 2);
+        hex(&format!("{:0>1$}", value, digits))
+    }
+
+    /// Vector of the ECC CDH primitive with the points encoded uncompressed.
+    struct CdhVector {
+        curve: &'static Curve,
+        peer: Vec<u8>,
+        d: Vec<u8>,
+        public_key: Vec<u8>,
+        secret: Vec<u8>,
+    }
+
+    fn parse_cdh(rsp: &str) -> Vec<CdhVector> {
+        let mut vectors = Vec::new();
+        let mut curve = None;
+        let (mut peer, mut d, mut public_key) = (vec![4], Vec::new(), vec![4]);
+        for line in rsp.lines() {
+            if line.starts_with('[') {
+                curve = curve_of(line);
+            }
+            let curve = match curve {
+                Some(curve) => curve,
+                None => continue,
+            };
+            if let Some(value) = line.strip_prefix("QCAVSx = ") {
+                peer = [vec![4], integer(curve, value)].concat();
+            } else if let Some(value) = line.strip_prefix("QCAVSy = ") {
+                peer.extend(integer(curve, value));
+            } else if let Some(value) = line.strip_prefix("dIUT = ") {
+                d = integer(curve, value);
+            } else if let Some(value) = line.strip_prefix("QIUTx = ") {
+                public_key = [vec![4], integer(curve, value)].concat();
+            } else if let Some(value) = line.strip_prefix("QIUTy = ") {
+                public_key.extend(integer(curve, value));
+            } else if let Some(value) = line.strip_prefix("ZIUT = ") {
+                let (peer, d, public_key) = (peer.clone(), d.clone(), public_key.clone());
+                vectors.push(CdhVector { curve, peer, d, public_key, secret: integer(curve, value) });
+            }
+        }
+        vectors
+    }
+
+    fn check_cdh(rsp: &str) {
+        let vectors = parse_cdh(rsp);
+        // 25 vectors of each curve
+        for curve in [&P256, &P384] {
+            assert_eq!(vectors.iter().filter(|vector| { vector.curve == curve }).count(), 25, "{}", curve.name);
+        }
+        for vector in vectors {
+            let dump = base16::encode_lower(&vector.d);
+            let key = EcdhPrivateKey::from_bytes(vector.curve, &vector.d).unwrap();
+            assert_eq!(key.public_key().to_sec1_uncompressed(), vector.public_key, "dIUT = {}", dump);
+            let peer = PublicKey::from_sec1(vector.curve, &vector.peer).unwrap();
+            assert_eq!(key.compute_shared_secret(&peer).unwrap(), vector.secret, "dIUT = {}", dump);
+        }
+    }
+
+    /// Check the public key validation, and get the numbers of the valid and
+    /// the invalid points of each curve.
+    fn check_pkv(rsp: &str) -> [(usize, usize); 2] {
+        let mut curve = None;
+        let mut point = Vec::new();
+        let mut counts = [(0, 0); 2];
+        for line in rsp.lines() {
+            if line.starts_with('[') {
+                curve = curve_of(line);
+            }
+            let curve = match curve {
+                Some(curve) => curve,
+                None => continue,
+            };
+            if let Some(value) = line.strip_prefix("Qx = ") {
+                point = [vec![4], integer(curve, value)].concat();
+            } else if let Some(value) = line.strip_prefix("Qy = ") {
+                point.extend(integer(curve, value));
+            } else if let Some(value) = line.strip_prefix("Result = ") {
+                let count = &mut counts[if curve == &P256 { 0 } else { 1 }];
+                let result = PublicKey::from_sec1(curve, &point);
+                if value.starts_with('P') {
+                    assert_eq!(result.unwrap().to_sec1_uncompressed(), point);
+                    count.0 += 1;
+                } else {
+                    assert_eq!(result, Err(EccError::InvalidPoint), "{}", value);
+                    count.1 += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn test_generated_cdh() {
+        check_cdh(include_str!("../../res/generated/ecc/ecc_cdh.txt"));
+    }
+
+    #[test]
+    #[ignore = "needs the NIST vectors of res/fetch-vectors.sh"]
+    fn test_cavp_cdh() {
+        check_cdh(&upstream_vectors("cavp/ecc/KAS_ECC_CDH_PrimitiveTest.txt"));
+    }
+
+    #[test]
+    fn test_generated_public_key_validation() {
+        let counts = check_pkv(include_str!("../../res/generated/ecc/pkv.rsp"));
+        assert_eq!(counts.iter().map(|(valid, invalid)| { valid + invalid }).sum::<usize>(), 24);
+    }
+
+    #[test]
+    #[ignore = "needs the NIST vectors of res/fetch-vectors.sh"]
+    fn test_cavp_public_key_validation() {
+        let counts = check_pkv(&upstream_vectors("cavp/ecc/PKV.rsp"));
+        for (valid, invalid) in counts {
+            assert!(valid > 0 && invalid > 0, "{} valid and {} invalid points", valid, invalid);
+        }
+    }
+
+    #[test]
+    fn test_compression() {
+        let mut rng = ChaChaRng::from_seed(&[11; 32]);
+        for curve in [&P256, &P384] {
+            for _ in 0..4 {
+                let public_key = EcdhPrivateKey::generate(curve, &mut rng).public_key().clone();
+                let compressed = public_key.to_sec1_compressed();
+                assert_eq!(compressed.len(), 1 + curve.size());
+                assert_eq!(compressed[1..], public_key.to_sec1_uncompressed()[1..1 + curve.size()]);
+                assert_eq!(PublicKey::from_sec1(curve, &compressed), Ok(public_key.clone()));
+
+                // the other y of the x
+                let mut negated = compressed.clone();
+                negated[0] ^= 1;
+                let negated = PublicKey::from_sec1(curve, &negated).unwrap();
+                assert_eq!(negated.x, public_key.x);
+                assert_eq!(negated.y, curve.field.neg(&public_key.y));
+            }
+        }
+
+        // the base point of P-256 has the odd y
+        let g = hex("036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296");
+        let key = EcdhPrivateKey::from_bytes(&P256, &[&[0; 31][..], &[1]].concat()).unwrap();
+        assert_eq!(key.public_key().to_sec1_compressed(), g);
+    }
+
+    #[test]
+    fn test_invalid_encodings() {
+        let key = EcdhPrivateKey::from_bytes(&P256, &[7; 32]).unwrap();
+        let uncompressed = key.public_key().to_sec1_uncompressed();
+        let compressed = key.public_key().to_sec1_compressed();
+
+        let mut hybrid = uncompressed.clone();
+        hybrid[0] = 0x06 | (compressed[0] & 1);
+        let mut off_curve = uncompressed.clone();
+        off_curve[64] ^= 1;
+        let invalid = [
+            Vec::new(),
+            vec![0x00],
+            hybrid,
+            off_curve,
+            uncompressed[..64].to_vec(),
+            [&uncompressed[..], &[0]].concat(),
+            compressed[..32].to_vec(),
+            [&compressed[..], &[0]].concat(),
+            // x ^ 3 - 3 * x + b is a non-residue for x = 1 on P-256
+            [&[0x02][..], &[0; 31], &[1]].concat(),
+            // x = p
+            hex("02ffffffff00000001000000000000000000000000ffffffffffffffffffffffff"),
+        ];
+        for bytes in invalid.iter() {
+            assert_eq!(PublicKey::from_sec1(&P256, bytes), Err(EccError::InvalidPoint), "{:?}", bytes);
+        }
+        // the points of P-256 are not of P-384
+        assert_eq!(PublicKey::from_sec1(&P384, &uncompressed), Err(EccError::InvalidPoint));
+    }
+
+    #[test]
+    fn test_private_key_range() {
+        for curve in [&P256, &P384] {
+            let size = curve.size();
+            let order = encode_limbs(&curve.order, curve.field.limbs);
+            let mut order1 = order.clone();
+            order1[size - 1] -= 1;
+            assert_eq!(EcdhPrivateKey::from_bytes(curve, &vec![0; size]).unwrap_err(), EccError::InvalidPrivateKey);
+            assert_eq!(EcdhPrivateKey::from_bytes(curve, &order).unwrap_err(), EccError::InvalidPrivateKey);
+            assert_eq!(EcdhPrivateKey::from_bytes(curve, &vec![0xff; size]).unwrap_err(), EccError::InvalidPrivateKey);
+            assert_eq!(EcdhPrivateKey::from_bytes(curve, &order1[1..]).unwrap_err(), EccError::InvalidPrivateKey);
+
+            // n - 1 gives -G
+            let key = EcdhPrivateKey::from_bytes(curve, &order1).unwrap();
+            assert_eq!(key.public_key().x, curve.gx);
+            assert_eq!(key.public_key().y, curve.field.neg(&curve.gy));
+        }
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut rng = ChaChaRng::from_seed(&[12; 32]);
+        for curve in [&P256, &P384] {
+            let a = EcdhPrivateKey::generate(curve, &mut rng);
+            let b = EcdhPrivateKey::generate(curve, &mut rng);
+            assert_ne!(a.public_key(), b.public_key());
+            let secret = a.compute_shared_secret(b.public_key()).unwrap();
+            assert_eq!(secret.len(), curve.size());
+            assert_eq!(b.compute_shared_secret(a.public_key()).unwrap(), secret);
+        }
+        let a = EcdhPrivateKey::generate(&P256, &mut rng);
+        let b = EcdhPrivateKey::generate(&P384, &mut rng);
+        assert_eq!(a.compute_shared_secret(b.public_key()), Err(EccError::CurveMismatch));
+    }
+}
@@ -0,0 +1,86 @@
+//! Parameters of the NIST curves of FIPS 186-4 D.1.2, which are
+//! `secp256r1` and `secp384r1` of SEC 2, with `a = -3`.
+//!
+//! `b` and the base point are in Montgomery form.
+
+use super::Curve;
+use super::field::Field;
+
+pub(super) const P256: Curve = Curve {
+    name: "P-256",
+    field: Field {
+        limbs: 4,
+        modulus: [0xffffffffffffffff, 0x00000000ffffffff, 0, 0xffffffff00000001, 0, 0],
+        m_inv: 0x0000000000000001,
+        r2: [0x0000000000000003, 0xfffffffbffffffff, 0xfffffffffffffffe, 0x00000004fffffffd, 0, 0],
+    },
+    order: [0xf3b9cac2fc632551, 0xbce6faada7179e84, 0xffffffffffffffff, 0xffffffff00000000, 0, 0],
+    b: [0xd89cdf6229c4bddf, 0xacf005cd78843090, 0xe5a220abf7212ed6, 0xdc30061d04874834, 0, 0],
+    gx: [0x79e730d418a9143c, 0x75ba95fc5fedb601, 0x79fb732b77622510, 0x18905f76a53755c6, 0, 0],
+    gy: [0xddf25357ce95560a, 0x8b4ab8e4ba19e45c, 0xd2e88688dd21f325, 0x8571ff1825885d85, 0, 0],
+};
+
+pub(super) const P384: Curve = Curve {
+    name: "P-384",
+    field: Field {
+        limbs: 6,
+        modulus: [
+            0x00000000ffffffff, 0xffffffff00000000, 0xfffffffffffffffe,
+            0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff,
+        ],
+        m_inv: 0x0000000100000001,
+        r2: [
+            0xfffffffe00000001, 0x0000000200000000, 0xfffffffe00000000,
+            0x0000000200000000, 0x0000000000000001, 0,
+        ],
+    },
+    order: [
+        0xecec196accc52973, 0x581a0db248b0a77a, 0xc7634d81f4372ddf,
+        0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff,
+    ],
+    b: [
+        0x081188719d412dcc, 0xf729add87a4c32ec, 0x77f2209b1920022e,
+        0xe3374bee94938ae2, 0xb62b21f41f022094, 0xcd08114b604fbff9,
+    ],
+    gx: [
+        0x3dd0756649c0b528, 0x20e378e2a0d6ce38, 0x879c3afc541b4d6e,
+        0x6454868459a30eff, 0x812ff723614ede2b, 0x4d3aadc2299e1513,
+    ],
+    gy: [
+        0x23043dad4b03a4fe, 0xa1bfa8bf7bb4a9ac, 0x8bade7562e83b050,
+        0xc6c3521968f4ffd9, 0xdd8002263969a840, 0x2b78abc25a15c5e9,
+    ],
+};
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::hex;
+    use crate::pk::ecc::{P256, P384};
+
+    #[test]
+    fn test_parameters() {
+        // the canonical values of FIPS 186-4 D.1.2
+        let curves = [
+            (&P256, "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b", concat!(
+                "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+                "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+            )),
+            (&P384, concat!(
+                "b3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875a",
+                "c656398d8a2ed19d2a85c8edd3ec2aef",
+            ), concat!(
+                "aa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a38",
+                "5502f25dbf55296c3a545e3872760ab73617de4a96262c6f5d9e98bf9292dc29",
+                "f8f41dbd289a147ce9da3113b5f0b8c00a60b1ce1d7e819d7a431d7c90ea0e5f",
+            )),
+        ];
+        for (curve, b, g) in curves {
+            let g = hex(g);
+            let (gx, gy) = g.split_at(curve.size());
+            assert_eq!(curve.field.encode(&curve.b), hex(b));
+            assert_eq!(curve.field.encode(&curve.gx), gx);
+            assert_eq!(curve.field.encode(&curve.gy), gy);
+            assert!(curve.is_on_curve(&curve.gx, &curve.gy));
+        }
+    }
+}
@@ -0,0 +1,294 @@
+//! Arithmetic modulo the primes of the curves in Montgomery form.
+//!
+//! Elements are held as fixed arrays of the limbs of the largest prime, so
+//! that the points don't allocate, and the limbs beyond the prime are zero.
+//! The operations don't branch on the values or index by them, but for the
+//! exponentiation by public exponents.
+
+use alloc::vec::Vec;
+
+/// Number of the 64-bit limbs of the largest prime, which is of P-384.
+pub(super) const MAX_LIMBS: usize = 6;
+
+/// Limbs of a value from the least significant.
+pub(super) type Limbs = [u64; MAX_LIMBS];
+
+/// Prime field of the coordinates, whose elements are in Montgomery form
+/// `x * R mod p` with `R = 2 ^ (64 * limbs)`.
+#[derive(Debug, PartialEq)]
+pub(super) struct Field {
+    /// Number of the limbs of the prime.
+    pub(super) limbs: usize,
+    pub(super) modulus: Limbs,
+    /// `-p ^ -1 mod 2 ^ 64`
+    pub(super) m_inv: u64,
+    /// `R ^ 2 mod p`
+    pub(super) r2: Limbs,
+}
+
+impl Field {
+    /// Get the length of the encoded elements in bytes.
+    pub(super) fn size(&self) -> usize {
+        self.limbs * 8
+    }
+
+    pub(super) fn zero(&self) -> Limbs {
+        [0; MAX_LIMBS]
+    }
+
+    pub(super) fn one(&self) -> Limbs {
+        self.mul(&small(1), &self.r2)
+    }
+
+    /// Get `a * b * R ^ -1 mod p` by the CIOS method.
+    pub(super) fn mul(&self, a: &Limbs, b: &Limbs) -> Limbs {
+        let n = self.limbs;
+        let m = &self.modulus;
+        let mut t = [0; MAX_LIMBS + 2];
+        for b_limb in b[..n].iter() {
+            // t += a * b[i]
+            let mut carry: u128 = 0;
+            for j in 0..n {
+                let sum = t[j] as u128 + a[j] as u128 * *b_limb as u128 + carry;
+                t[j] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[n] as u128 + carry;
+            t[n] = sum as u64;
+            t[n + 1] = (sum >> 64) as u64;
+
+            // t = (t + u * p) / 2 ^ 64, where u makes the lowest limb 0
+            let u = t[0].wrapping_mul(self.m_inv);
+            let sum = t[0] as u128 + u as u128 * m[0] as u128;
+            let mut carry = sum >> 64;
+            for j in 1..n {
+                let sum = t[j] as u128 + u as u128 * m[j] as u128 + carry;
+                t[j - 1] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[n] as u128 + carry;
+            t[n - 1] = sum as u64;
+            t[n] = t[n + 1] + (sum >> 64) as u64;
+        }
+
+        let mut result = [0; MAX_LIMBS];
+        result[..n].copy_from_slice(&t[..n]);
+        self.reduce_once(&result, t[n])
+    }
+
+    pub(super) fn square(&self, a: &Limbs) -> Limbs {
+        self.mul(a, a)
+    }
+
+    pub(super) fn add(&self, a: &Limbs, b: &Limbs) -> Limbs {
+        let (sum, carry) = add_limbs(a, b, self.limbs);
+        self.reduce_once(&sum, carry)
+    }
+
+    pub(super) fn sub(&self, a: &Limbs, b: &Limbs) -> Limbs {
+        let (difference, borrow) = sub_limbs(a, b, self.limbs);
+        let (corrected, _) = add_limbs(&difference, &self.modulus, self.limbs);
+        select(borrow.wrapping_neg(), &corrected, &difference)
+    }
+
+    pub(super) fn neg(&self, a: &Limbs) -> Limbs {
+        self.sub(&self.zero(), a)
+    }
+
+    /// Subtract the prime once if `a + carry * R >= p`, which holds for the
+    /// values less than `2 * p`.
+    fn reduce_once(&self, a: &Limbs, carry: u64) -> Limbs {
+        let (difference, borrow) = sub_limbs(a, &self.modulus, self.limbs);
+        select((carry | (borrow ^ 1)).wrapping_neg(), &difference, a)
+    }
+
+    /// Calculate `a ^ exponent` by the square-and-multiply, which branches
+    /// on the bits of the exponent, so it must be public.
+    fn pow(&self, a: &Limbs, exponent: &Limbs) -> Limbs {
+        let mut result = self.one();
+        for index in (0..self.limbs * 64).rev() {
+            result = self.square(&result);
+            if (exponent[index / 64] >> (index % 64)) & 1 == 1 {
+                result = self.mul(&result, a);
+            }
+        }
+        result
+    }
+
+    /// Get `a ^ -1` by Fermat's little theorem as `a ^ (p - 2)`, which is 0
+    /// for 0.
+    pub(super) fn invert(&self, a: &Limbs) -> Limbs {
+        let (exponent, _) = sub_limbs(&self.modulus, &small(2), self.limbs);
+        self.pow(a, &exponent)
+    }
+
+    /// Get a square root as `a ^ ((p + 1) / 4)`, which the primes of
+    /// `p = 3 mod 4` allow, or `None` for a non-residue.
+    pub(super) fn sqrt(&self, a: &Limbs) -> Option<Limbs> {
+        let (mut exponent, _) = add_limbs(&self.modulus, &small(1), self.limbs);
+        for index in 0..self.limbs {
+            let next = exponent.get(index + 1).copied().unwrap_or(0);
+            exponent[index] = (exponent[index] >> 2) | (next << 62);
+        }
+        let root = self.pow(a, &exponent);
+        if self.square(&root) == *a {
+            Some(root)
+        } else {
+            None
+        }
+    }
+
+    /// Check the parity of the value out of Montgomery form, which the
+    /// compressed points encode.
+    pub(super) fn is_odd(&self, a: &Limbs) -> bool {
+        self.mul(a, &small(1))[0] & 1 == 1
+    }
+
+    /// Decode the big-endian bytes of the length of the prime into
+    /// Montgomery form, or `None` if they are not less than the prime.
+    pub(super) fn decode(&self, bytes: &[u8]) -> Option<Limbs> {
+        if bytes.len() != self.size() {
+            return None;
+        }
+        let value = decode_limbs(bytes);
+        let (_, borrow) = sub_limbs(&value, &self.modulus, self.limbs);
+        if borrow == 0 {
+            return None;
+        }
+        Some(self.mul(&value, &self.r2))
+    }
+
+    /// Encode out of Montgomery form into big-endian bytes of the length of
+    /// the prime.
+    pub(super) fn encode(&self, a: &Limbs) -> Vec<u8> {
+        encode_limbs(&self.mul(a, &small(1)), self.limbs)
+    }
+}
+
+/// Get the limbs of a small value.
+fn small(value: u64) -> Limbs {
+    let mut limbs = [0; MAX_LIMBS];
+    limbs[0] = value;
+    limbs
+}
+
+/// Get `a` if the mask is all 1 bits, or `b` if it is 0, without branching.
+pub(super) fn select(mask: u64, a: &Limbs, b: &Limbs) -> Limbs {
+    let mut result = [0; MAX_LIMBS];
+    for (limb, (a_limb, b_limb)) in result.iter_mut().zip(a.iter().zip(b.iter())) {
+        *limb = (a_limb & mask) | (b_limb & !mask);
+    }
+    result
+}
+
+/// Get `a + b` of the lowest `n` limbs and the carry.
+pub(super) fn add_limbs(a: &Limbs, b: &Limbs, n: usize) -> (Limbs, u64) {
+    let mut sum = [0; MAX_LIMBS];
+    let mut carry = 0;
+    for j in 0..n {
+        let s = a[j] as u128 + b[j] as u128 + carry as u128;
+        sum[j] = s as u64;
+        carry = (s >> 64) as u64;
+    }
+    (sum, carry)
+}
+
+/// Get `a - b` of the lowest `n` limbs and the borrow, which is 1 if
+/// `a < b`.
+pub(super) fn sub_limbs(a: &Limbs, b: &Limbs, n: usize) -> (Limbs, u64) {
+    let mut difference = [0; MAX_LIMBS];
+    let mut borrow = 0;
+    for j in 0..n {
+        let (d, borrow1) = a[j].overflowing_sub(b[j]);
+        let (d, borrow2) = d.overflowing_sub(borrow);
+        difference[j] = d;
+        borrow = (borrow1 | borrow2) as u64;
+    }
+    (difference, borrow)
+}
+
+/// Decode big-endian bytes of at most `8 * MAX_LIMBS` bytes.
+pub(super) fn decode_limbs(bytes: &[u8]) -> Limbs {
+    let mut limbs = [0; MAX_LIMBS];
+    for (index, byte) in bytes.iter().rev().enumerate() {
+        limbs[index / 8] |= (*byte as u64) << (index % 8 * 8);
+    }
+    limbs
+}
+
+/// Encode the lowest `n` limbs into big-endian bytes.
+pub(super) fn encode_limbs(limbs: &Limbs, n: usize) -> Vec<u8> {
+    limbs[..n].iter().rev().flat_map(|limb| { limb.to_be_bytes() }).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bigint::BigUint;
+    use crate::pk::ecc::{P256, P384};
+
+    fn to_biguint(limbs: &Limbs) -> BigUint {
+        BigUint::from_bytes_be(&encode_limbs(limbs, MAX_LIMBS))
+    }
+
+    #[test]
+    fn test_parameters() {
+        for field in [&P256.field, &P384.field] {
+            let p = to_biguint(&field.modulus);
+            assert_eq!(p.bits(), field.limbs * 64);
+            assert_eq!(field.m_inv.wrapping_mul(field.modulus[0]), u64::MAX);
+            let r = BigUint::one() << (field.limbs * 64);
+            assert_eq!(to_biguint(&field.r2), &(&r * &r) % &p);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        for field in [&P256.field, &P384.field] {
+            let p = to_biguint(&field.modulus);
+            let a_bytes = vec![0xa5; field.size()];
+            let mut b_bytes = field.encode(&field.neg(&field.one()));
+            b_bytes[field.size() - 1] -= 7;
+            let a = field.decode(&a_bytes).unwrap();
+            let b = field.decode(&b_bytes).unwrap();
+            let (x, y) = (BigUint::from_bytes_be(&a_bytes), BigUint::from_bytes_be(&b_bytes));
+            let value = |limbs: &Limbs| { BigUint::from_bytes_be(&field.encode(limbs)) };
+
+            assert_eq!(value(&field.add(&a, &b)), &(&x + &y) % &p);
+            assert_eq!(value(&field.sub(&a, &b)), &(&(&x + &p) - &y) % &p);
+            assert_eq!(value(&field.sub(&b, &a)), &(&y - &x) % &p);
+            assert_eq!(value(&field.mul(&a, &b)), &(&x * &y) % &p);
+            assert_eq!(field.mul(&a, &field.invert(&a)), field.one());
+            assert_eq!(field.invert(&field.zero()), field.zero());
+            assert_eq!(field.encode(&field.neg(&field.zero())), vec![0; field.size()]);
+        }
+    }
+
+    #[test]
+    fn test_sqrt() {
+        for field in [&P256.field, &P384.field] {
+            let a = field.decode(&vec![0x5a; field.size()]).unwrap();
+            let square = field.square(&a);
+            let root = field.sqrt(&square).unwrap();
+            assert!(root == a || root == field.neg(&a));
+            // -1 is a non-residue as p = 3 mod 4
+            assert_eq!(field.sqrt(&field.neg(&square)), None);
+        }
+    }
+
+    #[test]
+    fn test_encoding() {
+        let field = &P256.field;
+        let p = encode_limbs(&field.modulus, field.limbs);
+        assert_eq!(field.decode(&p), None);
+        assert_eq!(field.decode(&[0xff; 32]), None);
+        assert_eq!(field.decode(&[1; 31]), None);
+
+        let mut p1 = p.clone();
+        p1[31] -= 1;
+        assert_eq!(field.encode(&field.decode(&p1).unwrap()), p1);
+        assert_eq!(decode_limbs(&p), field.modulus);
+        assert!(field.is_odd(&field.one()));
+        assert!(!field.is_odd(&field.decode(&p1).unwrap()));
+    }
+}
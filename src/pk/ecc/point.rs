@@ -0,0 +1,235 @@
+//! Arithmetic of the points in projective coordinates `(X : Y : Z)`, which
+//! are `(X / Z, Y / Z)` in affine coordinates, by the complete formulas of
+//! Renes, Costello and Batina for `a = -3` (ePrint 2015/1060, algorithms 4
+//! and 6).
+//!
+//! The formulas have no exceptional cases, e.g. the point at infinity or
+//! the addition of a point to itself, so the scalar multiplication never
+//! branches on the scalar.
+
+use super::Curve;
+use super::field::{select, Limbs};
+use crate::ct::eq_mask_u64;
+
+/// Window size of the scalar multiplication in bits.
+const WINDOW_BITS: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct ProjectivePoint {
+    x: Limbs,
+    y: Limbs,
+    z: Limbs,
+}
+
+impl Curve {
+    /// Get the point at infinity `(0 : 1 : 0)`.
+    pub(super) fn identity(&self) -> ProjectivePoint {
+        ProjectivePoint { x: self.field.zero(), y: self.field.one(), z: self.field.zero() }
+    }
+
+    pub(super) fn generator(&self) -> ProjectivePoint {
+        self.to_projective(&self.gx, &self.gy)
+    }
+
+    pub(super) fn to_projective(&self, x: &Limbs, y: &Limbs) -> ProjectivePoint {
+        ProjectivePoint { x: *x, y: *y, z: self.field.one() }
+    }
+
+    /// Get the affine coordinates, or `None` for the point at infinity.
+    pub(super) fn to_affine(&self, point: &ProjectivePoint) -> Option<(Limbs, Limbs)> {
+        let f = &self.field;
+        if point.z == f.zero() {
+            return None;
+        }
+        let z_inv = f.invert(&point.z);
+        Some((f.mul(&point.x, &z_inv), f.mul(&point.y, &z_inv)))
+    }
+
+    /// Check `y ^ 2 = x ^ 3 - 3 * x + b`.
+    pub(super) fn is_on_curve(&self, x: &Limbs, y: &Limbs) -> bool {
+        self.field.square(y) == self.curve_rhs(x)
+    }
+
+    /// Get `x ^ 3 - 3 * x + b`.
+    pub(super) fn curve_rhs(&self, x: &Limbs) -> Limbs {
+        let f = &self.field;
+        let x3 = f.mul(&f.square(x), x);
+        let three_x = f.add(&f.add(x, x), x);
+        f.add(&f.sub(&x3, &three_x), &self.b)
+    }
+
+    /// Get `p + q` by algorithm 4.
+    pub(super) fn add(&self, p: &ProjectivePoint, q: &ProjectivePoint) -> ProjectivePoint {
+        let f = &self.field;
+        let b = &self.b;
+        let (x1, y1, z1) = (&p.x, &p.y, &p.z);
+        let (x2, y2, z2) = (&q.x, &q.y, &q.z);
+
+        let mut t0 = f.mul(x1, x2);
+        let mut t1 = f.mul(y1, y2);
+        let mut t2 = f.mul(z1, z2);
+        let mut t3 = f.add(x1, y1);
+        let mut t4 = f.add(x2, y2);
+        t3 = f.mul(&t3, &t4);
+        t4 = f.add(&t0, &t1);
+        t3 = f.sub(&t3, &t4);
+        t4 = f.add(y1, z1);
+        let mut x3 = f.add(y2, z2);
+        t4 = f.mul(&t4, &x3);
+        x3 = f.add(&t1, &t2);
+        t4 = f.sub(&t4, &x3);
+        x3 = f.add(x1, z1);
+        let mut y3 = f.add(x2, z2);
+        x3 = f.mul(&x3, &y3);
+        y3 = f.add(&t0, &t2);
+        y3 = f.sub(&x3, &y3);
+        let mut z3 = f.mul(b, &t2);
+        x3 = f.sub(&y3, &z3);
+        z3 = f.add(&x3, &x3);
+        x3 = f.add(&x3, &z3);
+        z3 = f.sub(&t1, &x3);
+        x3 = f.add(&t1, &x3);
+        y3 = f.mul(b, &y3);
+        t1 = f.add(&t2, &t2);
+        t2 = f.add(&t1, &t2);
+        y3 = f.sub(&y3, &t2);
+        y3 = f.sub(&y3, &t0);
+        t1 = f.add(&y3, &y3);
+        y3 = f.add(&t1, &y3);
+        t1 = f.add(&t0, &t0);
+        t0 = f.add(&t1, &t0);
+        t0 = f.sub(&t0, &t2);
+        t1 = f.mul(&t4, &y3);
+        t2 = f.mul(&t0, &y3);
+        y3 = f.mul(&x3, &z3);
+        y3 = f.add(&y3, &t2);
+        x3 = f.mul(&t3, &x3);
+        x3 = f.sub(&x3, &t1);
+        z3 = f.mul(&t4, &z3);
+        t1 = f.mul(&t3, &t0);
+        z3 = f.add(&z3, &t1);
+        ProjectivePoint { x: x3, y: y3, z: z3 }
+    }
+
+    /// Get `2 * p` by algorithm 6.
+    pub(super) fn double(&self, p: &ProjectivePoint) -> ProjectivePoint {
+        let f = &self.field;
+        let b = &self.b;
+        let (x, y, z) = (&p.x, &p.y, &p.z);
+
+        let mut t0 = f.square(x);
+        let t1 = f.square(y);
+        let mut t2 = f.square(z);
+        let mut t3 = f.mul(x, y);
+        t3 = f.add(&t3, &t3);
+        let mut z3 = f.mul(x, z);
+        z3 = f.add(&z3, &z3);
+        let mut y3 = f.mul(b, &t2);
+        y3 = f.sub(&y3, &z3);
+        let mut x3 = f.add(&y3, &y3);
+        y3 = f.add(&x3, &y3);
+        x3 = f.sub(&t1, &y3);
+        y3 = f.add(&t1, &y3);
+        y3 = f.mul(&x3, &y3);
+        x3 = f.mul(&x3, &t3);
+        t3 = f.add(&t2, &t2);
+        t2 = f.add(&t2, &t3);
+        z3 = f.mul(b, &z3);
+        z3 = f.sub(&z3, &t2);
+        z3 = f.sub(&z3, &t0);
+        t3 = f.add(&z3, &z3);
+        z3 = f.add(&z3, &t3);
+        t3 = f.add(&t0, &t0);
+        t0 = f.add(&t3, &t0);
+        t0 = f.sub(&t0, &t2);
+        t0 = f.mul(&t0, &z3);
+        y3 = f.add(&y3, &t0);
+        t0 = f.mul(y, z);
+        t0 = f.add(&t0, &t0);
+        z3 = f.mul(&t0, &z3);
+        x3 = f.sub(&x3, &z3);
+        z3 = f.mul(&t0, &t1);
+        z3 = f.add(&z3, &z3);
+        z3 = f.add(&z3, &z3);
+        ProjectivePoint { x: x3, y: y3, z: z3 }
+    }
+
+    /// Get `scalar * point` by a fixed window over all the bits of the
+    /// limbs. Every window adds an entry of the table, which is selected
+    /// without a memory access depending on the scalar, and the entry 0 is
+    /// the point at infinity.
+    pub(super) fn mul(&self, point: &ProjectivePoint, scalar: &Limbs) -> ProjectivePoint {
+        let mut table = [self.identity(); 1 << WINDOW_BITS];
+        table[1] = *point;
+        for index in 2..table.len() {
+            table[index] = self.add(&table[index - 1], point);
+        }
+
+        let mut result = self.identity();
+        let windows = self.field.limbs * 64 / WINDOW_BITS;
+        for window in (0..windows).rev() {
+            for _ in 0..WINDOW_BITS {
+                result = self.double(&result);
+            }
+
+            let limb = scalar[window * WINDOW_BITS / 64];
+            let value = (limb >> (window * WINDOW_BITS % 64)) & ((1 << WINDOW_BITS) - 1);
+            let mut entry = self.identity();
+            for (index, candidate) in table.iter().enumerate() {
+                let mask = eq_mask_u64(index as u64, value);
+                entry.x = select(mask, &candidate.x, &entry.x);
+                entry.y = select(mask, &candidate.y, &entry.y);
+                entry.z = select(mask, &candidate.z, &entry.z);
+            }
+            result = self.add(&result, &entry);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pk::ecc::{P256, P384};
+    use crate::pk::ecc::field::MAX_LIMBS;
+
+    fn scalar(value: u64) -> Limbs {
+        let mut limbs = [0; MAX_LIMBS];
+        limbs[0] = value;
+        limbs
+    }
+
+    #[test]
+    fn test_group_law() {
+        for curve in [&P256, &P384] {
+            let g = curve.generator();
+            let identity = curve.identity();
+            let g2 = curve.double(&g);
+            assert_eq!(curve.to_affine(&curve.add(&g, &g)), curve.to_affine(&g2));
+            assert_eq!(curve.to_affine(&curve.add(&g, &identity)), curve.to_affine(&g));
+            assert_eq!(curve.to_affine(&curve.double(&identity)), None);
+
+            let g3 = curve.add(&g2, &g);
+            let (x, y) = curve.to_affine(&g3).unwrap();
+            assert!(curve.is_on_curve(&x, &y));
+            assert_eq!(curve.to_affine(&curve.mul(&g, &scalar(3))), Some((x, y)));
+            assert_eq!(curve.to_affine(&curve.add(&g3, &curve.to_projective(&x, &curve.field.neg(&y)))), None);
+            assert_eq!(curve.to_affine(&curve.mul(&g, &scalar(0))), None);
+            assert_eq!(curve.to_affine(&curve.mul(&g, &scalar(1))), curve.to_affine(&g));
+        }
+    }
+
+    #[test]
+    fn test_order() {
+        for curve in [&P256, &P384] {
+            let g = curve.generator();
+            assert_eq!(curve.to_affine(&curve.mul(&g, &curve.order)), None);
+
+            // (n - 1) * G = -G
+            let mut order1 = curve.order;
+            order1[0] -= 1;
+            let (x, y) = curve.to_affine(&curve.mul(&g, &order1)).unwrap();
+            assert_eq!((x, curve.field.neg(&y)), (curve.gx, curve.gy));
+        }
+    }
+}
@@ -0,0 +1,803 @@
+//! RSA (RFC 8017) for the RSA key exchange and the signatures of TLS.
+//!
+//! Private key operations use the CRT parameters and are blinded with a
+//! random value, so that their timing doesn't depend on the input chosen by
+//! an attacker. PKCS #1 v1.5 decryption doesn't fail on invalid padding but
+//! returns a synthetic message derived from the ciphertext and the key
+//! (implicit rejection of draft-irtf-cfrg-rsa-guidance), which takes away
+//! the Bleichenbacher oracle from the RSA key exchange.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use crate::bigint::{self, BigUint};
+use crate::ct::{self, eq_mask, lt_mask};
+use crate::hash::{Digest, Sha256};
+use crate::mac::Hmac;
+use crate::rng::Rng;
+
+#[cfg(test)]
+mod wycheproof_test;
+
+/// Minimum size of the modulus in bits.
+pub const MIN_MODULUS_BITS: usize = 1024;
+/// Maximum size of the modulus in bits, which bounds the time spent on keys
+/// sent by the peer.
+pub const MAX_MODULUS_BITS: usize = 16384;
+
+/// Minimum length of the padding string of PKCS #1 v1.5.
+const MIN_PADDING_LEN: usize = 8;
+/// Number of the candidate lengths of the synthetic message, which makes
+/// the chance that none is short enough negligible.
+const SYNTHETIC_LENGTH_CANDIDATES: usize = 128;
+
+/// Errors of RSA.
+#[derive(Debug, PartialEq)]
+pub enum RsaError {
+    /// The key parameters are inconsistent or of an unsupported size.
+    InvalidKey,
+    /// The message of the length is too long for the modulus.
+    MessageTooLong(usize),
+    /// The ciphertext or the signature is not of the length of the modulus.
+    InvalidLength(usize),
+    /// The hash is not of the length of the hash function.
+    InvalidDigestLength(usize),
+    /// The salt of the length doesn't fit in the modulus.
+    InvalidSaltLength(usize),
+    /// The ciphertext can't be decrypted.
+    DecryptionFailed,
+    /// The signature doesn't match the hash.
+    InvalidSignature,
+    /// The private key operation gave an inconsistent result, e.g. by a
+    /// hardware fault, and it is withheld not to leak the key.
+    FaultDetected,
+}
+
+impl fmt::Display for RsaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RsaError::InvalidKey => write!(f, "invalid RSA key"),
+            RsaError::MessageTooLong(len) => write!(f, "message too long: {} bytes", len),
+            RsaError::InvalidLength(len) => write!(f, "invalid RSA input length {}", len),
+            RsaError::InvalidDigestLength(len) => write!(f, "invalid digest length {}", len),
+            RsaError::InvalidSaltLength(len) => write!(f, "invalid salt length {}", len),
+            RsaError::DecryptionFailed => write!(f, "RSA decryption failed"),
+            RsaError::InvalidSignature => write!(f, "invalid RSA signature"),
+            RsaError::FaultDetected => write!(f, "fault detected in RSA private key operation"),
+        }
+    }
+}
+
+impl core::error::Error for RsaError {}
+
+/// Hash function of PKCS #1 v1.5 signatures, which determines the
+/// `DigestInfo` before the hash.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HashAlgorithm {
+    /// MD5 and SHA-1 concatenated without `DigestInfo`, of TLS 1.0 and 1.1.
+    Md5Sha1,
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    /// Get the length of the hash in bytes.
+    pub fn output_size(self) -> usize {
+        match self {
+            HashAlgorithm::Md5Sha1 => 36,
+            HashAlgorithm::Sha1 => 20,
+            HashAlgorithm::Sha224 => 28,
+            HashAlgorithm::Sha256 => 32,
+            HashAlgorithm::Sha384 => 48,
+            HashAlgorithm::Sha512 => 64,
+        }
+    }
+
+    /// Get the DER encoding of `DigestInfo` up to the hash (RFC 8017 9.2).
+    fn digest_info_prefix(self) -> &'static [u8] {
+        match self {
+            HashAlgorithm::Md5Sha1 => &[],
+            HashAlgorithm::Sha1 => &[
+                0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+            ],
+            HashAlgorithm::Sha224 => &[
+                0x30, 0x2d, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x04,
+                0x05, 0x00, 0x04, 0x1c,
+            ],
+            HashAlgorithm::Sha256 => &[
+                0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01,
+                0x05, 0x00, 0x04, 0x20,
+            ],
+            HashAlgorithm::Sha384 => &[
+                0x30, 0x41, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02,
+                0x05, 0x00, 0x04, 0x30,
+            ],
+            HashAlgorithm::Sha512 => &[
+                0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03,
+                0x05, 0x00, 0x04, 0x40,
+            ],
+        }
+    }
+}
+
+/// RSA public key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RsaPublicKey {
+    n: BigUint,
+    e: BigUint,
+}
+
+impl RsaPublicKey {
+    /// Create with the modulus and the public exponent, e.g. of a
+    /// certificate.
+    pub fn new(n: BigUint, e: BigUint) -> Result<RsaPublicKey, RsaError> {
+        if n.is_even() || n.bits() < MIN_MODULUS_BITS || n.bits() > MAX_MODULUS_BITS {
+            return Err(RsaError::InvalidKey);
+        }
+        if e.is_even() || e < BigUint::from(3) || e >= n {
+            return Err(RsaError::InvalidKey);
+        }
+        Ok(RsaPublicKey { n, e })
+    }
+
+    /// Get the modulus.
+    pub fn n(&self) -> &BigUint {
+        &self.n
+    }
+
+    /// Get the public exponent.
+    pub fn e(&self) -> &BigUint {
+        &self.e
+    }
+
+    /// Get the length of the modulus in bytes, which ciphertexts and
+    /// signatures are of.
+    pub fn size(&self) -> usize {
+        self.n.bits().div_ceil(8)
+    }
+
+    /// Encrypt the message with PKCS #1 v1.5 padding (RSAES-PKCS1-v1_5), e.g.
+    /// the premaster secret.
+    pub fn encrypt_pkcs1v15<R: Rng + ?Sized>(&self, rng: &mut R, message: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let k = self.size();
+        if message.len() + 3 + MIN_PADDING_LEN > k {
+            return Err(RsaError::MessageTooLong(message.len()));
+        }
+
+        // EM = 0x00 || 0x02 || PS || 0x00 || M, where PS is nonzero
+        let mut em = vec![0; k];
+        em[1] = 2;
+        let padding_end = k - message.len() - 1;
+        let padding = &mut em[2..padding_end];
+        rng.fill_bytes(padding);
+        for byte in padding.iter_mut() {
+            while *byte == 0 {
+                let mut replacement = [0];
+                rng.fill_bytes(&mut replacement);
+                *byte = replacement[0];
+            }
+        }
+        em[padding_end + 1..].copy_from_slice(message);
+        Ok(self.encrypt_raw(&BigUint::from_bytes_be(&em)))
+    }
+
+    /// Encrypt the message with OAEP padding (RSAES-OAEP) over the hash
+    /// function `D`, which MGF1 also uses.
+    pub fn encrypt_oaep<D: Digest, R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        message: &[u8],
+        label: &[u8],
+    ) -> Result<Vec<u8>, RsaError> {
+        let k = self.size();
+        let h_len = D::OUTPUT_SIZE;
+        if message.len() + 2 * h_len + 2 > k {
+            return Err(RsaError::MessageTooLong(message.len()));
+        }
+
+        // EM = 0x00 || maskedSeed || maskedDB, DB = lHash || PS || 0x01 || M
+        let mut em = vec![0; k];
+        let (seed, db) = em[1..].split_at_mut(h_len);
+        db[..h_len].copy_from_slice(&D::digest(label));
+        let separator = db.len() - message.len() - 1;
+        db[separator] = 1;
+        db[separator + 1..].copy_from_slice(message);
+        rng.fill_bytes(seed);
+        mgf1_xor::<D>(seed, db);
+        mgf1_xor::<D>(db, seed);
+        Ok(self.encrypt_raw(&BigUint::from_bytes_be(&em)))
+    }
+
+    /// Verify the PKCS #1 v1.5 signature (RSASSA-PKCS1-v1_5) of the hash.
+    ///
+    /// The expected encoding is built and compared as a whole instead of
+    /// parsing the signed `DigestInfo`, which rules out the forgeries of
+    /// lenient parsers.
+    pub fn verify_pkcs1v15(&self, hash: HashAlgorithm, hashed: &[u8], signature: &[u8]) -> Result<(), RsaError> {
+        let expected = pkcs1v15_signature_encoding(hash, hashed, self.size())?;
+        let s = self.to_integer(signature, RsaError::InvalidSignature)?;
+        let em = self.encrypt_raw(&s);
+        if !ct::eq(&em, &expected) {
+            return Err(RsaError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// Verify the PSS signature (RSASSA-PSS) of the hash by the hash function
+    /// `D` with the salt of the length, e.g. the hash length in TLS 1.3.
+    pub fn verify_pss<D: Digest>(&self, hashed: &[u8], signature: &[u8], salt_len: usize) -> Result<(), RsaError> {
+        let h_len = D::OUTPUT_SIZE;
+        if hashed.len() != h_len {
+            return Err(RsaError::InvalidDigestLength(hashed.len()));
+        }
+        let s = self.to_integer(signature, RsaError::InvalidSignature)?;
+        let m = &s.modpow(&self.e, &self.n);
+
+        // EM = maskedDB || H || 0xbc of emBits = modBits - 1
+        let em_bits = self.n.bits() - 1;
+        let em_len = em_bits.div_ceil(8);
+        let mut em = m.to_bytes_be_padded(em_len).ok_or(RsaError::InvalidSignature)?;
+        if em_len < h_len + salt_len + 2 || em[em_len - 1] != 0xbc {
+            return Err(RsaError::InvalidSignature);
+        }
+        let top_mask = 0xff >> (8 * em_len - em_bits);
+        if em[0] & !top_mask != 0 {
+            return Err(RsaError::InvalidSignature);
+        }
+        let (db, h) = em[..em_len - 1].split_at_mut(em_len - h_len - 1);
+        mgf1_xor::<D>(h, db);
+        db[0] &= top_mask;
+
+        // DB = PS || 0x01 || salt, where PS is zeros
+        let separator = db.len() - salt_len - 1;
+        if db[..separator].iter().any(|byte| { *byte != 0 }) || db[separator] != 1 {
+            return Err(RsaError::InvalidSignature);
+        }
+        let expected = pss_hash::<D>(hashed, &db[separator + 1..]);
+        if !ct::eq(&expected, h) {
+            return Err(RsaError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// RSAEP and RSAVP1: `m ^ e mod n` in the bytes of the modulus length.
+    fn encrypt_raw(&self, m: &BigUint) -> Vec<u8> {
+        m.modpow(&self.e, &self.n).to_bytes_be_padded(self.size()).unwrap()
+    }
+
+    /// Convert the ciphertext or the signature into the integer, which must
+    /// be of the modulus length and less than the modulus.
+    fn to_integer(&self, input: &[u8], out_of_range: RsaError) -> Result<BigUint, RsaError> {
+        if input.len() != self.size() {
+            return Err(RsaError::InvalidLength(input.len()));
+        }
+        let value = BigUint::from_bytes_be(input);
+        if value >= self.n {
+            return Err(out_of_range);
+        }
+        Ok(value)
+    }
+}
+
+/// RSA private key with the CRT parameters.
+#[derive(Clone)]
+pub struct RsaPrivateKey {
+    public_key: RsaPublicKey,
+    d: BigUint,
+    p: BigUint,
+    q: BigUint,
+    /// `d mod (p - 1)`
+    dp: BigUint,
+    /// `d mod (q - 1)`
+    dq: BigUint,
+    /// `q ^ -1 mod p`
+    qinv: BigUint,
+}
+
+impl RsaPrivateKey {
+    /// Create with the modulus, the exponents and the prime factors, and
+    /// calculate the CRT parameters.
+    pub fn from_components(n: BigUint, e: BigUint, d: BigUint, p: BigUint, q: BigUint) -> Result<RsaPrivateKey, RsaError> {
+        let public_key = RsaPublicKey::new(n, e)?;
+        let one = BigUint::one();
+        if p <= one || q <= one || p == q || &p * &q != public_key.n {
+            return Err(RsaError::InvalidKey);
+        }
+
+        let p1 = &p - &one;
+        let q1 = &q - &one;
+        let dp = &d % &p1;
+        let dq = &d % &q1;
+        // e * d = 1 mod lcm(p - 1, q - 1) iff it is for both of them
+        if !(&(&public_key.e * &dp) % &p1).is_one() || !(&(&public_key.e * &dq) % &q1).is_one() {
+            return Err(RsaError::InvalidKey);
+        }
+        let qinv = q.mod_inverse(&p).ok_or(RsaError::InvalidKey)?;
+        Ok(RsaPrivateKey { public_key, d, p, q, dp, dq, qinv })
+    }
+
+    /// Generate the key of the modulus size in bits with the public exponent
+    /// by the random primes of FIPS 186-4 B.3.3.
+    ///
+    /// The size must be even, e.g. 2048 or 3072, and the exponent odd and
+    /// greater than `2 ^ 16`, e.g. 65537.
+    pub fn generate<R: Rng + ?Sized>(bits: usize, exponent: u64, rng: &mut R) -> Result<RsaPrivateKey, RsaError> {
+        if !bits.is_multiple_of(2) || !(MIN_MODULUS_BITS..=MAX_MODULUS_BITS).contains(&bits) {
+            return Err(RsaError::InvalidKey);
+        }
+        if exponent.is_multiple_of(2) || exponent <= 1 << 16 {
+            return Err(RsaError::InvalidKey);
+        }
+
+        let e = BigUint::from(exponent);
+        let one = BigUint::one();
+        let prime_bits = bits / 2;
+        // d exists only if gcd(p - 1, e) = 1
+        let coprime = |candidate: &BigUint| { (candidate - &one).gcd(&e).is_one() };
+        let min_distance = &one << (prime_bits - 100);
+        let min_d = &one << prime_bits;
+        loop {
+            let p = bigint::generate_prime(prime_bits, rng, coprime);
+            let q = bigint::generate_prime(prime_bits, rng, coprime);
+            // close primes are found by Fermat's factorization
+            let distance = if p > q { &p - &q } else { &q - &p };
+            if distance <= min_distance {
+                continue;
+            }
+
+            // d = e ^ -1 mod lcm(p - 1, q - 1), which mustn't be small
+            let p1 = &p - &one;
+            let q1 = &q - &one;
+            let lambda = &(&p1 * &q1) / &p1.gcd(&q1);
+            let d = match e.mod_inverse(&lambda) {
+                Some(d) if d > min_d => d,
+                _ => continue,
+            };
+            return RsaPrivateKey::from_components(&p * &q, e, d, p, q);
+        }
+    }
+
+    pub fn public_key(&self) -> &RsaPublicKey {
+        &self.public_key
+    }
+
+    /// Decrypt the ciphertext with PKCS #1 v1.5 padding (RSAES-PKCS1-v1_5).
+    ///
+    /// Invalid padding gives the synthetic message instead of an error, in
+    /// time independent of the padding. The caller of the RSA key exchange
+    /// still has to check the length and the version of the premaster
+    /// secret without branching, as RFC 5246 7.4.7.1 describes.
+    /// Only the ciphertext of the wrong length or out of range, which are
+    /// public, are errors.
+    pub fn decrypt_pkcs1v15<R: Rng + ?Sized>(&self, rng: &mut R, ciphertext: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let k = self.public_key.size();
+        let c = self.public_key.to_integer(ciphertext, RsaError::DecryptionFailed)?;
+        let em = self.decrypt_raw(rng, &c)?.to_bytes_be_padded(k).unwrap();
+        let (synthetic, synthetic_start) = self.synthetic_message(ciphertext);
+
+        // EM = 0x00 || 0x02 || PS || 0x00 || M, where PS is nonzero
+        let mut good = eq_mask(em[0] as usize, 0) & eq_mask(em[1] as usize, 2);
+        let mut looking = usize::MAX;
+        let mut separator = 0;
+        for (index, byte) in em.iter().enumerate().skip(2) {
+            let zero = eq_mask(*byte as usize, 0);
+            separator |= looking & zero & index;
+            looking &= !zero;
+        }
+        good &= !looking & !lt_mask(separator, 2 + MIN_PADDING_LEN);
+
+        let start = (good & (separator + 1)) | (!good & synthetic_start);
+        let byte_mask = good as u8;
+        let output: Vec<u8> = em.iter().zip(synthetic.iter())
+            .map(|(byte, synthetic_byte)| { (byte & byte_mask) | (synthetic_byte & !byte_mask) })
+            .collect();
+        Ok(output[start..].to_vec())
+    }
+
+    /// Decrypt the ciphertext with OAEP padding (RSAES-OAEP) over the hash
+    /// function `D`.
+    /// All the errors of the padding are the same and found without
+    /// branching (Manger's attack).
+    pub fn decrypt_oaep<D: Digest, R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        ciphertext: &[u8],
+        label: &[u8],
+    ) -> Result<Vec<u8>, RsaError> {
+        let k = self.public_key.size();
+        let h_len = D::OUTPUT_SIZE;
+        if k < 2 * h_len + 2 {
+            return Err(RsaError::DecryptionFailed);
+        }
+        let c = self.public_key.to_integer(ciphertext, RsaError::DecryptionFailed)?;
+        let mut em = self.decrypt_raw(rng, &c)?.to_bytes_be_padded(k).unwrap();
+
+        // EM = 0x00 || maskedSeed || maskedDB, DB = lHash || PS || 0x01 || M
+        let first_byte = em[0];
+        let (seed, db) = em[1..].split_at_mut(h_len);
+        mgf1_xor::<D>(db, seed);
+        mgf1_xor::<D>(seed, db);
+        let mut good = eq_mask(first_byte as usize, 0) & 0usize.wrapping_sub(ct::eq(&db[..h_len], &D::digest(label)) as usize);
+        let mut looking = usize::MAX;
+        let mut separator = 0;
+        let mut invalid = 0;
+        for (index, byte) in db.iter().enumerate().skip(h_len) {
+            let zero = eq_mask(*byte as usize, 0);
+            let one = eq_mask(*byte as usize, 1);
+            separator |= looking & one & index;
+            invalid |= looking & !zero & !one;
+            looking &= zero;
+        }
+        good &= !looking & !invalid;
+
+        if good == 0 {
+            return Err(RsaError::DecryptionFailed);
+        }
+        Ok(db[separator + 1..].to_vec())
+    }
+
+    /// Sign the hash with PKCS #1 v1.5 padding (RSASSA-PKCS1-v1_5).
+    pub fn sign_pkcs1v15<R: Rng + ?Sized>(&self, rng: &mut R, hash: HashAlgorithm, hashed: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let k = self.public_key.size();
+        let em = pkcs1v15_signature_encoding(hash, hashed, k)?;
+        let s = self.decrypt_raw(rng, &BigUint::from_bytes_be(&em))?;
+        Ok(s.to_bytes_be_padded(k).unwrap())
+    }
+
+    /// Sign the hash by the hash function `D` with PSS padding (RSASSA-PSS)
+    /// and a random salt of the length.
+    pub fn sign_pss<D: Digest, R: Rng + ?Sized>(&self, rng: &mut R, hashed: &[u8], salt_len: usize) -> Result<Vec<u8>, RsaError> {
+        let h_len = D::OUTPUT_SIZE;
+        if hashed.len() != h_len {
+            return Err(RsaError::InvalidDigestLength(hashed.len()));
+        }
+        let em_bits = self.public_key.n.bits() - 1;
+        let em_len = em_bits.div_ceil(8);
+        if em_len < h_len + salt_len + 2 {
+            return Err(RsaError::InvalidSaltLength(salt_len));
+        }
+
+        // EM = maskedDB || H || 0xbc, DB = PS || 0x01 || salt
+        let mut em = vec![0; em_len];
+        let (db, h) = em[..em_len - 1].split_at_mut(em_len - h_len - 1);
+        let separator = db.len() - salt_len - 1;
+        db[separator] = 1;
+        rng.fill_bytes(&mut db[separator + 1..]);
+        h.copy_from_slice(&pss_hash::<D>(hashed, &db[separator + 1..]));
+        mgf1_xor::<D>(h, db);
+        db[0] &= 0xff >> (8 * em_len - em_bits);
+        em[em_len - 1] = 0xbc;
+
+        let s = self.decrypt_raw(rng, &BigUint::from_bytes_be(&em))?;
+        Ok(s.to_bytes_be_padded(self.public_key.size()).unwrap())
+    }
+
+    /// RSADP and RSASP1 by the CRT, blinded by a random `r` as
+    /// `(c * r ^ e) ^ d * r ^ -1 = c ^ d mod n`.
+    fn decrypt_raw<R: Rng + ?Sized>(&self, rng: &mut R, c: &BigUint) -> Result<BigUint, RsaError> {
+        let RsaPublicKey { n, e } = &self.public_key;
+        let (r, r_inv) = loop {
+            let r = BigUint::random_below(n, rng);
+            if let Some(r_inv) = r.mod_inverse(n) {
+                break (r, r_inv);
+            }
+        };
+        let blinded = &(c * &r.modpow(e, n)) % n;
+
+        // m = m2 + q * (qinv * (m1 - m2) mod p)
+        let m1 = blinded.modpow(&self.dp, &self.p);
+        let m2 = blinded.modpow(&self.dq, &self.q);
+        let difference = &(&m1 + &self.p) - &(&m2 % &self.p);
+        let h = &(&self.qinv * &difference) % &self.p;
+        let blinded_m = &m2 + &(&h * &self.q);
+        let m = &(&blinded_m * &r_inv) % n;
+
+        // a faulty half of the CRT would give away a factor of n
+        if &m.modpow(e, n) != c {
+            return Err(RsaError::FaultDetected);
+        }
+        Ok(m)
+    }
+
+    /// Get the synthetic message of the implicit rejection in the buffer of
+    /// the modulus length and the index where it starts.
+    ///
+    /// It is derived by HMAC from the ciphertext and the private exponent,
+    /// so that it is the same for the same ciphertext and unpredictable to
+    /// the attacker.
+    fn synthetic_message(&self, ciphertext: &[u8]) -> (Vec<u8>, usize) {
+        let k = self.public_key.size();
+        let d_hash = Sha256::digest(&self.d.to_bytes_be_padded(k).unwrap());
+        let kdk = Hmac::<Sha256>::mac(&d_hash, ciphertext);
+        let message = iprf(&kdk, b"message", k);
+        let candidates = iprf(&kdk, b"length", SYNTHETIC_LENGTH_CANDIDATES * 2);
+
+        // take the last candidate shorter than the longest valid message
+        let max_len = k - 2 - MIN_PADDING_LEN;
+        let len_mask = usize::MAX >> max_len.leading_zeros();
+        let mut len = 0;
+        for candidate in candidates.chunks_exact(2) {
+            let candidate = (((candidate[0] as usize) << 8) | candidate[1] as usize) & len_mask;
+            let mask = lt_mask(candidate, max_len);
+            len = (candidate & mask) | (len & !mask);
+        }
+        (message, k - len)
+    }
+}
+
+impl fmt::Debug for RsaPrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RsaPrivateKey")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Get `EM = 0x00 || 0x01 || PS || 0x00 || DigestInfo` of PKCS #1 v1.5
+/// signatures, where PS is 0xff bytes.
+fn pkcs1v15_signature_encoding(hash: HashAlgorithm, hashed: &[u8], k: usize) -> Result<Vec<u8>, RsaError> {
+    if hashed.len() != hash.output_size() {
+        return Err(RsaError::InvalidDigestLength(hashed.len()));
+    }
+    let prefix = hash.digest_info_prefix();
+    let t_len = prefix.len() + hashed.len();
+    if t_len + 3 + MIN_PADDING_LEN > k {
+        return Err(RsaError::MessageTooLong(t_len));
+    }
+    let mut em = vec![0xff; k];
+    em[0] = 0;
+    em[1] = 1;
+    em[k - t_len - 1] = 0;
+    em[k - t_len..k - hashed.len()].copy_from_slice(prefix);
+    em[k - hashed.len()..].copy_from_slice(hashed);
+    Ok(em)
+}
+
+/// Get `H = Hash(0x00 * 8 || mHash || salt)` of PSS.
+fn pss_hash<D: Digest>(hashed: &[u8], salt: &[u8]) -> Vec<u8> {
+    let mut digest = D::new();
+    digest.update(&[0; 8]);
+    digest.update(hashed);
+    digest.update(salt);
+    digest.finalize()
+}
+
+/// XOR the mask of MGF1 (RFC 8017 B.2.1) from the seed into the data.
+fn mgf1_xor<D: Digest>(seed: &[u8], data: &mut [u8]) {
+    for (counter, chunk) in data.chunks_mut(D::OUTPUT_SIZE).enumerate() {
+        let mut digest = D::new();
+        digest.update(seed);
+        digest.update(&(counter as u32).to_be_bytes());
+        for (byte, mask) in chunk.iter_mut().zip(digest.finalize()) {
+            *byte ^= mask;
+        }
+    }
+}
+
+/// Pseudorandom function of the implicit rejection, which expands the key
+/// with the label into the bytes by HMAC-SHA256 in counter mode.
+fn iprf(key: &[u8], label: &[u8], len: usize) -> Vec<u8> {
+    let bits = (len * 8) as u16;
+    let hmac = Hmac::<Sha256>::new(key);
+    let mut output = Vec::with_capacity(len + Sha256::OUTPUT_SIZE);
+    let mut counter = 0u16;
+    while output.len() < len {
+        let mut block_hmac = hmac.clone();
+        block_hmac.update(&counter.to_be_bytes());
+        block_hmac.update(label);
+        block_hmac.update(&bits.to_be_bytes());
+        output.extend_from_slice(&block_hmac.finalize());
+        counter += 1;
+    }
+    output.truncate(len);
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hash::{Sha1, Sha384};
+    use crate::rng::ChaChaRng;
+    use crate::test_util::biguint;
+
+    /// 2048-bit key with `e = 65537` generated by pyca/cryptography.
+    mod rsa2048 {
+        pub const N: &str = concat!(
+            "c33f618461e5b65bdad0374b1da241d13c02106edc543880963033f5a7bfc5a6",
+            "77ad5c70c21717e3be5d7f3f6e14c8b5592e03a413fe6d6662d93a1a3343ff54",
+            "be6711d1a0a18e390d8d211cb3ce935bff002ff9c6e88eed09cd702b80c84f47",
+            "c8c3a916d8b9fc4cac2c4ff1feb9f9086b84111b35554db5c8f8d2dd32b1b740",
+            "681a47a796489c9463e3a8dd2ee791dbc4534589d8e881b38cb72632b47e8cc3",
+            "50ca1835fbd68bc41d9160217b137b1c5ead151d67b307573e3082e9cd1dc2bb",
+            "aca91d5cacafc68cbc22039a8b8155320e432768db8592f646b5a9c79624bae7",
+            "dbbe6ec4b1759f845cacb5edb69d53be01b89a8c83b6461127d4a237a79967b5",
+        );
+        pub const D: &str = concat!(
+            "2cf8914683a1fc51c5e1858ad8a581d166a7f70f01d2a83cfddad66cf3995936",
+            "1184adb10f13709e353fa5ca7f596c2d51002738889e66a659883d38c1ac1cc8",
+            "711e9e5d114984cfa14e6b5d081141a1c3e7502590ea09d991136f8da598fda1",
+            "c41d44fa0d2b31709b8b8d5268e07efab080f7cbb38a155f148e334b5d9ccf5f",
+            "305d6f40325c65c1b85041603fb098b3cd933851f3fcc57de88f417693c3aba8",
+            "153fc68628fe4fd1d4e26d13f7cc50d7c64d775b8cee38ecf9023ea5e5d9cf2b",
+            "aad7940cbd8d141b934d31ee4090428417c2a3c62ba4a7b9b0f03a8f496d9312",
+            "48d274dba5a3d08a92b02126951d09b6a6b8d72ace0e298b759a157de36ec701",
+        );
+        pub const P: &str = concat!(
+            "e098795d842dc1615492dff66db4c371f53d06fa46799482ae3e187a4b21be33",
+            "cb8ade9836f6a9e35167590098fb2d159086a30cca6c0bea08d6415a336b8d80",
+            "f6bf347097ee37b52183d2f47e0e87f8d619599071d452d00dcec85573daef51",
+            "065c3aeb6e8307d579d29126a735733754ff948bfe785f5090cb44fb223c1d75",
+        );
+        pub const Q: &str = concat!(
+            "de8c60c17871a6d81f831758ab3d25117f53bd8c7f4625f9d5dad2c5d6e5eeab",
+            "2ec1d5083252539abe6c3cc7e66a2c580dfb11cdca3d786e130ea854e55b3939",
+            "407bcfeb3911a537a326ff160b081360c3814e06d2c208b4204744c478aa037d",
+            "42c21cd0f5c21a59002553a8690ab90881e46c8f88bc68427624b370d76f9941",
+        );
+    }
+
+    fn test_key() -> RsaPrivateKey {
+        RsaPrivateKey::from_components(
+            biguint(rsa2048::N), BigUint::from(65537), biguint(rsa2048::D), biguint(rsa2048::P), biguint(rsa2048::Q),
+        ).unwrap()
+    }
+
+    fn test_rng() -> ChaChaRng {
+        ChaChaRng::from_seed(&[7; 32])
+    }
+
+    #[test]
+    fn test_public_key_validation() {
+        let n = biguint(rsa2048::N);
+        let e = BigUint::from(65537);
+        assert!(RsaPublicKey::new(n.clone(), e.clone()).is_ok());
+        assert_eq!(RsaPublicKey::new(&n + &BigUint::one(), e.clone()), Err(RsaError::InvalidKey));
+        assert_eq!(RsaPublicKey::new(&n >> 1100, e.clone()), Err(RsaError::InvalidKey));
+        assert_eq!(RsaPublicKey::new(n.clone(), BigUint::from(65536)), Err(RsaError::InvalidKey));
+        assert_eq!(RsaPublicKey::new(n.clone(), BigUint::one()), Err(RsaError::InvalidKey));
+        assert_eq!(RsaPublicKey::new(n.clone(), n.clone()), Err(RsaError::InvalidKey));
+        assert_eq!(RsaPublicKey::new(n, e).unwrap().size(), 256);
+    }
+
+    #[test]
+    fn test_private_key_validation() {
+        let key = test_key();
+        assert_eq!(key.public_key().n(), &biguint(rsa2048::N));
+        let components = || { (biguint(rsa2048::N), BigUint::from(65537), biguint(rsa2048::D), biguint(rsa2048::P), biguint(rsa2048::Q)) };
+
+        let (n, e, d, p, _) = components();
+        assert!(RsaPrivateKey::from_components(n, e, d, p.clone(), p).is_err());
+        let (n, e, d, p, q) = components();
+        assert!(RsaPrivateKey::from_components(n, e, d, &p + &BigUint::from(2), q).is_err());
+        let (n, e, d, p, q) = components();
+        assert!(RsaPrivateKey::from_components(n, e, &d + &BigUint::from(2), p, q).is_err());
+        let (n, _, d, p, q) = components();
+        assert!(RsaPrivateKey::from_components(n, BigUint::from(3), d, p, q).is_err());
+    }
+
+    #[test]
+    fn test_pkcs1v15_round_trip() {
+        let key = test_key();
+        let mut rng = test_rng();
+        for len in [0, 1, 48, 245] {
+            let message: Vec<u8> = (0..len).map(|i| { i as u8 }).collect();
+            let ciphertext = key.public_key().encrypt_pkcs1v15(&mut rng, &message).unwrap();
+            assert_eq!(ciphertext.len(), 256);
+            assert_eq!(key.decrypt_pkcs1v15(&mut rng, &ciphertext).unwrap(), message);
+        }
+        assert_eq!(key.public_key().encrypt_pkcs1v15(&mut rng, &[0; 246]), Err(RsaError::MessageTooLong(246)));
+    }
+
+    #[test]
+    fn test_pkcs1v15_implicit_rejection() {
+        let key = test_key();
+        let mut rng = test_rng();
+        let mut ciphertext = key.public_key().encrypt_pkcs1v15(&mut rng, &[3; 48]).unwrap();
+        ciphertext[100] ^= 1;
+
+        // the same synthetic message under different blinding values
+        let synthetic = key.decrypt_pkcs1v15(&mut rng, &ciphertext).unwrap();
+        assert_eq!(key.decrypt_pkcs1v15(&mut test_rng(), &ciphertext).unwrap(), synthetic);
+        assert!(synthetic.len() <= 245);
+        assert_ne!(synthetic, [3; 48]);
+
+        assert_eq!(key.decrypt_pkcs1v15(&mut rng, &ciphertext[1..]), Err(RsaError::InvalidLength(255)));
+        let n = key.public_key().n().to_bytes_be();
+        assert_eq!(key.decrypt_pkcs1v15(&mut rng, &n), Err(RsaError::DecryptionFailed));
+    }
+
+    #[test]
+    fn test_oaep_round_trip() {
+        let key = test_key();
+        let mut rng = test_rng();
+        for (len, label) in [(0, &b""[..]), (32, b"label"), (190, b"")] {
+            let message: Vec<u8> = (0..len).map(|i| { i as u8 }).collect();
+            let ciphertext = key.public_key().encrypt_oaep::<Sha256, _>(&mut rng, &message, label).unwrap();
+            assert_eq!(key.decrypt_oaep::<Sha256, _>(&mut rng, &ciphertext, label).unwrap(), message);
+            assert_eq!(key.decrypt_oaep::<Sha256, _>(&mut rng, &ciphertext, b"other"), Err(RsaError::DecryptionFailed));
+            assert_eq!(key.decrypt_oaep::<Sha1, _>(&mut rng, &ciphertext, label), Err(RsaError::DecryptionFailed));
+        }
+        assert_eq!(key.public_key().encrypt_oaep::<Sha256, _>(&mut rng, &[0; 191], b""), Err(RsaError::MessageTooLong(191)));
+    }
+
+    #[test]
+    fn test_pkcs1v15_sign_verify() {
+        let key = test_key();
+        let hashed = Sha384::digest(b"message");
+        let signature = key.sign_pkcs1v15(&mut test_rng(), HashAlgorithm::Sha384, &hashed).unwrap();
+        assert!(key.public_key().verify_pkcs1v15(HashAlgorithm::Sha384, &hashed, &signature).is_ok());
+        assert_eq!(
+            key.public_key().verify_pkcs1v15(HashAlgorithm::Sha384, &Sha384::digest(b"massage"), &signature),
+            Err(RsaError::InvalidSignature),
+        );
+        assert_eq!(
+            key.sign_pkcs1v15(&mut test_rng(), HashAlgorithm::Sha256, &hashed),
+            Err(RsaError::InvalidDigestLength(48)),
+        );
+
+        // MD5 and SHA-1 of TLS 1.0 without DigestInfo
+        let hashed = [0x5a; 36];
+        let signature = key.sign_pkcs1v15(&mut test_rng(), HashAlgorithm::Md5Sha1, &hashed).unwrap();
+        assert!(key.public_key().verify_pkcs1v15(HashAlgorithm::Md5Sha1, &hashed, &signature).is_ok());
+        let em = key.public_key().encrypt_raw(&BigUint::from_bytes_be(&signature));
+        assert_eq!(em[..2], [0, 1]);
+        assert_eq!(em[256 - 37..], [&[0][..], &hashed].concat()[..]);
+    }
+
+    #[test]
+    fn test_pss_sign_verify() {
+        let key = test_key();
+        let mut rng = test_rng();
+        let hashed = Sha256::digest(b"message");
+        for salt_len in [0, 20, 32, 222] {
+            let signature = key.sign_pss::<Sha256, _>(&mut rng, &hashed, salt_len).unwrap();
+            assert!(key.public_key().verify_pss::<Sha256>(&hashed, &signature, salt_len).is_ok());
+            assert_eq!(key.public_key().verify_pss::<Sha256>(&hashed, &signature, salt_len + 1), Err(RsaError::InvalidSignature));
+        }
+        assert_eq!(key.sign_pss::<Sha256, _>(&mut rng, &hashed, 223), Err(RsaError::InvalidSaltLength(223)));
+
+        // the random salt makes every signature different
+        let a = key.sign_pss::<Sha256, _>(&mut rng, &hashed, 32).unwrap();
+        let b = key.sign_pss::<Sha256, _>(&mut rng, &hashed, 32).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut rng = test_rng();
+        let key = RsaPrivateKey::generate(1024, 65537, &mut rng).unwrap();
+        let public_key = key.public_key();
+        assert_eq!(public_key.n().bits(), 1024);
+        assert_eq!(public_key.e(), &BigUint::from(65537));
+        assert_eq!((key.p.bits(), key.q.bits()), (512, 512));
+        assert!(key.d > BigUint::one() << 512);
+        assert!(key.p.is_probable_prime(20, &mut rng) && key.q.is_probable_prime(20, &mut rng));
+
+        let ciphertext = public_key.encrypt_pkcs1v15(&mut rng, b"premaster secret").unwrap();
+        assert_eq!(key.decrypt_pkcs1v15(&mut rng, &ciphertext).unwrap(), b"premaster secret");
+
+        // another key from the rest of the stream
+        let other = RsaPrivateKey::generate(1024, 3 << 16 | 1, &mut rng).unwrap();
+        assert_ne!(other.public_key().n(), public_key.n());
+    }
+
+    #[test]
+    fn test_generate_invalid_parameters() {
+        let mut rng = test_rng();
+        assert!(RsaPrivateKey::generate(1023, 65537, &mut rng).is_err());
+        assert!(RsaPrivateKey::generate(512, 65537, &mut rng).is_err());
+        assert!(RsaPrivateKey::generate(2048, 65536, &mut rng).is_err());
+        assert!(RsaPrivateKey::generate(2048, 3, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_fault_detection() {
+        let mut key = test_key();
+        key.dp = &key.dp + &BigUint::one();
+        let hashed = Sha256::digest(b"message");
+        assert_eq!(key.sign_pkcs1v15(&mut test_rng(), HashAlgorithm::Sha256, &hashed), Err(RsaError::FaultDetected));
+    }
+}
@@ -0,0 +1,253 @@
+//! Tests over the JSON vectors of Project Wycheproof.
+//!
+//! The vectors under `res/generated/rsa` are generated locally in the same
+//! layout and are not from Wycheproof; the upstream files are read by the
+//! ignored tests.
+
+use serde_json::Value;
+use super::*;
+use crate::hash::{Sha1, Sha384, Sha512};
+use crate::rng::ChaChaRng;
+use crate::test_util::{hex, upstream_vectors};
+
+fn field<'a>(value: &'a Value, name: &str) -> &'a str {
+    value[name].as_str().unwrap_or_else(|| { panic!("missing field {}", name) })
+}
+
+/// Get the test groups after checking the number of the tests.
+fn test_groups(json: &str) -> Vec<Value> {
+    let root: Value = serde_json::from_str(json).unwrap();
+    let groups = root["testGroups"].as_array().unwrap().clone();
+    let count: usize = groups.iter().map(|group| { group["tests"].as_array().unwrap().len() }).sum();
+    assert_eq!(count as u64, root["numberOfTests"].as_u64().unwrap());
+    groups
+}
+
+fn public_key(group: &Value) -> RsaPublicKey {
+    let key = &group["publicKey"];
+    RsaPublicKey::new(
+        BigUint::from_hex(field(key, "modulus")).unwrap(),
+        BigUint::from_hex(field(key, "publicExponent")).unwrap(),
+    ).unwrap()
+}
+
+fn private_key(group: &Value) -> RsaPrivateKey {
+    let key = &group["privateKey"];
+    let integer = |name| { BigUint::from_hex(field(key, name)).unwrap() };
+    let private_key = RsaPrivateKey::from_components(
+        integer("modulus"), integer("publicExponent"), integer("privateExponent"),
+        integer("prime1"), integer("prime2"),
+    ).unwrap();
+    assert_eq!(private_key.dp, integer("exponent1"));
+    assert_eq!(private_key.dq, integer("exponent2"));
+    assert_eq!(private_key.qinv, integer("coefficient"));
+    private_key
+}
+
+/// Get whether the test must pass, or `None` for the acceptable ones which
+/// may either pass or fail.
+fn expected(test: &Value) -> Option<bool> {
+    match field(test, "result") {
+        "valid" => Some(true),
+        "invalid" => Some(false),
+        "acceptable" => None,
+        result => panic!("unexpected result {}", result),
+    }
+}
+
+fn has_flag(test: &Value, flag: &str) -> bool {
+    test["flags"].as_array().is_some_and(|flags| { flags.iter().any(|value| { value == flag }) })
+}
+
+fn check_pkcs1v15_decryption(json: &str) {
+    let mut rng = ChaChaRng::from_seed(&[1; 32]);
+    for group in test_groups(json) {
+        let key = private_key(&group);
+        for test in group["tests"].as_array().unwrap() {
+            let id = &test["tcId"];
+            let output = key.decrypt_pkcs1v15(&mut rng, &hex(field(test, "ct")));
+            let message = hex(field(test, "msg"));
+            // invalid padding gives the synthetic message instead of an error
+            match expected(test) {
+                Some(true) => assert_eq!(output, Ok(message), "tcId {}", id),
+                _ if has_flag(test, "ImplicitRejection") => assert_eq!(output, Ok(message), "tcId {}", id),
+                Some(false) => assert_ne!(output, Ok(message), "tcId {}", id),
+                None => {},
+            }
+        }
+    }
+}
+
+#[test]
+fn test_pkcs1v15_decrypt_implicit_rejection() {
+    check_pkcs1v15_decryption(include_str!("../../../res/generated/rsa/pkcs1_2048_implicit_rejection.json"));
+}
+
+#[test]
+#[ignore = "needs the Wycheproof vectors of res/fetch-vectors.sh"]
+fn test_wycheproof_pkcs1v15_decrypt() {
+    check_pkcs1v15_decryption(&upstream_vectors("wycheproof/rsa_pkcs1_2048_test.json"));
+}
+
+fn check_oaep_decryption(json: &str) {
+    let mut rng = ChaChaRng::from_seed(&[2; 32]);
+    for group in test_groups(json) {
+        assert_eq!((field(&group, "sha"), field(&group, "mgfSha")), ("SHA-256", "SHA-256"));
+        let key = private_key(&group);
+        for test in group["tests"].as_array().unwrap() {
+            let id = &test["tcId"];
+            let output = key.decrypt_oaep::<Sha256, _>(&mut rng, &hex(field(test, "ct")), &hex(field(test, "label")));
+            match expected(test) {
+                Some(true) => assert_eq!(output, Ok(hex(field(test, "msg"))), "tcId {}", id),
+                Some(false) => assert!(output.is_err(), "tcId {}", id),
+                None => {},
+            }
+        }
+    }
+}
+
+#[test]
+fn test_oaep_decrypt() {
+    check_oaep_decryption(include_str!("../../../res/generated/rsa/oaep_2048_sha256_mgf1sha256.json"));
+}
+
+#[test]
+#[ignore = "needs the Wycheproof vectors of res/fetch-vectors.sh"]
+fn test_wycheproof_oaep_decrypt() {
+    check_oaep_decryption(&upstream_vectors("wycheproof/rsa_oaep_2048_sha256_mgf1sha256_test.json"));
+}
+
+/// Verify the signatures, and with the key of the generated vectors, check
+/// that signing gives the valid ones as the signatures are deterministic.
+fn check_pkcs1v15_signatures<D: Digest>(json: &str, hash: HashAlgorithm, signing_key: Option<&RsaPrivateKey>) {
+    let mut rng = ChaChaRng::from_seed(&[3; 32]);
+    for group in test_groups(json) {
+        let key = public_key(&group);
+        if let Some(signing_key) = signing_key {
+            assert_eq!(&key, signing_key.public_key());
+        }
+        for test in group["tests"].as_array().unwrap() {
+            let id = &test["tcId"];
+            let hashed = D::digest(&hex(field(test, "msg")));
+            let signature = hex(field(test, "sig"));
+            let result = key.verify_pkcs1v15(hash, &hashed, &signature);
+            match expected(test) {
+                Some(true) => {
+                    assert_eq!(result, Ok(()), "tcId {}", id);
+                    if let Some(signing_key) = signing_key {
+                        let output = signing_key.sign_pkcs1v15(&mut rng, hash, &hashed).unwrap();
+                        assert_eq!(output, signature, "tcId {}", id);
+                    }
+                },
+                Some(false) => assert!(result.is_err(), "tcId {}", id),
+                None => {},
+            }
+        }
+    }
+}
+
+/// The signing key of the generated signature vectors, which is the key of
+/// the generated decryption vectors.
+fn signing_key() -> RsaPrivateKey {
+    let json = include_str!("../../../res/generated/rsa/pkcs1_2048_implicit_rejection.json");
+    private_key(&test_groups(json)[0])
+}
+
+#[test]
+fn test_pkcs1v15_signature_sha1() {
+    let json = include_str!("../../../res/generated/rsa/signature_2048_sha1.json");
+    check_pkcs1v15_signatures::<Sha1>(json, HashAlgorithm::Sha1, Some(&signing_key()));
+}
+
+#[test]
+fn test_pkcs1v15_signature_sha256() {
+    let json = include_str!("../../../res/generated/rsa/signature_2048_sha256.json");
+    check_pkcs1v15_signatures::<Sha256>(json, HashAlgorithm::Sha256, Some(&signing_key()));
+}
+
+#[test]
+fn test_pkcs1v15_signature_sha512() {
+    let json = include_str!("../../../res/generated/rsa/signature_2048_sha512.json");
+    check_pkcs1v15_signatures::<Sha512>(json, HashAlgorithm::Sha512, Some(&signing_key()));
+}
+
+#[test]
+#[ignore = "needs the Wycheproof vectors of res/fetch-vectors.sh"]
+fn test_wycheproof_pkcs1v15_signature_sha256() {
+    let json = upstream_vectors("wycheproof/rsa_signature_2048_sha256_test.json");
+    check_pkcs1v15_signatures::<Sha256>(&json, HashAlgorithm::Sha256, None);
+}
+
+#[test]
+#[ignore = "needs the Wycheproof vectors of res/fetch-vectors.sh"]
+fn test_wycheproof_pkcs1v15_signature_sha384() {
+    let json = upstream_vectors("wycheproof/rsa_signature_2048_sha384_test.json");
+    check_pkcs1v15_signatures::<Sha384>(&json, HashAlgorithm::Sha384, None);
+}
+
+#[test]
+#[ignore = "needs the Wycheproof vectors of res/fetch-vectors.sh"]
+fn test_wycheproof_pkcs1v15_signature_sha512() {
+    let json = upstream_vectors("wycheproof/rsa_signature_2048_sha512_test.json");
+    check_pkcs1v15_signatures::<Sha512>(&json, HashAlgorithm::Sha512, None);
+}
+
+/// Verify the signatures, and with the key of the generated vectors, check
+/// that signing with the salt length gives the signatures which verify.
+fn check_pss_signatures<D: Digest>(json: &str, sha: &str, signing_key: Option<&RsaPrivateKey>) {
+    let mut rng = ChaChaRng::from_seed(&[4; 32]);
+    for group in test_groups(json) {
+        assert_eq!((field(&group, "sha"), field(&group, "mgf"), field(&group, "mgfSha")), (sha, "MGF1", sha));
+        let salt_len = group["sLen"].as_u64().unwrap() as usize;
+        let key = public_key(&group);
+        for test in group["tests"].as_array().unwrap() {
+            let id = &test["tcId"];
+            let hashed = D::digest(&hex(field(test, "msg")));
+            let result = key.verify_pss::<D>(&hashed, &hex(field(test, "sig")), salt_len);
+            match expected(test) {
+                Some(true) => {
+                    assert_eq!(result, Ok(()), "tcId {}", id);
+                    if let Some(signing_key) = signing_key {
+                        let signature = signing_key.sign_pss::<D, _>(&mut rng, &hashed, salt_len).unwrap();
+                        assert_eq!(key.verify_pss::<D>(&hashed, &signature, salt_len), Ok(()), "tcId {}", id);
+                    }
+                },
+                Some(false) => assert!(result.is_err(), "tcId {}", id),
+                None => {},
+            }
+        }
+    }
+}
+
+#[test]
+fn test_pss_signature_sha256() {
+    let json = include_str!("../../../res/generated/rsa/pss_2048_sha256_mgf1_32.json");
+    check_pss_signatures::<Sha256>(json, "SHA-256", Some(&signing_key()));
+}
+
+#[test]
+fn test_pss_signature_sha384() {
+    let json = include_str!("../../../res/generated/rsa/pss_2048_sha384_mgf1_48.json");
+    check_pss_signatures::<Sha384>(json, "SHA-384", Some(&signing_key()));
+}
+
+#[test]
+#[ignore = "needs the Wycheproof vectors of res/fetch-vectors.sh"]
+fn test_wycheproof_pss_signature_sha1() {
+    let json = upstream_vectors("wycheproof/rsa_pss_2048_sha1_mgf1_20_test.json");
+    check_pss_signatures::<Sha1>(&json, "SHA-1", None);
+}
+
+#[test]
+#[ignore = "needs the Wycheproof vectors of res/fetch-vectors.sh"]
+fn test_wycheproof_pss_signature_sha256_empty_salt() {
+    let json = upstream_vectors("wycheproof/rsa_pss_2048_sha256_mgf1_0_test.json");
+    check_pss_signatures::<Sha256>(&json, "SHA-256", None);
+}
+
+#[test]
+#[ignore = "needs the Wycheproof vectors of res/fetch-vectors.sh"]
+fn test_wycheproof_pss_signature_sha256() {
+    let json = upstream_vectors("wycheproof/rsa_pss_2048_sha256_mgf1_32_test.json");
+    check_pss_signatures::<Sha256>(&json, "SHA-256", None);
+}
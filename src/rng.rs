@@ -0,0 +1,124 @@
+//! Random number generators for keys, nonces and the blinding of private key
+//! operations.
+//!
+//! The library doesn't assume an operating system, so the functions which
+//! need randomness take any `Rng` given by the caller.
+
+use crate::cipher::chacha20::{self, ChaCha20};
+
+/// Source of random bytes.
+///
+/// The bytes must be unpredictable to an attacker, i.e. from a
+/// cryptographically secure generator.
+pub trait Rng {
+    /// Fill the buffer with random bytes.
+    fn fill_bytes(&mut self, dest: &mut [u8]);
+}
+
+impl<R: Rng + ?Sized> Rng for &mut R {
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        (**self).fill_bytes(dest)
+    }
+}
+
+/// Deterministic generator of the ChaCha20 key stream from a 32-byte seed.
+///
+/// It is as secure as the seed is, e.g. seeded from the operating system
+/// once. A fixed seed is useful to reproduce tests.
+pub struct ChaChaRng {
+    chacha20: ChaCha20,
+}
+
+impl ChaChaRng {
+    /// Create with the seed as the key and the zero nonce.
+    pub fn from_seed(seed: &[u8; chacha20::KEY_SIZE]) -> ChaChaRng {
+        ChaChaRng { chacha20: ChaCha20::new(seed, &[0; chacha20::NONCE_SIZE], 0) }
+    }
+
+    /// Create with the seed from the other generator.
+    pub fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> ChaChaRng {
+        let mut seed = [0; chacha20::KEY_SIZE];
+        rng.fill_bytes(&mut seed);
+        ChaChaRng::from_seed(&seed)
+    }
+}
+
+impl Rng for ChaChaRng {
+    /// # Panics
+    ///
+    /// Panics after 256 GiB of output, where the block counter overflows.
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        dest.fill(0);
+        self.chacha20.apply_key_stream(dest);
+    }
+}
+
+/// Generator of the operating system which reads `/dev/urandom`.
+#[cfg(all(feature = "std", unix))]
+pub struct OsRng {
+    file: std::fs::File,
+}
+
+#[cfg(all(feature = "std", unix))]
+impl OsRng {
+    /// Open `/dev/urandom`.
+    ///
+    /// Returns the I/O error if the device can't be opened, e.g. in a chroot
+    /// without `/dev`.
+    pub fn new() -> std::io::Result<OsRng> {
+        Ok(OsRng { file: std::fs::File::open("/dev/urandom")? })
+    }
+}
+
+#[cfg(all(feature = "std", unix))]
+impl Rng for OsRng {
+    /// # Panics
+    ///
+    /// Panics if reading the device fails, which shouldn't happen once it is
+    /// open.
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        use std::io::Read;
+        self.file.read_exact(dest).expect("failed to read /dev/urandom");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn test_chacha_rng_key_stream() {
+        // the key stream of the zero key and nonce, RFC 8439 A.1 test vector #1
+        let mut rng = ChaChaRng::from_seed(&[0; 32]);
+        let mut output = [0; 64];
+        rng.fill_bytes(&mut output[..10]);
+        rng.fill_bytes(&mut output[10..]);
+        assert_eq!(output[..], hex(concat!(
+            "76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7",
+            "da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586",
+        ))[..]);
+    }
+
+    #[test]
+    fn test_chacha_rng_overwrites() {
+        let mut a = ChaChaRng::from_seed(&[1; 32]);
+        let mut b = ChaChaRng::from_seed(&[1; 32]);
+        let mut output_a = [0; 40];
+        let mut output_b = [0xff; 40];
+        a.fill_bytes(&mut output_a);
+        b.fill_bytes(&mut output_b);
+        assert_eq!(output_a, output_b);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix))]
+    fn test_os_rng() {
+        let mut rng = OsRng::new().unwrap();
+        let mut a = [0; 32];
+        let mut b = [0; 32];
+        rng.fill_bytes(&mut a);
+        rng.fill_bytes(&mut b);
+        assert_ne!(a, b);
+    }
+}
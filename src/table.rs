@@ -0,0 +1,29 @@
+//! Const generators of the lookup tables shared by BASE16, BASE32 and BASE64.
+
+/// Value of decode tables for the bytes which are not symbols.
+pub(crate) const INVALID_VALUE: u8 = 0xFF;
+
+/// Generate the encode table, which maps values to symbols, from the string of
+/// symbols ordered by their values.
+pub(crate) const fn generate_encode_table_from<const N: usize>(symbols: &str) -> [u8; N] {
+    let symbols = symbols.as_bytes();
+    let mut encode_table: [u8; N] = [0; N];
+    let mut index = 0;
+    while index < N {
+        encode_table[index] = symbols[index];
+        index += 1;
+    }
+    encode_table
+}
+
+/// Generate the decode table, which maps symbols to values, from the encode
+/// table. The bytes which are not symbols are mapped to `INVALID_VALUE`.
+pub(crate) const fn generate_decode_table_from<const N: usize>(encode_table: &[u8; N]) -> [u8; 256] {
+    let mut decode_table = [INVALID_VALUE; 256];
+    let mut index = 0;
+    while index < N {
+        decode_table[encode_table[index] as usize] = index as u8;
+        index += 1;
+    }
+    decode_table
+}
@@ -0,0 +1,23 @@
+//! Helpers shared by the unit tests.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::base16;
+use crate::bigint::BigUint;
+
+/// Decode the hex of a test vector, which is trusted to be valid.
+pub(crate) fn hex(s: &str) -> Vec<u8> {
+    base16::decode(s).unwrap()
+}
+
+/// Parse the hex of a test vector as an integer.
+pub(crate) fn biguint(s: &str) -> BigUint {
+    BigUint::from_hex(s).unwrap()
+}
+
+/// Read the upstream test vectors which `res/fetch-vectors.sh` downloads
+/// under `res`.
+pub(crate) fn upstream_vectors(path: &str) -> String {
+    let path = format!("{}/res/{}", env!("CARGO_MANIFEST_DIR"), path);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| { panic!("{}: {}, run res/fetch-vectors.sh", path, e) })
+}